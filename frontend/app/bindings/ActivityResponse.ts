@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AuditEventResponse } from "./AuditEventResponse";
+
+export type ActivityResponse = { events: Array<AuditEventResponse>, total_pages: number, total_items: number, };
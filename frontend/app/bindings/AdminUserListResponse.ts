@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AdminUserResponse } from "./AdminUserResponse";
+
+export type AdminUserListResponse = { users: Array<AdminUserResponse>, total_pages: number, total_items: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AdminUserResponse = { pid: string, email: string, name: string, is_admin: boolean, is_verified: boolean, is_disabled: boolean, is_suspended: boolean, created_at: string, email_verified_at: string | null, disabled_at: string | null, deletion_requested_at: string | null, suspension_reason: string | null, suspended_until: string | null, 
+/**
+ * Assigned plan, `None` when the user is on the default plan
+ */
+plan: string | null, };
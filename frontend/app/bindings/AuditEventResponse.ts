@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AuditEventResponse = { event: string, detail: string | null, ip_address: string | null, user_agent: string | null, created_at: string, };
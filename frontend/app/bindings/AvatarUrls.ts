@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * URLs of the avatar thumbnails of a user.
+ */
+export type AvatarUrls = { small: string, medium: string, large: string, };
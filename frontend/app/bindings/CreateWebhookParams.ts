@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Registers an endpoint. A signing secret is generated unless one is given.
+ */
+export type CreateWebhookParams = { url: string, event_types: Array<string>, secret: string | null, };
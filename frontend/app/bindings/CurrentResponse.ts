@@ -1,3 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AvatarUrls } from "./AvatarUrls";
 
-export type CurrentResponse = { token: string, pid: string, name: string, email: string, };
+export type CurrentResponse = { token: string, pid: string, name: string, email: string, avatar: AvatarUrls | null, 
+/**
+ * email of the administrator impersonating this user, if any
+ */
+impersonated_by: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A message of the email outbox. The rendered bodies are left out.
+ */
+export type EmailMessageResponse = { pid: string, template: string, recipient: string, subject: string, status: string, attempts: number, last_error: string | null, provider_message_id: string | null, created_at: string, sent_at: string | null, 
+/**
+ * When the one-time links in the message were redacted. Such messages
+ * cannot be resent.
+ */
+redacted_at: string | null, };
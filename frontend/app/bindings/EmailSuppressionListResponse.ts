@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EmailSuppressionResponse } from "./EmailSuppressionResponse";
+
+export type EmailSuppressionListResponse = { suppressions: Array<EmailSuppressionResponse>, total_pages: number, total_items: number, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An address emails are no longer sent to.
+ */
+export type EmailSuppressionResponse = { pid: string, email: string, 
+/**
+ * `bounce` or `complaint`
+ */
+reason: string, 
+/**
+ * Format of the notification that suppressed the address
+ */
+source: string, detail: string | null, created_at: string, updated_at: string, };
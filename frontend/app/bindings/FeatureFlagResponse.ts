@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type FeatureFlagResponse = { key: string, enabled: boolean, percentage: number, user_pids: Array<string>, 
+/**
+ * Whether the rule is stored in the database rather than coming from
+ * the configuration defaults
+ */
+overridden: boolean, };
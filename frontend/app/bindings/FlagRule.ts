@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a flag is rolled out. A flag is on for a user when it is enabled
+ * globally, when the user is listed explicitly or when the user falls in the
+ * rollout percentage.
+ */
+export type FlagRule = { enabled: boolean, percentage: number, user_pids: Array<string>, };
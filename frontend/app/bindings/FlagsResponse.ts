@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Flags evaluated for the current user, by key
+ */
+export type FlagsResponse = { flags: { [key in string]?: boolean }, };
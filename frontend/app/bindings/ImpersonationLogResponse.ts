@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ImpersonationLogResponse = { event: string, impersonator_pid: string, user_pid: string, ip_address: string | null, created_at: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ImpersonationResponse = { token: string, pid: string, name: string, email: string, impersonator_pid: string, 
+/**
+ * unix timestamp at which the impersonation token expires
+ */
+expires_at: number, };
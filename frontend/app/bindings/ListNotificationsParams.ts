@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ListNotificationsParams = { page: number | null, page_size: number | null, 
+/**
+ * Only return notifications that have not been read yet
+ */
+unread: boolean | null, };
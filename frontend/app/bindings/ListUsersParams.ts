@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SortOrder } from "./SortOrder";
+import type { UserSortField } from "./UserSortField";
+
+/**
+ * Query parameters accepted by the admin user listing. Dates are RFC 3339
+ * strings.
+ */
+export type ListUsersParams = { page: number | null, page_size: number | null, verified: boolean | null, email: string | null, created_from: string | null, created_to: string | null, sort_by: UserSortField | null, order: SortOrder | null, };
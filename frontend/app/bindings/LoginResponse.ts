@@ -1,3 +1,4 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AvatarUrls } from "./AvatarUrls";
 
-export type LoginResponse = { token: string, pid: string, name: string, is_verified: boolean, email: string, };
+export type LoginResponse = { token: string, pid: string, name: string, is_verified: boolean, email: string, avatar: AvatarUrls | null, };
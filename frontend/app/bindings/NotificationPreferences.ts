@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Non-critical notifications a user can opt out of. Security notices such
+ * as password changes are always sent.
+ */
+export type NotificationPreferences = { new_login: boolean, email_verified: boolean, };
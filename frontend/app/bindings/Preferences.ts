@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NotificationPreferences } from "./NotificationPreferences";
+import type { Theme } from "./Theme";
+
+/**
+ * Settings stored per user in the `preferences` column. Every field has a
+ * default so missing keys, including a `NULL` column, read as defaults.
+ *
+ * Unknown keys are ignored when reading, so a document written by a newer
+ * version still reads after a rollback, but rejected in updates by
+ * [`Preferences::merge`].
+ */
+export type Preferences = { theme: Theme, locale: string | null, notifications: NotificationPreferences, };
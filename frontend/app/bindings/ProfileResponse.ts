@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AvatarUrls } from "./AvatarUrls";
+
+export type ProfileResponse = { pid: string, email: string, name: string, display_name: string | null, timezone: string | null, locale: string | null, bio: string | null, avatar: AvatarUrls | null, };
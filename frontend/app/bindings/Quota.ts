@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A limit a plan can put on a user.
+ */
+export type Quota = "requests_per_day" | "storage_bytes";
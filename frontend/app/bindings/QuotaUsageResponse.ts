@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Quota } from "./Quota";
+
+export type QuotaUsageResponse = { quota: Quota, used: number, 
+/**
+ * `null` when the plan does not limit this quota
+ */
+limit: number | null, resets_at: string | null, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters accepted by the outbox search. Every filter is optional.
+ */
+export type SearchEmailsParams = { page: number | null, page_size: number | null, 
+/**
+ * Part of the recipient address
+ */
+recipient: string | null, template: string | null, 
+/**
+ * `queued`, `sending`, `sent`, `failed` or `suppressed`
+ */
+status: string | null, };
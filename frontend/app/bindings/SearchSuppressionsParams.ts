@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Query parameters accepted by the suppression list. Every filter is
+ * optional.
+ */
+export type SearchSuppressionsParams = { page: number | null, page_size: number | null, 
+/**
+ * Part of the suppressed address
+ */
+email: string | null, 
+/**
+ * `bounce` or `complaint`
+ */
+reason: string | null, };
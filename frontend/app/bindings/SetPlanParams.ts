@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Plan to assign. `null` puts the user back on the default plan.
+ */
+export type SetPlanParams = { plan: string | null, };
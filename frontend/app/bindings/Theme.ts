@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Color scheme of the frontend.
+ */
+export type Theme = "light" | "dark" | "system";
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Changes to the rollout of a flag. Omitted fields keep their current
+ * value, or the configured default when the flag is not stored yet.
+ */
+export type UpdateFeatureFlagParams = { enabled: boolean | null, percentage: number | null, user_pids: Array<string> | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Profile fields that can be changed by the user. Omitted fields are left
+ * untouched and an empty string clears an optional field.
+ */
+export type UpdateProfileParams = { name: string | null, display_name: string | null, timezone: string | null, locale: string | null, bio: string | null, };
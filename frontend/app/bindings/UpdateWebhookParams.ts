@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Omitted fields are left untouched
+ */
+export type UpdateWebhookParams = { url: string | null, event_types: Array<string> | null, active: boolean | null, };
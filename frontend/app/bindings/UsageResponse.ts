@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QuotaUsageResponse } from "./QuotaUsageResponse";
+
+export type UsageResponse = { plan: string, plan_name: string, quotas: Array<QuotaUsageResponse>, };
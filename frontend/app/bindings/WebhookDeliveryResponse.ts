@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type WebhookDeliveryResponse = { pid: string, event: string, payload: unknown, status: string, attempts: number, response_status: number | null, response_body: string | null, error: string | null, created_at: string, delivered_at: string | null, 
+/**
+ * When a failed delivery is attempted again
+ */
+next_attempt_at: string | null, };
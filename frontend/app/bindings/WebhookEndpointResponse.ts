@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type WebhookEndpointResponse = { pid: string, url: string, event_types: Array<string>, active: boolean, created_at: string, 
+/**
+ * Only returned when the endpoint is created
+ */
+secret: string | null, };
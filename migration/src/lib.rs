@@ -2,6 +2,24 @@
 #![allow(clippy::wildcard_imports)]
 pub use sea_orm_migration::prelude::*;
 mod m20220101_000001_users;
+mod m20261018_100000_add_deletion_to_users;
+mod m20261018_110000_add_admin_to_users;
+mod m20261018_120000_impersonation_logs;
+mod m20261018_130000_add_profile_to_users;
+mod m20261018_140000_add_avatar_to_users;
+mod m20261018_150000_add_suspension_to_users;
+mod m20261018_160000_audit_events;
+mod m20261018_170000_add_security_notifications_to_users;
+mod m20261018_180000_add_preferences_to_users;
+mod m20261018_190000_feature_flags;
+mod m20261018_200000_notifications;
+mod m20261018_210000_webhooks;
+mod m20261018_220000_plans_and_usage;
+mod m20261018_230000_email_outbox;
+mod m20261018_240000_email_suppressions;
+mod m20261018_250000_data_exports;
+mod m20261018_260000_add_last_login_to_users;
+mod m20261018_270000_add_retries_to_webhook_deliveries;
 
 pub struct Migrator;
 
@@ -10,6 +28,24 @@ impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
         vec![
             Box::new(m20220101_000001_users::Migration),
+            Box::new(m20261018_100000_add_deletion_to_users::Migration),
+            Box::new(m20261018_110000_add_admin_to_users::Migration),
+            Box::new(m20261018_120000_impersonation_logs::Migration),
+            Box::new(m20261018_130000_add_profile_to_users::Migration),
+            Box::new(m20261018_140000_add_avatar_to_users::Migration),
+            Box::new(m20261018_150000_add_suspension_to_users::Migration),
+            Box::new(m20261018_160000_audit_events::Migration),
+            Box::new(m20261018_170000_add_security_notifications_to_users::Migration),
+            Box::new(m20261018_180000_add_preferences_to_users::Migration),
+            Box::new(m20261018_190000_feature_flags::Migration),
+            Box::new(m20261018_200000_notifications::Migration),
+            Box::new(m20261018_210000_webhooks::Migration),
+            Box::new(m20261018_220000_plans_and_usage::Migration),
+            Box::new(m20261018_230000_email_outbox::Migration),
+            Box::new(m20261018_240000_email_suppressions::Migration),
+            Box::new(m20261018_250000_data_exports::Migration),
+            Box::new(m20261018_260000_add_last_login_to_users::Migration),
+            Box::new(m20261018_270000_add_retries_to_webhook_deliveries::Migration),
             // inject-above (do not remove this comment)
         ]
     }
@@ -0,0 +1,30 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        // Users are referenced by pid rather than by foreign key so the log
+        // outlives the accounts it mentions, including purged ones.
+        create_table(
+            m,
+            "impersonation_logs",
+            &[
+                ("id", ColType::PkAuto),
+                ("event", ColType::String),
+                ("impersonator_pid", ColType::Uuid),
+                ("user_pid", ColType::Uuid),
+                ("ip_address", ColType::StringNull),
+            ],
+            &[],
+        )
+        .await
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "impersonation_logs").await
+    }
+}
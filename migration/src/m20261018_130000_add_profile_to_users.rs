@@ -0,0 +1,24 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        add_column(m, "users", "display_name", ColType::StringNull).await?;
+        add_column(m, "users", "timezone", ColType::StringNull).await?;
+        add_column(m, "users", "locale", ColType::StringNull).await?;
+        add_column(m, "users", "bio", ColType::TextNull).await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        remove_column(m, "users", "display_name").await?;
+        remove_column(m, "users", "timezone").await?;
+        remove_column(m, "users", "locale").await?;
+        remove_column(m, "users", "bio").await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        add_column(
+            m,
+            "users",
+            "suspended_at",
+            ColType::TimestampWithTimeZoneNull,
+        )
+        .await?;
+        add_column(m, "users", "suspension_reason", ColType::StringNull).await?;
+        add_column(
+            m,
+            "users",
+            "suspended_until",
+            ColType::TimestampWithTimeZoneNull,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        remove_column(m, "users", "suspended_at").await?;
+        remove_column(m, "users", "suspension_reason").await?;
+        remove_column(m, "users", "suspended_until").await?;
+        Ok(())
+    }
+}
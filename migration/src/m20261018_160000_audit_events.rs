@@ -0,0 +1,28 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "audit_events",
+            &[
+                ("id", ColType::PkAuto),
+                ("event", ColType::String),
+                ("detail", ColType::StringNull),
+                ("ip_address", ColType::StringNull),
+                ("user_agent", ColType::StringNull),
+            ],
+            &[("users", "")],
+        )
+        .await
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "audit_events").await
+    }
+}
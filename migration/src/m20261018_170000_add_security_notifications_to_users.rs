@@ -0,0 +1,32 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        add_column(
+            m,
+            "users",
+            "notify_new_login",
+            ColType::BooleanWithDefault(true),
+        )
+        .await?;
+        add_column(
+            m,
+            "users",
+            "notify_email_verified",
+            ColType::BooleanWithDefault(true),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        remove_column(m, "users", "notify_new_login").await?;
+        remove_column(m, "users", "notify_email_verified").await?;
+        Ok(())
+    }
+}
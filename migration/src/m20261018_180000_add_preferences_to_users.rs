@@ -0,0 +1,86 @@
+use loco_rs::schema::*;
+use sea_orm_migration::{prelude::*, sea_orm::DatabaseBackend};
+
+/// Moves the locale and the security notification toggles into a single
+/// `preferences` JSON document.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        add_column(m, "users", "preferences", ColType::JsonBinaryNull).await?;
+
+        let copy = match m.get_database_backend() {
+            DatabaseBackend::Postgres => {
+                "UPDATE users SET preferences = jsonb_build_object(
+                    'locale', locale,
+                    'notifications', jsonb_build_object(
+                        'new_login', notify_new_login,
+                        'email_verified', notify_email_verified
+                    )
+                )"
+            }
+            DatabaseBackend::Sqlite => {
+                "UPDATE users SET preferences = json_object(
+                    'locale', locale,
+                    'notifications', json_object(
+                        'new_login', json(CASE WHEN notify_new_login THEN 'true' ELSE 'false' END),
+                        'email_verified', json(CASE WHEN notify_email_verified THEN 'true' ELSE 'false' END)
+                    )
+                )"
+            }
+            DatabaseBackend::MySql => {
+                return Err(DbErr::Migration("MySQL is not supported".to_string()))
+            }
+        };
+        m.get_connection().execute_unprepared(copy).await?;
+
+        remove_column(m, "users", "locale").await?;
+        remove_column(m, "users", "notify_new_login").await?;
+        remove_column(m, "users", "notify_email_verified").await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        add_column(m, "users", "locale", ColType::StringNull).await?;
+        add_column(
+            m,
+            "users",
+            "notify_new_login",
+            ColType::BooleanWithDefault(true),
+        )
+        .await?;
+        add_column(
+            m,
+            "users",
+            "notify_email_verified",
+            ColType::BooleanWithDefault(true),
+        )
+        .await?;
+
+        let copy = match m.get_database_backend() {
+            DatabaseBackend::Postgres => {
+                "UPDATE users SET
+                    locale = preferences->>'locale',
+                    notify_new_login = COALESCE((preferences->'notifications'->>'new_login')::boolean, true),
+                    notify_email_verified = COALESCE((preferences->'notifications'->>'email_verified')::boolean, true)
+                WHERE preferences IS NOT NULL"
+            }
+            DatabaseBackend::Sqlite => {
+                "UPDATE users SET
+                    locale = json_extract(preferences, '$.locale'),
+                    notify_new_login = COALESCE(json_extract(preferences, '$.notifications.new_login'), 1),
+                    notify_email_verified = COALESCE(json_extract(preferences, '$.notifications.email_verified'), 1)
+                WHERE preferences IS NOT NULL"
+            }
+            DatabaseBackend::MySql => {
+                return Err(DbErr::Migration("MySQL is not supported".to_string()))
+            }
+        };
+        m.get_connection().execute_unprepared(copy).await?;
+
+        remove_column(m, "users", "preferences").await?;
+        Ok(())
+    }
+}
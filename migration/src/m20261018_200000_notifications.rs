@@ -0,0 +1,39 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "notifications",
+            &[
+                ("id", ColType::PkAuto),
+                ("pid", ColType::UuidUniq),
+                ("kind", ColType::String),
+                ("payload", ColType::JsonBinary),
+                ("read_at", ColType::TimestampWithTimeZoneNull),
+            ],
+            &[("users", "")],
+        )
+        .await?;
+
+        // The unread count and the unread filter hit this on every page load
+        m.create_index(
+            Index::create()
+                .name("idx-notifications-user_id-read_at")
+                .table(Alias::new("notifications"))
+                .col(Alias::new("user_id"))
+                .col(Alias::new("read_at"))
+                .to_owned(),
+        )
+        .await
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "notifications").await
+    }
+}
@@ -0,0 +1,49 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "webhook_endpoints",
+            &[
+                ("id", ColType::PkAuto),
+                ("pid", ColType::UuidUniq),
+                ("url", ColType::String),
+                ("secret", ColType::String),
+                ("event_types", ColType::JsonBinary),
+                ("active", ColType::BooleanWithDefault(true)),
+            ],
+            &[],
+        )
+        .await?;
+
+        create_table(
+            m,
+            "webhook_deliveries",
+            &[
+                ("id", ColType::PkAuto),
+                ("pid", ColType::UuidUniq),
+                ("event", ColType::String),
+                ("payload", ColType::JsonBinary),
+                ("status", ColType::String),
+                ("attempts", ColType::IntegerWithDefault(0)),
+                ("response_status", ColType::IntegerNull),
+                ("response_body", ColType::TextNull),
+                ("error", ColType::StringNull),
+                ("delivered_at", ColType::TimestampWithTimeZoneNull),
+            ],
+            &[("webhook_endpoints", "")],
+        )
+        .await
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "webhook_deliveries").await?;
+        drop_table(m, "webhook_endpoints").await
+    }
+}
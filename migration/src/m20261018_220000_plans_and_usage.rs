@@ -0,0 +1,43 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        add_column(m, "users", "plan", ColType::StringNull).await?;
+
+        create_table(
+            m,
+            "usage_counters",
+            &[
+                ("id", ColType::PkAuto),
+                ("metric", ColType::String),
+                ("bucket", ColType::String),
+                ("value", ColType::BigIntegerWithDefault(0)),
+            ],
+            &[("users", "")],
+        )
+        .await?;
+
+        // Counters are upserted on this key
+        m.create_index(
+            Index::create()
+                .name("idx-usage_counters-user_id-metric-bucket")
+                .table(Alias::new("usage_counters"))
+                .col(Alias::new("user_id"))
+                .col(Alias::new("metric"))
+                .col(Alias::new("bucket"))
+                .unique()
+                .to_owned(),
+        )
+        .await
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "usage_counters").await?;
+        remove_column(m, "users", "plan").await
+    }
+}
@@ -0,0 +1,64 @@
+use loco_rs::schema::{table_auto_tz, timestamptz_null};
+use sea_orm_migration::{prelude::*, schema::*};
+
+/// `create_table` pluralizes table names, so the outbox is created by hand to
+/// keep its singular name.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.create_table(
+            table_auto_tz(Alias::new("email_outbox"))
+                .col(pk_auto(Alias::new("id")))
+                .col(uuid_uniq(Alias::new("pid")))
+                .col(string(Alias::new("template")))
+                .col(string(Alias::new("recipient")))
+                .col(string(Alias::new("subject")))
+                .col(text(Alias::new("text")))
+                .col(text(Alias::new("html")))
+                .col(string(Alias::new("status")))
+                .col(integer(Alias::new("attempts")).default(0))
+                .col(text_null(Alias::new("last_error")))
+                .col(string_null(Alias::new("provider_message_id")))
+                .col(timestamptz_null(Alias::new("sent_at")))
+                .col(timestamptz_null(Alias::new("locked_until")))
+                .col(timestamptz_null(Alias::new("redacted_at")))
+                .col(integer(Alias::new("user_id")))
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-email_outbox-user_id-to-users")
+                        .from(Alias::new("email_outbox"), Alias::new("user_id"))
+                        .to(Alias::new("users"), Alias::new("id"))
+                        .on_delete(ForeignKeyAction::Cascade)
+                        .on_update(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        // Support searches by recipient, the dispatcher scans by status
+        m.create_index(
+            Index::create()
+                .name("idx-email_outbox-recipient")
+                .table(Alias::new("email_outbox"))
+                .col(Alias::new("recipient"))
+                .to_owned(),
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx-email_outbox-status")
+                .table(Alias::new("email_outbox"))
+                .col(Alias::new("status"))
+                .to_owned(),
+        )
+        .await
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.drop_table(Table::drop().table(Alias::new("email_outbox")).to_owned())
+            .await
+    }
+}
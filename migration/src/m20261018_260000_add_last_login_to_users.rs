@@ -0,0 +1,45 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        add_column(
+            m,
+            "users",
+            "last_login_at",
+            ColType::TimestampWithTimeZoneNull,
+        )
+        .await?;
+
+        let db = m.get_connection();
+        db.execute_unprepared(
+            "UPDATE users SET last_login_at = (
+               SELECT MAX(created_at) FROM audit_events
+               WHERE audit_events.user_id = users.id
+                 AND audit_events.event IN ('login', 'magic_link_used')
+             )",
+        )
+        .await?;
+        // Accounts older than the audit log may have logged in without a
+        // trace. They are taken as logged in when created, so the cleanup of
+        // unverified accounts never deletes an account in use.
+        db.execute_unprepared(
+            "UPDATE users SET last_login_at = created_at
+             WHERE last_login_at IS NULL
+               AND NOT EXISTS (
+                 SELECT 1 FROM audit_events WHERE audit_events.user_id = users.id
+               )",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        remove_column(m, "users", "last_login_at").await?;
+        Ok(())
+    }
+}
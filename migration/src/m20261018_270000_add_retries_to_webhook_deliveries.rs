@@ -0,0 +1,33 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+/// Retries are scheduled on the delivery instead of waiting inside the job.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        add_column(
+            m,
+            "webhook_deliveries",
+            "next_attempt_at",
+            ColType::TimestampWithTimeZoneNull,
+        )
+        .await?;
+        add_column(
+            m,
+            "webhook_deliveries",
+            "locked_until",
+            ColType::TimestampWithTimeZoneNull,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        remove_column(m, "webhook_deliveries", "next_attempt_at").await?;
+        remove_column(m, "webhook_deliveries", "locked_until").await?;
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ use loco_rs::{
     bgworker::{BackgroundWorker, Queue},
     boot::{create_app, BootResult, StartMode},
     config::Config,
-    controller::AppRoutes,
+    controller::{middleware, AppRoutes},
     db::truncate_table,
     environment::Environment,
     task::Tasks,
@@ -15,7 +15,22 @@ use std::{path::Path, sync::Arc};
 use tokio::sync::Mutex;
 
 #[allow(unused_imports)]
-use crate::{controllers, models::_entities::users, tasks, workers::downloader::DownloadWorker};
+use crate::{
+    common::{
+        events::EventHub, feature_flags::FeatureFlags, links::Links, plans::Plans,
+        settings::Settings, storage,
+    },
+    controllers,
+    mailers::memory::MemoryTransport,
+    middlewares::metering::Metering,
+    models::_entities::{
+        audit_events, data_exports, email_outbox, email_suppressions, feature_flags,
+        impersonation_logs, notifications, usage_counters, users, webhook_deliveries,
+        webhook_endpoints,
+    },
+    tasks,
+    workers::{downloader::DownloadWorker, mailer::MailWorker, webhook::WebhookWorker},
+};
 
 static TRUNCATE_GUARD: std::sync::OnceLock<Arc<Mutex<bool>>> = std::sync::OnceLock::new();
 
@@ -48,17 +63,70 @@ impl Hooks for App {
         Ok(vec![])
     }
 
-    fn routes(_ctx: &AppContext) -> AppRoutes {
-        AppRoutes::with_default_routes() // controller routes below
+    fn routes(ctx: &AppContext) -> AppRoutes {
+        let routes = AppRoutes::with_default_routes() // controller routes below
             .add_route(controllers::auth::routes())
+            .add_route(controllers::account::routes())
+            .add_route(controllers::admin::routes())
+            .add_route(controllers::profile::routes())
+            .add_route(controllers::preferences::routes())
+            .add_route(controllers::avatars::routes())
+            .add_route(controllers::flags::routes())
+            .add_route(controllers::flags::admin_routes())
+            .add_route(controllers::notifications::routes())
+            .add_route(controllers::usage::routes())
+            .add_route(controllers::events::routes())
+            .add_route(controllers::webhooks::routes())
+            .add_route(controllers::emails::routes())
+            .add_route(controllers::email_events::routes())
+            .add_route(controllers::email_suppressions::routes());
+
+        match ctx.environment {
+            Environment::Development | Environment::Test => {
+                routes.add_route(controllers::mailer_preview::routes())
+            }
+            _ => routes,
+        }
+    }
+    fn middlewares(ctx: &AppContext) -> Vec<Box<dyn middleware::MiddlewareLayer>> {
+        let mut middlewares = middleware::default_middleware_stack(ctx);
+        // Innermost, so the default stack also covers metered requests
+        middlewares.insert(0, Box::new(Metering::new(ctx)));
+        middlewares
     }
+
+    async fn after_context(ctx: AppContext) -> Result<AppContext> {
+        let settings = Settings::from_config(&ctx.config)?;
+        ctx.shared_store
+            .insert(FeatureFlags::new(settings.feature_flags));
+        ctx.shared_store.insert(Plans::new(settings.plans));
+        ctx.shared_store.insert(EventHub::default());
+        ctx.shared_store.insert(Links::from_config(&ctx.config)?);
+        ctx.shared_store.insert(MemoryTransport::default());
+        Ok(AppContext {
+            storage: storage::build(&settings.storage)?.into(),
+            ..ctx
+        })
+    }
+
     async fn connect_workers(ctx: &AppContext, queue: &Queue) -> Result<()> {
         queue.register(DownloadWorker::build(ctx)).await?;
+        queue.register(MailWorker::build(ctx)).await?;
+        queue.register(WebhookWorker::build(ctx)).await?;
         Ok(())
     }
 
-    #[allow(unused_variables)]
     fn register_tasks(tasks: &mut Tasks) {
+        tasks.register(tasks::cleanup_unverified_accounts::CleanupUnverifiedAccounts);
+        tasks.register(tasks::drain_email_outbox::DrainEmailOutbox);
+        tasks.register(tasks::expire_data_exports::ExpireDataExports);
+        tasks.register(tasks::preview_mailer::PreviewMailer);
+        tasks.register(tasks::prune_usage_counters::PruneUsageCounters);
+        tasks.register(tasks::purge_deleted_accounts::PurgeDeletedAccounts);
+        tasks.register(tasks::retry_webhook_deliveries::RetryWebhookDeliveries);
+        tasks.register(tasks::set_admin::SetAdmin);
+        tasks.register(tasks::suspend_user::SuspendUser);
+        tasks.register(tasks::unsuspend_user::UnsuspendUser);
         // tasks-inject (do not remove)
     }
 
@@ -76,7 +144,17 @@ impl Hooks for App {
 
         let db = &ctx.db;
 
+        truncate_table(db, audit_events::Entity).await?;
+        truncate_table(db, data_exports::Entity).await?;
+        truncate_table(db, email_outbox::Entity).await?;
+        truncate_table(db, email_suppressions::Entity).await?;
+        truncate_table(db, feature_flags::Entity).await?;
+        truncate_table(db, impersonation_logs::Entity).await?;
+        truncate_table(db, notifications::Entity).await?;
+        truncate_table(db, usage_counters::Entity).await?;
         truncate_table(db, users::Entity).await?;
+        truncate_table(db, webhook_deliveries::Entity).await?;
+        truncate_table(db, webhook_endpoints::Entity).await?;
         Ok(())
     }
 
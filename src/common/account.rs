@@ -0,0 +1,22 @@
+use loco_rs::prelude::*;
+
+use super::{avatar, data_export};
+use crate::models::{data_exports, users};
+
+/// Deletes an account along with the files it owns in storage. The files go
+/// first, so when the storage backend fails the account is kept and the
+/// deletion can be retried without leaving orphaned files behind.
+///
+/// # Errors
+///
+/// when the storage backend or the database fails
+pub async fn delete(ctx: &AppContext, user: users::Model) -> Result<()> {
+    for export in data_exports::Model::find_by_user(&ctx.db, &user).await? {
+        data_export::delete(ctx, export).await?;
+    }
+    if let Some(key) = &user.avatar_key {
+        avatar::delete(ctx, key).await?;
+    }
+    user.delete(&ctx.db).await?;
+    Ok(())
+}
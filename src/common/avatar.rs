@@ -0,0 +1,122 @@
+use std::{io::Cursor, path::PathBuf};
+
+use image::{imageops::FilterType, ImageFormat, ImageReader, Limits};
+use loco_rs::{app::AppContext, Error, Result};
+
+/// Largest accepted upload, kept below the default request body limit.
+pub const MAX_AVATAR_BYTES: usize = 1_000_000;
+
+/// Largest accepted width or height of the uploaded image, in pixels.
+pub const MAX_AVATAR_DIMENSION: u32 = 4096;
+
+/// Content types accepted for uploads.
+pub const ALLOWED_CONTENT_TYPES: &[&str] = &["image/png", "image/jpeg", "image/webp", "image/gif"];
+
+/// Square thumbnails generated for every upload, by name and edge length.
+pub const AVATAR_SIZES: &[(&str, u32)] = &[("small", 64), ("medium", 128), ("large", 256)];
+
+/// Content type of the stored thumbnails.
+pub const AVATAR_CONTENT_TYPE: &str = "image/png";
+
+/// A re-encoded thumbnail ready to be stored.
+pub struct Thumbnail {
+    pub size: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+/// Path of a thumbnail inside the storage backend.
+#[must_use]
+pub fn storage_path(key: &str, size: &str) -> PathBuf {
+    PathBuf::from("avatars")
+        .join(key)
+        .join(format!("{size}.png"))
+}
+
+/// Public URL a thumbnail is served from.
+#[must_use]
+pub fn url(key: &str, size: &str) -> String {
+    format!("/api/avatars/{key}/{size}")
+}
+
+/// Deletes every thumbnail of an avatar from storage. Deleting a thumbnail
+/// that does not exist succeeds, so a failed call can be retried.
+///
+/// # Errors
+///
+/// when the storage backend fails
+pub async fn delete(ctx: &AppContext, key: &str) -> Result<()> {
+    for (size, _) in AVATAR_SIZES {
+        ctx.storage.delete(&storage_path(key, size)).await?;
+    }
+    Ok(())
+}
+
+/// Checks the declared content type and size of an upload.
+///
+/// # Errors
+///
+/// when the upload is not an accepted image type or is too large
+pub fn validate_upload(content_type: Option<&str>, bytes: &[u8]) -> Result<()> {
+    if !content_type.is_some_and(|content_type| ALLOWED_CONTENT_TYPES.contains(&content_type)) {
+        return Err(Error::BadRequest(format!(
+            "avatar must be one of: {}",
+            ALLOWED_CONTENT_TYPES.join(", ")
+        )));
+    }
+    if bytes.is_empty() {
+        return Err(Error::BadRequest("avatar is empty".to_string()));
+    }
+    if bytes.len() > MAX_AVATAR_BYTES {
+        return Err(Error::BadRequest(format!(
+            "avatar must be at most {MAX_AVATAR_BYTES} bytes"
+        )));
+    }
+    Ok(())
+}
+
+/// Decodes the upload and re-encodes it into square PNG thumbnails for every
+/// entry of [`AVATAR_SIZES`]. The image is center-cropped to a square.
+///
+/// Decoding is CPU bound, call it from a blocking task.
+///
+/// # Errors
+///
+/// when the bytes are not a supported image or exceed
+/// [`MAX_AVATAR_DIMENSION`]
+pub fn thumbnails(bytes: &[u8]) -> Result<Vec<Thumbnail>> {
+    let invalid = |_| Error::BadRequest("avatar is not a valid image".to_string());
+
+    let mut reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(invalid)?;
+    if !matches!(
+        reader.format(),
+        Some(ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP | ImageFormat::Gif)
+    ) {
+        return Err(Error::BadRequest("avatar is not a valid image".to_string()));
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_AVATAR_DIMENSION);
+    limits.max_image_height = Some(MAX_AVATAR_DIMENSION);
+    reader.limits(limits);
+
+    let image = reader
+        .decode()
+        .map_err(|_| Error::BadRequest("avatar is not a valid image".to_string()))?;
+
+    AVATAR_SIZES
+        .iter()
+        .map(|(size, edge)| {
+            let mut encoded = Cursor::new(Vec::new());
+            image
+                .resize_to_fill(*edge, *edge, FilterType::Lanczos3)
+                .write_to(&mut encoded, ImageFormat::Png)
+                .map_err(|err| Error::Message(err.to_string()))?;
+            Ok(Thumbnail {
+                size,
+                bytes: encoded.into_inner(),
+            })
+        })
+        .collect()
+}
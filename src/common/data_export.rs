@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use chrono::Duration;
+use loco_rs::prelude::*;
+
+use super::links::Links;
+use crate::models::data_exports;
+
+/// How long the emailed download link stays valid.
+pub const EXPORT_LINK_EXPIRATION_HOURS: i64 = 24;
+
+/// Path of an export archive inside the storage backend.
+#[must_use]
+pub fn storage_path(export_id: &str) -> PathBuf {
+    PathBuf::from("exports").join(format!("{export_id}.zip"))
+}
+
+/// Builds a download link for an export that expires after
+/// [`EXPORT_LINK_EXPIRATION_HOURS`]. The link is signed so it can be used
+/// without being logged in.
+///
+/// # Errors
+///
+/// when the links cannot be built from the configuration
+pub fn signed_url(ctx: &AppContext, export_id: &str) -> Result<String> {
+    Ok(Links::from_context(ctx)?
+        .data_export(export_id)
+        .signed(Duration::hours(EXPORT_LINK_EXPIRATION_HOURS))
+        .to_string())
+}
+
+/// Deletes an export archive from storage and then its record. Deleting an
+/// archive that does not exist succeeds, so a failed call can be retried.
+///
+/// # Errors
+///
+/// when the storage backend or the database fails
+pub async fn delete(ctx: &AppContext, export: data_exports::Model) -> Result<()> {
+    ctx.storage
+        .delete(&storage_path(&export.pid.to_string()))
+        .await?;
+    export.delete(&ctx.db).await?;
+    Ok(())
+}
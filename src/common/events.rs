@@ -0,0 +1,178 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use loco_rs::app::AppContext;
+use tokio::sync::broadcast;
+
+pub const EVENT_EMAIL_VERIFIED: &str = "email_verified";
+pub const EVENT_PASSWORD_RESET: &str = "password_reset";
+pub const EVENT_MAGIC_LINK_USED: &str = "magic_link_used";
+
+/// Number of recent events kept for `Last-Event-ID` resume, across all users.
+pub const BUFFER_SIZE: usize = 1024;
+
+/// How long an event stays available for resume.
+pub const BUFFER_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Capacity of the live channel. Subscribers that fall further behind catch
+/// up from the buffer.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// An event published to a single user. Streamed as an SSE frame with `id`,
+/// `kind` as the event name and `data` as JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserEvent {
+    pub id: u64,
+    pub pid: String,
+    pub kind: String,
+    pub data: serde_json::Value,
+    pub published_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct HubState {
+    next_id: u64,
+    buffer: VecDeque<UserEvent>,
+}
+
+/// In-process fan-out of [`UserEvent`]s to the open event streams of each
+/// user.
+///
+/// Event ids increase monotonically and start from the boot time in
+/// microseconds, so ids from before a restart are always lower than new
+/// ones. Events are only delivered by the instance they were published on.
+///
+/// Registered in the shared store at boot, get it with
+/// [`EventHub::from_context`].
+#[derive(Debug, Clone)]
+pub struct EventHub {
+    state: Arc<Mutex<HubState>>,
+    sender: broadcast::Sender<UserEvent>,
+}
+
+impl Default for EventHub {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            state: Arc::new(Mutex::new(HubState {
+                next_id: u64::try_from(Utc::now().timestamp_micros()).unwrap_or_default(),
+                buffer: VecDeque::with_capacity(BUFFER_SIZE),
+            })),
+            sender,
+        }
+    }
+}
+
+/// Live events of one user, preceded by the buffered events that were missed.
+pub struct Subscription {
+    pid: String,
+    backlog: VecDeque<UserEvent>,
+    last_id: u64,
+    receiver: broadcast::Receiver<UserEvent>,
+    state: Arc<Mutex<HubState>>,
+}
+
+fn lock(state: &Mutex<HubState>) -> std::sync::MutexGuard<'_, HubState> {
+    state
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+fn buffered_after(state: &HubState, pid: &str, last_id: u64) -> VecDeque<UserEvent> {
+    state
+        .buffer
+        .iter()
+        .filter(|event| event.pid == pid && event.id > last_id)
+        .cloned()
+        .collect()
+}
+
+impl EventHub {
+    /// The hub of the application. Falls back to a fresh hub, which nothing
+    /// publishes to, when none was registered.
+    #[must_use]
+    pub fn from_context(ctx: &AppContext) -> Self {
+        ctx.shared_store.get::<Self>().unwrap_or_default()
+    }
+
+    /// Sends an event to every open stream of the user and keeps it for
+    /// resume. Returns the published event.
+    pub fn publish(&self, pid: &str, kind: &str, data: serde_json::Value) -> UserEvent {
+        let mut state = lock(&self.state);
+        state.next_id += 1;
+        let event = UserEvent {
+            id: state.next_id,
+            pid: pid.to_string(),
+            kind: kind.to_string(),
+            data,
+            published_at: Utc::now(),
+        };
+
+        let expired_before = event.published_at - BUFFER_TTL;
+        while state.buffer.len() >= BUFFER_SIZE
+            || state
+                .buffer
+                .front()
+                .is_some_and(|oldest| oldest.published_at < expired_before)
+        {
+            state.buffer.pop_front();
+        }
+        state.buffer.push_back(event.clone());
+
+        // Sending under the lock keeps the live order in line with the buffer.
+        // An error only means nobody is listening.
+        let _ = self.sender.send(event.clone());
+        event
+    }
+
+    /// Subscribes to the events of the user. With `last_event_id`, buffered
+    /// events published after it are replayed first.
+    #[must_use]
+    pub fn subscribe(&self, pid: &str, last_event_id: Option<u64>) -> Subscription {
+        let state = lock(&self.state);
+        let receiver = self.sender.subscribe();
+        let (backlog, last_id) = match last_event_id {
+            Some(last_id) => (buffered_after(&state, pid, last_id), last_id),
+            None => (VecDeque::new(), state.next_id),
+        };
+        drop(state);
+
+        Subscription {
+            pid: pid.to_string(),
+            backlog,
+            last_id,
+            receiver,
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl Subscription {
+    /// Waits for the next event of the user. Returns `None` once every handle
+    /// to the hub is dropped.
+    pub async fn next(&mut self) -> Option<UserEvent> {
+        loop {
+            if let Some(event) = self.backlog.pop_front() {
+                self.last_id = event.id;
+                return Some(event);
+            }
+
+            match self.receiver.recv().await {
+                Ok(event) if event.pid == self.pid && event.id > self.last_id => {
+                    self.last_id = event.id;
+                    return Some(event);
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::debug!(skipped, "event stream lagged, replaying from buffer");
+                    self.backlog = buffered_after(&lock(&self.state), &self.pid, self.last_id);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use loco_rs::{app::AppContext, Result};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::models::{feature_flags, users};
+
+/// How a flag is rolled out. A flag is on for a user when it is enabled
+/// globally, when the user is listed explicitly or when the user falls in the
+/// rollout percentage.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, Validate, ts_rs::TS)]
+#[serde(default)]
+#[ts(export)]
+pub struct FlagRule {
+    pub enabled: bool,
+    #[validate(range(max = 100, message = "Percentage must be between 0 and 100."))]
+    pub percentage: u8,
+    #[validate(custom(function = "validate_pids"))]
+    pub user_pids: Vec<String>,
+}
+
+fn validate_pids(pids: &[String]) -> std::result::Result<(), ValidationError> {
+    if pids.iter().all(|pid| Uuid::parse_str(pid).is_ok()) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("user_pids").with_message("User pids must be UUIDs.".into()))
+    }
+}
+
+impl FlagRule {
+    /// Evaluates the rule for a user, or for an anonymous visitor when `pid`
+    /// is `None`. Anonymous visitors only see globally enabled flags.
+    #[must_use]
+    pub fn evaluate(&self, key: &str, pid: Option<&str>) -> bool {
+        if self.enabled {
+            return true;
+        }
+        let Some(pid) = pid else {
+            return false;
+        };
+        self.user_pids.iter().any(|user_pid| user_pid == pid) || bucket(key, pid) < self.percentage
+    }
+}
+
+/// Places a user in one of 100 buckets. The flag key is part of the hash so
+/// every flag rolls out to a different subset of users, and raising the
+/// percentage only ever adds users.
+#[must_use]
+pub fn bucket(key: &str, pid: &str) -> u8 {
+    let digest = Sha256::digest(format!("{key}:{pid}").as_bytes());
+    let mut head = [0u8; 8];
+    head.copy_from_slice(&digest[..8]);
+    u8::try_from(u64::from_be_bytes(head) % 100).expect("bucket is below 100")
+}
+
+/// Evaluates feature flags. Rules stored in the `feature_flags` table take
+/// precedence over the defaults from the `settings.feature_flags` section of
+/// the configuration.
+///
+/// Built once at boot and kept in the shared store, get it with
+/// [`FeatureFlags::from_context`].
+#[derive(Debug, Clone, Default)]
+pub struct FeatureFlags {
+    defaults: Arc<BTreeMap<String, FlagRule>>,
+}
+
+impl FeatureFlags {
+    #[must_use]
+    pub fn new(defaults: BTreeMap<String, FlagRule>) -> Self {
+        Self {
+            defaults: Arc::new(defaults),
+        }
+    }
+
+    /// The evaluator of the application. Falls back to an evaluator without
+    /// defaults when none was registered.
+    #[must_use]
+    pub fn from_context(ctx: &AppContext) -> Self {
+        ctx.shared_store.get::<Self>().unwrap_or_default()
+    }
+
+    /// The configured default for a flag, if any.
+    #[must_use]
+    pub fn default_rule(&self, key: &str) -> Option<&FlagRule> {
+        self.defaults.get(key)
+    }
+
+    /// The effective rule of every known flag.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn rules(&self, db: &DatabaseConnection) -> Result<BTreeMap<String, FlagRule>> {
+        let mut rules = (*self.defaults).clone();
+        for flag in feature_flags::Model::list(db).await? {
+            rules.insert(flag.key.clone(), flag.rule());
+        }
+        Ok(rules)
+    }
+
+    /// The effective rule of a single flag.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn rule(&self, db: &DatabaseConnection, key: &str) -> Result<Option<FlagRule>> {
+        Ok(feature_flags::Model::find_by_key(db, key)
+            .await?
+            .map(|flag| flag.rule())
+            .or_else(|| self.defaults.get(key).cloned()))
+    }
+
+    /// Whether the flag is on for the user. Unknown flags are off.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn is_enabled(
+        &self,
+        db: &DatabaseConnection,
+        key: &str,
+        user: Option<&users::Model>,
+    ) -> Result<bool> {
+        let pid = user.map(|user| user.pid.to_string());
+        Ok(self
+            .rule(db, key)
+            .await?
+            .is_some_and(|rule| rule.evaluate(key, pid.as_deref())))
+    }
+
+    /// Evaluates every known flag for the user.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn evaluate(
+        &self,
+        db: &DatabaseConnection,
+        user: Option<&users::Model>,
+    ) -> Result<BTreeMap<String, bool>> {
+        let pid = user.map(|user| user.pid.to_string());
+        Ok(self
+            .rules(db)
+            .await?
+            .into_iter()
+            .map(|(key, rule)| {
+                let enabled = rule.evaluate(&key, pid.as_deref());
+                (key, enabled)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,192 @@
+use std::{fmt, sync::Arc};
+
+use chrono::{Duration, Utc};
+use loco_rs::{app::AppContext, config::Config, Result};
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use super::{settings::Settings, signing};
+
+/// Where links in emails point to, read from `settings.links`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LinksSettings {
+    /// Public base URL of the frontend, which also proxies the API. Falls
+    /// back to the URL of the server when unset.
+    pub frontend_url: Option<String>,
+}
+
+/// Builds the public links sent to users, all relative to one base URL.
+///
+/// Built once at boot and kept in the shared store, get it with
+/// [`Links::from_context`].
+#[derive(Debug, Clone)]
+pub struct Links {
+    base_url: Arc<str>,
+    secret: Arc<str>,
+}
+
+/// A link to a path below the base URL. Rendered with [`fmt::Display`].
+#[derive(Debug, Clone)]
+pub struct Link {
+    base_url: Arc<str>,
+    secret: Arc<str>,
+    path: String,
+    query: Vec<(String, String)>,
+}
+
+/// What a signature covers: the path and every query parameter before it.
+fn payload(path: &str, query: &[(String, String)]) -> String {
+    let query = form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(query)
+        .finish();
+    format!("{path}?{query}")
+}
+
+impl Links {
+    #[must_use]
+    pub fn new(base_url: &str, secret: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').into(),
+            secret: secret.into(),
+        }
+    }
+
+    /// Builds the links from the configuration. Links are signed with the
+    /// JWT secret.
+    ///
+    /// # Errors
+    ///
+    /// when the settings cannot be read or the JWT configuration is missing
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let base_url = Settings::from_config(config)?
+            .links
+            .frontend_url
+            .unwrap_or_else(|| config.server.full_url());
+        Ok(Self::new(&base_url, &config.get_jwt_config()?.secret))
+    }
+
+    /// The links of the application. Built from the configuration when none
+    /// was registered.
+    ///
+    /// # Errors
+    ///
+    /// when the links have to be built and the configuration is invalid
+    pub fn from_context(ctx: &AppContext) -> Result<Self> {
+        ctx.shared_store
+            .get::<Self>()
+            .map_or_else(|| Self::from_config(&ctx.config), Ok)
+    }
+
+    /// A link to any path, which must start with `/`.
+    #[must_use]
+    pub fn link(&self, path: &str) -> Link {
+        Link {
+            base_url: self.base_url.clone(),
+            secret: self.secret.clone(),
+            path: path.to_string(),
+            query: Vec::new(),
+        }
+    }
+
+    /// Verifies the email address of a newly registered account
+    #[must_use]
+    pub fn verify_email(&self, token: &str) -> Link {
+        self.link(&format!("/api/auth/verify/{token}"))
+    }
+
+    /// Page to choose a new password
+    #[must_use]
+    pub fn reset_password(&self, token: &str) -> Link {
+        self.forgot_password().query("token", token)
+    }
+
+    /// Page to request a password reset
+    #[must_use]
+    pub fn forgot_password(&self) -> Link {
+        self.link("/forgot")
+    }
+
+    /// Page that signs the user in with a magic link token
+    #[must_use]
+    pub fn magic_link(&self, token: &str) -> Link {
+        self.link("/magic-link").query("token", token)
+    }
+
+    /// Cancels a requested account deletion
+    #[must_use]
+    pub fn cancel_deletion(&self, token: &str) -> Link {
+        self.link(&format!("/api/account/cancel-deletion/{token}"))
+    }
+
+    /// Downloads a personal data export. Only valid when signed.
+    #[must_use]
+    pub fn data_export(&self, export_id: &str) -> Link {
+        self.link(&format!("/api/account/export/{export_id}"))
+    }
+
+    /// Checks the `expires` and `signature` query parameters added by
+    /// [`Link::signed`] against the path and the rest of the query of a
+    /// request.
+    #[must_use]
+    pub fn verify(&self, path: &str, query: Option<&str>) -> bool {
+        let mut signature = None;
+        let mut signed = Vec::new();
+        for (key, value) in
+            form_urlencoded::parse(query.unwrap_or_default().as_bytes()).into_owned()
+        {
+            if key == "signature" {
+                signature = Some(value);
+            } else {
+                signed.push((key, value));
+            }
+        }
+
+        let expires = signed
+            .iter()
+            .find(|(key, _)| key == "expires")
+            .and_then(|(_, value)| value.parse::<i64>().ok());
+        match (signature, expires) {
+            (Some(signature), Some(expires)) => {
+                expires > Utc::now().timestamp()
+                    && signing::verify(&self.secret, &payload(path, &signed), &signature)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Link {
+    /// Adds a query parameter
+    #[must_use]
+    pub fn query(mut self, key: &str, value: &str) -> Self {
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Adds an `expires` timestamp and a `signature` covering the path and
+    /// query, so the link can be trusted without a session until it expires.
+    /// Add every other query parameter before signing.
+    #[must_use]
+    pub fn signed(mut self, expires_in: Duration) -> Self {
+        let expires = (Utc::now() + expires_in).timestamp();
+        self.query
+            .push(("expires".to_string(), expires.to_string()));
+        let signature = signing::sign(&self.secret, &payload(&self.path, &self.query));
+        self.query.push(("signature".to_string(), signature));
+        self
+    }
+}
+
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.base_url, self.path)?;
+        if !self.query.is_empty() {
+            let query = form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(&self.query)
+                .finish();
+            write!(f, "?{query}")?;
+        }
+        Ok(())
+    }
+}
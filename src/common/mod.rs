@@ -0,0 +1,13 @@
+pub mod account;
+pub mod avatar;
+pub mod data_export;
+pub mod events;
+pub mod feature_flags;
+pub mod links;
+pub mod plans;
+pub mod search;
+pub mod settings;
+pub mod signing;
+pub mod storage;
+pub mod unverified_accounts;
+pub mod webhooks;
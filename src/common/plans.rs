@@ -0,0 +1,342 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Days, Utc};
+use loco_rs::{app::AppContext, controller::ErrorDetail, Error, Result};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{usage_counters, users};
+
+/// Usage counter bucket of the avatar thumbnails
+pub const STORAGE_AVATARS: &str = "avatars";
+
+/// Days the daily request counters are kept for, counting today
+pub const REQUEST_COUNTER_RETENTION_DAYS: u64 = 90;
+
+/// A limit a plan can put on a user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ts_rs::TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum Quota {
+    /// Authenticated API requests per UTC day
+    RequestsPerDay,
+    /// Bytes of user uploaded files
+    StorageBytes,
+}
+
+impl Quota {
+    pub const ALL: [Self; 2] = [Self::RequestsPerDay, Self::StorageBytes];
+
+    /// Name of the metric in the `usage_counters` table
+    #[must_use]
+    pub const fn metric(self) -> &'static str {
+        match self {
+            Self::RequestsPerDay => "requests",
+            Self::StorageBytes => "storage_bytes",
+        }
+    }
+}
+
+/// Limits of a plan. A missing limit means unlimited.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PlanSettings {
+    /// Display name, defaults to the key of the plan
+    pub name: Option<String>,
+    pub requests_per_day: Option<u64>,
+    pub storage_bytes: Option<u64>,
+}
+
+impl PlanSettings {
+    #[must_use]
+    pub const fn limit(&self, quota: Quota) -> Option<u64> {
+        match quota {
+            Quota::RequestsPerDay => self.requests_per_day,
+            Quota::StorageBytes => self.storage_bytes,
+        }
+    }
+}
+
+/// Plans on sale, read from `settings.plans`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PlansSettings {
+    /// Plan of users without an assigned plan, or with a plan that no longer
+    /// exists
+    pub default: String,
+    pub plans: BTreeMap<String, PlanSettings>,
+}
+
+impl Default for PlansSettings {
+    fn default() -> Self {
+        Self {
+            default: "free".to_string(),
+            plans: BTreeMap::new(),
+        }
+    }
+}
+
+/// Returned when an operation would take a user over a limit of their plan.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("quota exceeded")]
+pub struct QuotaExceeded {
+    pub quota: Quota,
+    pub limit: u64,
+    pub used: u64,
+    /// When the quota frees up again, for quotas that reset
+    pub resets_at: Option<DateTime<Utc>>,
+}
+
+impl From<QuotaExceeded> for Error {
+    fn from(err: QuotaExceeded) -> Self {
+        // Rates wait for the reset, amounts need a plan upgrade.
+        let status = match err.quota {
+            Quota::RequestsPerDay => StatusCode::TOO_MANY_REQUESTS,
+            Quota::StorageBytes => StatusCode::PAYMENT_REQUIRED,
+        };
+        Self::CustomError(
+            status,
+            ErrorDetail {
+                error: Some("quota_exceeded".to_string()),
+                description: Some(format!(
+                    "The {} quota of your plan is exhausted",
+                    err.quota.metric()
+                )),
+                errors: Some(serde_json::json!({
+                    "quota": err.quota,
+                    "limit": err.limit,
+                    "used": err.used,
+                    "resets_at": err.resets_at,
+                })),
+            },
+        )
+    }
+}
+
+/// Usage of one quota, as reported to the dashboard.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuotaUsage {
+    pub quota: Quota,
+    pub used: u64,
+    pub limit: Option<u64>,
+    pub resets_at: Option<DateTime<Utc>>,
+}
+
+/// Day bucket of a rate counter and the moment it ends.
+fn day_bucket(now: DateTime<Utc>) -> (String, DateTime<Utc>) {
+    let today = now.date_naive();
+    let tomorrow = today
+        .checked_add_days(Days::new(1))
+        .unwrap_or(today)
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc();
+    (today.format("%Y-%m-%d").to_string(), tomorrow)
+}
+
+fn to_u64(value: i64) -> u64 {
+    u64::try_from(value).unwrap_or_default()
+}
+
+fn to_i64(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+/// Resolves the plan of a user and enforces its quotas against the usage
+/// counters.
+///
+/// Built once at boot and kept in the shared store, get it with
+/// [`Plans::from_context`].
+#[derive(Debug, Clone, Default)]
+pub struct Plans {
+    settings: Arc<PlansSettings>,
+}
+
+impl Plans {
+    #[must_use]
+    pub fn new(settings: PlansSettings) -> Self {
+        Self {
+            settings: Arc::new(settings),
+        }
+    }
+
+    /// The plans of the application. Falls back to no plans, where nothing
+    /// is limited, when none were registered.
+    #[must_use]
+    pub fn from_context(ctx: &AppContext) -> Self {
+        ctx.shared_store.get::<Self>().unwrap_or_default()
+    }
+
+    /// Whether a plan with this key is configured
+    #[must_use]
+    pub fn exists(&self, key: &str) -> bool {
+        self.settings.plans.contains_key(key)
+    }
+
+    /// Key of the effective plan of the user
+    #[must_use]
+    pub fn plan_key<'a>(&'a self, user: &'a users::Model) -> &'a str {
+        user.plan
+            .as_deref()
+            .filter(|plan| self.exists(plan))
+            .unwrap_or(&self.settings.default)
+    }
+
+    /// Limits of the effective plan of the user. Unlimited when the default
+    /// plan is not configured either.
+    #[must_use]
+    pub fn plan(&self, user: &users::Model) -> PlanSettings {
+        self.settings
+            .plans
+            .get(self.plan_key(user))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Counts one request against the daily quota.
+    ///
+    /// # Errors
+    ///
+    /// [`QuotaExceeded`] when the daily quota is used up, or a DB query error
+    pub async fn consume_request(
+        &self,
+        db: &DatabaseConnection,
+        user: &users::Model,
+    ) -> Result<()> {
+        let limit = self.plan(user).requests_per_day;
+        let (bucket, resets_at) = day_bucket(Utc::now());
+        let metric = Quota::RequestsPerDay.metric();
+
+        let counted = usage_counters::Model::increment_within(
+            db,
+            user,
+            metric,
+            &bucket,
+            1,
+            limit.map(to_i64),
+        )
+        .await?;
+        match (counted, limit) {
+            (None, Some(limit)) => Err(QuotaExceeded {
+                quota: Quota::RequestsPerDay,
+                limit,
+                used: to_u64(usage_counters::Model::value(db, user, metric, &bucket).await?),
+                resets_at: Some(resets_at),
+            }
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Deletes the daily request counters older than
+    /// [`REQUEST_COUNTER_RETENTION_DAYS`]. Returns how many were deleted.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn prune_request_counters(
+        &self,
+        db: &DatabaseConnection,
+        now: DateTime<Utc>,
+    ) -> Result<u64> {
+        let oldest_kept = now
+            .checked_sub_days(Days::new(REQUEST_COUNTER_RETENTION_DAYS - 1))
+            .unwrap_or(now);
+        let (bucket, _) = day_bucket(oldest_kept);
+        Ok(usage_counters::Model::delete_buckets_before(
+            db,
+            Quota::RequestsPerDay.metric(),
+            &bucket,
+        )
+        .await?)
+    }
+
+    /// Checks that the storage of the user stays within the plan when the
+    /// files counted in `bucket` are replaced by `bytes` bytes.
+    ///
+    /// # Errors
+    ///
+    /// [`QuotaExceeded`] when the files would not fit, or a DB query error
+    pub async fn check_storage(
+        &self,
+        db: &DatabaseConnection,
+        user: &users::Model,
+        bucket: &str,
+        bytes: u64,
+    ) -> Result<()> {
+        let Some(limit) = self.plan(user).storage_bytes else {
+            return Ok(());
+        };
+        let metric = Quota::StorageBytes.metric();
+        let others = to_u64(usage_counters::Model::total(db, user, metric, Some(bucket)).await?);
+        if others.saturating_add(bytes) > limit {
+            return Err(QuotaExceeded {
+                quota: Quota::StorageBytes,
+                limit,
+                used: to_u64(usage_counters::Model::total(db, user, metric, None).await?),
+                resets_at: None,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Records the bytes now stored for the user in `bucket`.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn record_storage(
+        &self,
+        db: &DatabaseConnection,
+        user: &users::Model,
+        bucket: &str,
+        bytes: u64,
+    ) -> Result<()> {
+        usage_counters::Model::set(
+            db,
+            user,
+            Quota::StorageBytes.metric(),
+            bucket,
+            to_i64(bytes),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Current usage of every quota of the user.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn usage(
+        &self,
+        db: &DatabaseConnection,
+        user: &users::Model,
+    ) -> Result<Vec<QuotaUsage>> {
+        let plan = self.plan(user);
+        let (bucket, resets_at) = day_bucket(Utc::now());
+
+        let mut usage = Vec::with_capacity(Quota::ALL.len());
+        for quota in Quota::ALL {
+            let (used, resets_at) = match quota {
+                Quota::RequestsPerDay => (
+                    to_u64(usage_counters::Model::value(db, user, quota.metric(), &bucket).await?),
+                    Some(resets_at),
+                ),
+                Quota::StorageBytes => (
+                    to_u64(usage_counters::Model::total(db, user, quota.metric(), None).await?),
+                    None,
+                ),
+            };
+            usage.push(QuotaUsage {
+                quota,
+                used,
+                limit: plan.limit(quota),
+                resets_at,
+            });
+        }
+        Ok(usage)
+    }
+}
@@ -0,0 +1,28 @@
+use sea_orm::{
+    sea_query::{Expr, Func, LikeExpr, SimpleExpr},
+    ColumnTrait,
+};
+
+const LIKE_ESCAPE: char = '\\';
+
+/// Escapes the `LIKE` wildcards in `value` so it only matches itself
+#[must_use]
+pub fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for char in value.chars() {
+        if matches!(char, '%' | '_' | LIKE_ESCAPE) {
+            escaped.push(LIKE_ESCAPE);
+        }
+        escaped.push(char);
+    }
+    escaped
+}
+
+/// Matches rows whose `column` contains `needle`, ignoring case. Wildcards in
+/// `needle` are matched literally.
+#[must_use]
+pub fn contains_ignore_case<C: ColumnTrait>(column: C, needle: &str) -> SimpleExpr {
+    let pattern = format!("%{}%", escape_like(&needle.to_lowercase()));
+    Expr::expr(Func::lower(Expr::col((column.entity_name(), column))))
+        .like(LikeExpr::new(pattern).escape(LIKE_ESCAPE))
+}
@@ -0,0 +1,73 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use loco_rs::{config::Config, Error, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    feature_flags::FlagRule, links::LinksSettings, plans::PlansSettings,
+    unverified_accounts::UnverifiedAccountsSettings, webhooks::WebhookSettings,
+};
+use crate::mailers::MailSettings;
+
+/// Application specific configuration, read from the `settings` section of
+/// the environment YAML file.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    pub storage: StorageSettings,
+    /// Default rollout of every feature flag, by key. Rules stored through
+    /// the admin API take precedence.
+    pub feature_flags: BTreeMap<String, FlagRule>,
+    pub webhooks: WebhookSettings,
+    /// Plans and their quotas. Users without a plan get `plans.default`.
+    pub plans: PlansSettings,
+    pub mail: MailSettings,
+    pub links: LinksSettings,
+    pub unverified_accounts: UnverifiedAccountsSettings,
+}
+
+/// Backend used for user uploaded files.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "driver", rename_all = "snake_case")]
+pub enum StorageSettings {
+    /// Files are written below `path` on the local disk.
+    Local { path: PathBuf },
+    /// Files are kept in memory and lost on restart.
+    Memory,
+    /// Files are written to an S3 compatible bucket. Requires the
+    /// `storage_s3` feature. Without explicit credentials the ambient AWS
+    /// environment is used.
+    S3 {
+        bucket: String,
+        region: String,
+        endpoint: Option<String>,
+        access_key_id: Option<String>,
+        secret_access_key: Option<String>,
+    },
+}
+
+impl Default for StorageSettings {
+    fn default() -> Self {
+        Self::Local {
+            path: PathBuf::from("storage"),
+        }
+    }
+}
+
+impl Settings {
+    /// Reads the settings from the loaded configuration, falling back to
+    /// defaults when the section is missing.
+    ///
+    /// # Errors
+    ///
+    /// when the `settings` section cannot be deserialized
+    pub fn from_config(config: &Config) -> Result<Self> {
+        config.settings.as_ref().map_or_else(
+            || Ok(Self::default()),
+            |value| {
+                serde_json::from_value(value.clone())
+                    .map_err(|err| Error::Message(format!("invalid settings: {err}")))
+            },
+        )
+    }
+}
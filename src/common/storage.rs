@@ -0,0 +1,68 @@
+use loco_rs::{
+    storage::{drivers, Storage},
+    Error, Result,
+};
+
+use super::settings::StorageSettings;
+
+/// Builds the storage backend described by the settings.
+///
+/// # Errors
+///
+/// when the driver cannot be created, or S3 is requested without the
+/// `storage_s3` feature
+pub fn build(settings: &StorageSettings) -> Result<Storage> {
+    let driver = match settings {
+        StorageSettings::Local { path } => {
+            std::fs::create_dir_all(path)?;
+            drivers::local::new_with_prefix(path)?
+        }
+        StorageSettings::Memory => drivers::mem::new(),
+        StorageSettings::S3 { .. } => s3(settings)?,
+    };
+    Ok(Storage::single(driver))
+}
+
+#[cfg(feature = "storage_s3")]
+fn s3(settings: &StorageSettings) -> Result<Box<dyn drivers::StoreDriver>> {
+    let StorageSettings::S3 {
+        bucket,
+        region,
+        endpoint,
+        access_key_id,
+        secret_access_key,
+    } = settings
+    else {
+        unreachable!("s3 called with a non s3 storage configuration");
+    };
+
+    let credentials = match (access_key_id, secret_access_key) {
+        (Some(key_id), Some(secret_key)) => Some(drivers::aws::Credential {
+            key_id: key_id.clone(),
+            secret_key: secret_key.clone(),
+            token: None,
+        }),
+        _ => None,
+    };
+
+    let driver = match (endpoint, credentials) {
+        (Some(endpoint), Some(credentials)) => {
+            drivers::aws::with_credentials_and_endpoint(bucket, region, endpoint, credentials)?
+        }
+        (None, Some(credentials)) => drivers::aws::with_credentials(bucket, region, credentials)?,
+        (Some(_), None) => {
+            return Err(Error::Message(
+                "a custom s3 endpoint requires explicit credentials".to_string(),
+            ))
+        }
+        (None, None) => drivers::aws::new(bucket, region)?,
+    };
+    Ok(driver)
+}
+
+#[cfg(not(feature = "storage_s3"))]
+fn s3(_settings: &StorageSettings) -> Result<Box<dyn drivers::StoreDriver>> {
+    Err(Error::Message(
+        "the s3 storage driver requires the `storage_s3` feature".to_string(),
+    ))
+}
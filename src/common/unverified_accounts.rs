@@ -0,0 +1,116 @@
+//! Reminders and cleanup for accounts whose email address was never
+//! verified. Every step is derived from when the account was created and
+//! when the verification email was last sent, so the task can run at any
+//! interval without keeping state of its own.
+
+use chrono::{DateTime, Duration, FixedOffset};
+use loco_rs::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::models::users;
+
+/// When unverified accounts are reminded, warned and deleted, read from
+/// `settings.unverified_accounts`. Every delay is in days since the account
+/// was created.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UnverifiedAccountsSettings {
+    /// The verification email is sent again after this many days
+    pub remind_after_days: i64,
+    /// A final warning, with a fresh verification link, is sent after this
+    /// many days
+    pub warn_after_days: i64,
+    /// The account is deleted after this many days, and no sooner than
+    /// `delete_after_days - warn_after_days` days after the warning
+    pub delete_after_days: i64,
+}
+
+impl Default for UnverifiedAccountsSettings {
+    fn default() -> Self {
+        Self {
+            remind_after_days: 3,
+            warn_after_days: 23,
+            delete_after_days: 30,
+        }
+    }
+}
+
+/// What happens to an unverified account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Send the verification email again
+    Remind,
+    /// Send the final warning before deletion
+    Warn,
+    /// Delete the account
+    Delete,
+}
+
+impl Action {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Remind => "remind",
+            Self::Warn => "warn",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+impl UnverifiedAccountsSettings {
+    /// Checks that the steps come in order.
+    ///
+    /// # Errors
+    ///
+    /// When a delay is not positive or the steps are out of order
+    pub fn validate(&self) -> Result<()> {
+        if 0 < self.remind_after_days
+            && self.remind_after_days < self.warn_after_days
+            && self.warn_after_days < self.delete_after_days
+        {
+            Ok(())
+        } else {
+            Err(Error::string(
+                "unverified_accounts requires 0 < remind_after_days < warn_after_days < delete_after_days",
+            ))
+        }
+    }
+
+    /// Days between the final warning and the deletion
+    #[must_use]
+    pub const fn notice_days(&self) -> i64 {
+        self.delete_after_days - self.warn_after_days
+    }
+
+    /// The next step for an account at `now`, if any. Verified accounts are
+    /// left alone, and so are accounts that have logged in, since login does
+    /// not require a verified email.
+    #[must_use]
+    pub fn next_action(&self, user: &users::Model, now: DateTime<FixedOffset>) -> Option<Action> {
+        if user.email_verified_at.is_some() || user.last_login_at.is_some() {
+            return None;
+        }
+
+        let age = now - user.created_at;
+        let warned_at = user.created_at + Duration::days(self.warn_after_days);
+        let reminded_at = user.created_at + Duration::days(self.remind_after_days);
+        // Sent at or after the given time
+        let sent_since =
+            |since| matches!(user.email_verification_sent_at, Some(sent_at) if sent_at >= since);
+
+        if age >= Duration::days(self.delete_after_days)
+            && sent_since(warned_at)
+            && user
+                .email_verification_sent_at
+                .is_some_and(|sent_at| now - sent_at >= Duration::days(self.notice_days()))
+        {
+            Some(Action::Delete)
+        } else if age >= Duration::days(self.warn_after_days) && !sent_since(warned_at) {
+            Some(Action::Warn)
+        } else if age >= Duration::days(self.remind_after_days) && !sent_since(reminded_at) {
+            Some(Action::Remind)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::settings::Settings;
+use super::signing;
+use crate::{
+    models::{
+        users,
+        webhook_deliveries::{self, Attempt},
+        webhook_endpoints,
+    },
+    workers::webhook::{WebhookWorker, WebhookWorkerArgs},
+};
+
+pub const EVENT_USER_REGISTERED: &str = "user.registered";
+pub const EVENT_USER_VERIFIED: &str = "user.verified";
+pub const EVENT_USER_PASSWORD_RESET: &str = "user.password_reset";
+
+/// Event types endpoints can subscribe to
+pub const EVENT_TYPES: &[&str] = &[
+    EVENT_USER_REGISTERED,
+    EVENT_USER_VERIFIED,
+    EVENT_USER_PASSWORD_RESET,
+];
+
+pub const HEADER_EVENT: &str = "X-Webhook-Event";
+pub const HEADER_DELIVERY: &str = "X-Webhook-Delivery";
+pub const HEADER_SIGNATURE: &str = "X-Webhook-Signature";
+
+/// Deliveries loaded per round by [`retry_due`]
+const RETRY_BATCH: u64 = 100;
+
+/// Time a claimed delivery is given beyond the request timeout to record its
+/// attempt
+const CLAIM_MARGIN: Duration = Duration::from_secs(30);
+
+/// Delivery and retry behaviour, read from `settings.webhooks`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WebhookSettings {
+    /// Attempts before a delivery is marked as failed
+    pub max_attempts: u32,
+    /// Wait before the first retry. Doubles after every failed attempt.
+    /// Retries are made by the `retry_webhook_deliveries` task, so they also
+    /// wait for its next run.
+    pub retry_base_ms: u64,
+    /// Timeout of a single request
+    pub timeout_secs: u64,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            retry_base_ms: 1000,
+            timeout_secs: 10,
+        }
+    }
+}
+
+impl WebhookSettings {
+    /// Wait after the given failed attempt, counting from 1
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(self.retry_base_ms.saturating_mul(factor))
+    }
+
+    /// How long a worker keeps its claim on a delivery: long enough for one
+    /// request and recording it. A delivery still claimed after that is
+    /// taken to be abandoned and can be claimed again.
+    #[must_use]
+    pub fn claim_lease(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs) + CLAIM_MARGIN
+    }
+}
+
+/// Value of the signature header for a request body sent at `timestamp`.
+///
+/// The signed payload is `{timestamp}.{body}` so receivers can reject replays
+/// of old requests.
+#[must_use]
+pub fn signature_header(secret: &str, timestamp: i64, body: &str) -> String {
+    let signature = signing::sign(secret, &format!("{timestamp}.{body}"));
+    format!("t={timestamp},v1={signature}")
+}
+
+/// Checks a signature header produced by [`signature_header`]
+#[must_use]
+pub fn verify_signature(secret: &str, header: &str, body: &str) -> bool {
+    let mut timestamp = None;
+    let mut signature = None;
+    for part in header.split(',') {
+        match part.split_once('=') {
+            Some(("t", value)) => timestamp = Some(value),
+            Some(("v1", value)) => signature = Some(value),
+            _ => {}
+        }
+    }
+    match (timestamp, signature) {
+        (Some(timestamp), Some(signature)) => {
+            signing::verify(secret, &format!("{timestamp}.{body}"), signature)
+        }
+        _ => false,
+    }
+}
+
+/// The user fields shared with webhook receivers
+#[must_use]
+pub fn user_data(user: &users::Model) -> serde_json::Value {
+    serde_json::json!({
+        "user": {
+            "pid": user.pid,
+            "email": user.email,
+            "name": user.name,
+            "email_verified_at": user.email_verified_at,
+            "created_at": user.created_at,
+        }
+    })
+}
+
+/// Queues a delivery of the event to every endpoint subscribed to it.
+///
+/// # Errors
+///
+/// When the deliveries could not be stored or queued
+pub async fn dispatch(ctx: &AppContext, event: &str, data: serde_json::Value) -> Result<()> {
+    let endpoints = webhook_endpoints::Model::subscribed_to(&ctx.db, event).await?;
+    if endpoints.is_empty() {
+        return Ok(());
+    }
+
+    let payload = serde_json::json!({
+        "id": Uuid::new_v4(),
+        "event": event,
+        "created_at": Utc::now(),
+        "data": data,
+    });
+    for endpoint in endpoints {
+        let delivery =
+            webhook_deliveries::Model::create(&ctx.db, &endpoint, event, payload.clone()).await?;
+        enqueue(ctx, &delivery).await?;
+    }
+    Ok(())
+}
+
+/// Same as [`dispatch`] but only logs failures, so that a broken webhook
+/// setup never fails the request that triggered the event.
+pub async fn emit(ctx: &AppContext, event: &str, data: serde_json::Value) {
+    if let Err(err) = dispatch(ctx, event, data).await {
+        tracing::error!(event, error = err.to_string(), "could not dispatch webhook");
+    }
+}
+
+/// Hands a stored delivery to the webhook worker
+///
+/// # Errors
+///
+/// When the job could not be queued
+pub async fn enqueue(ctx: &AppContext, delivery: &webhook_deliveries::Model) -> Result<()> {
+    WebhookWorker::perform_later(
+        ctx,
+        WebhookWorkerArgs {
+            delivery_pid: delivery.pid.to_string(),
+        },
+    )
+    .await
+}
+
+/// Makes one attempt at a due delivery and records it. A failed attempt is
+/// scheduled again with exponential backoff until the attempts run out, then
+/// the delivery fails. Returns `None` when the delivery is not due or is
+/// being sent by another worker.
+///
+/// # Errors
+///
+/// When DB query error
+pub async fn deliver(ctx: &AppContext, pid: Uuid) -> Result<Option<webhook_deliveries::Model>> {
+    let settings = Settings::from_config(&ctx.config)?.webhooks;
+    let Some(delivery) =
+        webhook_deliveries::Model::claim(&ctx.db, pid, settings.claim_lease()).await?
+    else {
+        return Ok(None);
+    };
+    let endpoint = webhook_endpoints::Entity::find_by_id(delivery.webhook_endpoint_id)
+        .one(&ctx.db)
+        .await?
+        .ok_or_else(|| Error::NotFound)?;
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(settings.timeout_secs))
+        .build()
+        .map_err(|err| Error::Message(err.to_string()))?;
+    let attempt = send(&client, &endpoint, &delivery).await;
+
+    let number = u32::try_from(delivery.attempts).unwrap_or_default() + 1;
+    let retry_after = (number < settings.max_attempts.max(1)).then(|| settings.backoff(number));
+    let succeeded = attempt.succeeded();
+    let delivery = delivery
+        .into_active_model()
+        .record_attempt(&ctx.db, attempt, retry_after)
+        .await?;
+
+    if succeeded {
+        tracing::info!(delivery = pid.to_string(), "webhook delivered");
+    } else if delivery.status == webhook_deliveries::STATUS_FAILED {
+        tracing::warn!(
+            delivery = pid.to_string(),
+            attempts = delivery.attempts,
+            "webhook delivery failed"
+        );
+    } else {
+        tracing::info!(
+            delivery = pid.to_string(),
+            attempts = delivery.attempts,
+            "webhook attempt failed, retrying later"
+        );
+    }
+    Ok(Some(delivery))
+}
+
+/// Attempts every delivery whose retry is due, oldest first. Returns how many
+/// were attempted by this call.
+///
+/// # Errors
+///
+/// When DB query error
+pub async fn retry_due(ctx: &AppContext) -> Result<usize> {
+    let mut after = 0;
+    let mut attempted = 0;
+    loop {
+        let batch = webhook_deliveries::Model::find_due(&ctx.db, after, RETRY_BATCH).await?;
+        let Some(last) = batch.last() else {
+            return Ok(attempted);
+        };
+        after = last.id;
+        for delivery in batch {
+            if deliver(ctx, delivery.pid).await?.is_some() {
+                attempted += 1;
+            }
+        }
+    }
+}
+
+async fn send(
+    client: &reqwest::Client,
+    endpoint: &webhook_endpoints::Model,
+    delivery: &webhook_deliveries::Model,
+) -> Attempt {
+    let body = delivery.payload.to_string();
+    let signature = signature_header(&endpoint.secret, Utc::now().timestamp(), &body);
+    let result = client
+        .post(&endpoint.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(HEADER_EVENT, &delivery.event)
+        .header(HEADER_DELIVERY, delivery.pid.to_string())
+        .header(HEADER_SIGNATURE, signature)
+        .body(body)
+        .send()
+        .await;
+
+    match result {
+        Ok(response) => Attempt {
+            response_status: Some(response.status().as_u16()),
+            response_body: response.text().await.ok(),
+            error: None,
+        },
+        Err(err) => Attempt {
+            error: Some(err.to_string()),
+            ..Default::default()
+        },
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{
+    common::{data_export, links::Links},
+    extractors::{api_user::ApiUser, sensitive::SensitiveJWT},
+    mailers::auth::AuthMailer,
+    models::{_entities::users, audit_events},
+    views::account::ActivityResponse,
+    workers::downloader::{DownloadWorker, DownloadWorkerArgs},
+};
+use axum::{
+    debug_handler,
+    extract::Query,
+    http::{header, Uri},
+    response::IntoResponse,
+};
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct DeleteAccountParams {
+    pub password: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct ActivityParams {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+const MAX_ACTIVITY_PAGE_SIZE: u32 = 100;
+
+/// Requests deletion of the current user account. The password is required
+/// to confirm the request. The account is only marked as pending deletion and
+/// a cancellation link is emailed to the user; the account is purged by the
+/// `purge_deleted_accounts` task once the grace period elapses.
+#[debug_handler]
+async fn delete_account(
+    auth: SensitiveJWT,
+    State(ctx): State<AppContext>,
+    Json(params): Json<DeleteAccountParams>,
+) -> Result<Response> {
+    let user = auth.user;
+
+    if !user.verify_password(&params.password) {
+        return unauthorized("unauthorized!");
+    }
+
+    let user = user.into_active_model().request_deletion(&ctx.db).await?;
+
+    AuthMailer::send_account_deletion(&ctx, &user).await?;
+    tracing::info!(pid = user.pid.to_string(), "account deletion requested");
+
+    format::json(())
+}
+
+/// Cancels a pending account deletion using the token sent by email.
+#[debug_handler]
+async fn cancel_deletion(
+    State(ctx): State<AppContext>,
+    Path(token): Path<String>,
+) -> Result<Response> {
+    let Ok(user) = users::Model::find_by_deletion_token(&ctx.db, &token).await else {
+        return unauthorized("invalid token");
+    };
+
+    let user = user.into_active_model().cancel_deletion(&ctx.db).await?;
+    tracing::info!(pid = user.pid.to_string(), "account deletion cancelled");
+
+    format::json(())
+}
+
+/// Queues an export of the personal data of the current user. The download
+/// link is emailed once the archive is ready.
+#[debug_handler]
+async fn request_export(auth: SensitiveJWT, State(ctx): State<AppContext>) -> Result<Response> {
+    let user = auth.user;
+
+    DownloadWorker::perform_later(
+        &ctx,
+        DownloadWorkerArgs {
+            user_guid: user.pid.to_string(),
+        },
+    )
+    .await?;
+    tracing::info!(pid = user.pid.to_string(), "data export requested");
+
+    format::json(())
+}
+
+/// Downloads an export archive using the signed link sent by email.
+#[debug_handler]
+async fn download_export(
+    State(ctx): State<AppContext>,
+    Path(export_id): Path<String>,
+    uri: Uri,
+) -> Result<Response> {
+    if Uuid::parse_str(&export_id).is_err()
+        || !Links::from_context(&ctx)?.verify(uri.path(), uri.query())
+    {
+        return unauthorized("invalid or expired link");
+    }
+
+    let archive: Vec<u8> = ctx
+        .storage
+        .download(&data_export::storage_path(&export_id))
+        .await
+        .map_err(|_| Error::NotFound)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"data-export.zip\"",
+            ),
+        ],
+        archive,
+    )
+        .into_response())
+}
+
+/// Lists the authentication events of the current user, newest first
+#[debug_handler]
+async fn activity(
+    auth: ApiUser,
+    State(ctx): State<AppContext>,
+    Query(params): Query<ActivityParams>,
+) -> Result<Response> {
+    let user = auth.user;
+
+    let pagination = query::PaginationQuery {
+        page: params.page.unwrap_or(1).into(),
+        page_size: params
+            .page_size
+            .unwrap_or(25)
+            .clamp(1, MAX_ACTIVITY_PAGE_SIZE)
+            .into(),
+    };
+    let page = audit_events::Model::paginate_by_user(&ctx.db, &user, &pagination).await?;
+
+    format::json(ActivityResponse::new(&page))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/account")
+        .add("/", delete(delete_account))
+        .add("/cancel-deletion/{token}", get(cancel_deletion))
+        .add("/activity", get(activity))
+        .add("/export", post(request_export))
+        .add("/export/{export_id}", get(download_export))
+}
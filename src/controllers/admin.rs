@@ -0,0 +1,356 @@
+use crate::{
+    common::{account, plans::Plans, search},
+    extractors::{admin::Admin, client_info::ClientInfo},
+    mailers::auth::AuthMailer,
+    models::{
+        _entities::users,
+        impersonation_logs::{self, Impersonation},
+    },
+    views::admin::{
+        AdminUserListResponse, AdminUserResponse, ImpersonationLogResponse, ImpersonationResponse,
+    },
+};
+use axum::{debug_handler, extract::Query};
+use chrono::{DateTime, FixedOffset};
+use loco_rs::prelude::*;
+use sea_orm::{sea_query::Order, QueryFilter, QueryOrder, QueryTrait};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, ts_rs::TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum UserSortField {
+    #[default]
+    CreatedAt,
+    Email,
+    Name,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, ts_rs::TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Query parameters accepted by the admin user listing. Dates are RFC 3339
+/// strings.
+#[derive(Debug, Default, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct ListUsersParams {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    pub verified: Option<bool>,
+    pub email: Option<String>,
+    pub created_from: Option<String>,
+    pub created_to: Option<String>,
+    pub sort_by: Option<UserSortField>,
+    pub order: Option<SortOrder>,
+}
+
+/// Plan to assign. `null` puts the user back on the default plan.
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct SetPlanParams {
+    pub plan: Option<String>,
+}
+
+const MAX_PAGE_SIZE: u32 = 100;
+
+fn parse_date(value: &str) -> Result<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(value)
+        .map_err(|_| Error::BadRequest(format!("invalid date: {value}")))
+}
+
+async fn load_user(ctx: &AppContext, pid: &str) -> Result<users::Model> {
+    users::Model::find_by_pid(&ctx.db, pid)
+        .await
+        .map_err(|_| Error::NotFound)
+}
+
+/// Lists users with pagination, filtering and sorting
+#[debug_handler]
+async fn list(
+    _admin: Admin,
+    State(ctx): State<AppContext>,
+    Query(params): Query<ListUsersParams>,
+) -> Result<Response> {
+    let mut condition = query::condition();
+
+    match params.verified {
+        Some(true) => condition = condition.is_not_null(users::Column::EmailVerifiedAt),
+        Some(false) => condition = condition.is_null(users::Column::EmailVerifiedAt),
+        None => {}
+    }
+    if let Some(from) = params.created_from.as_deref() {
+        condition = condition.gte(users::Column::CreatedAt, parse_date(from)?);
+    }
+    if let Some(to) = params.created_to.as_deref() {
+        condition = condition.lte(users::Column::CreatedAt, parse_date(to)?);
+    }
+
+    let column = match params.sort_by.unwrap_or_default() {
+        UserSortField::CreatedAt => users::Column::CreatedAt,
+        UserSortField::Email => users::Column::Email,
+        UserSortField::Name => users::Column::Name,
+    };
+    let order = match params.order.unwrap_or_default() {
+        SortOrder::Asc => Order::Asc,
+        SortOrder::Desc => Order::Desc,
+    };
+
+    let pagination = query::PaginationQuery {
+        page: params.page.unwrap_or(1).into(),
+        page_size: params
+            .page_size
+            .unwrap_or(25)
+            .clamp(1, MAX_PAGE_SIZE)
+            .into(),
+    };
+
+    let page = query::paginate(
+        &ctx.db,
+        users::Entity::find()
+            .apply_if(
+                params.email.as_deref().filter(|email| !email.is_empty()),
+                |query, email| {
+                    query.filter(search::contains_ignore_case(users::Column::Email, email))
+                },
+            )
+            .order_by(column, order)
+            .order_by(users::Column::Id, Order::Asc),
+        Some(condition.build()),
+        &pagination,
+    )
+    .await?;
+
+    format::json(AdminUserListResponse::new(&page))
+}
+
+#[debug_handler]
+async fn get_one(
+    _admin: Admin,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = load_user(&ctx, &pid).await?;
+    format::json(AdminUserResponse::new(&user))
+}
+
+/// Marks the user email as verified without going through the verification
+/// email flow
+#[debug_handler]
+async fn verify(
+    admin: Admin,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = load_user(&ctx, &pid).await?;
+    let user = if user.email_verified_at.is_some() {
+        user
+    } else {
+        user.into_active_model().verified(&ctx.db).await?
+    };
+
+    tracing::info!(
+        admin_pid = admin.user.pid.to_string(),
+        pid = user.pid.to_string(),
+        "user force verified by admin"
+    );
+    format::json(AdminUserResponse::new(&user))
+}
+
+/// Sends a password reset email to the user on their behalf
+#[debug_handler]
+async fn reset_password(
+    admin: Admin,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = load_user(&ctx, &pid)
+        .await?
+        .into_active_model()
+        .set_forgot_password_sent(&ctx.db)
+        .await?;
+
+    AuthMailer::forgot_password(&ctx, &user).await?;
+
+    tracing::info!(
+        admin_pid = admin.user.pid.to_string(),
+        pid = user.pid.to_string(),
+        "password reset sent by admin"
+    );
+    format::json(AdminUserResponse::new(&user))
+}
+
+#[debug_handler]
+async fn disable(
+    admin: Admin,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = load_user(&ctx, &pid).await?;
+    if user.id == admin.user.id {
+        return bad_request("you cannot disable your own account");
+    }
+
+    let user = user.into_active_model().disable(&ctx.db).await?;
+
+    tracing::info!(
+        admin_pid = admin.user.pid.to_string(),
+        pid = user.pid.to_string(),
+        "user disabled by admin"
+    );
+    format::json(AdminUserResponse::new(&user))
+}
+
+#[debug_handler]
+async fn enable(
+    admin: Admin,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = load_user(&ctx, &pid)
+        .await?
+        .into_active_model()
+        .enable(&ctx.db)
+        .await?;
+
+    tracing::info!(
+        admin_pid = admin.user.pid.to_string(),
+        pid = user.pid.to_string(),
+        "user enabled by admin"
+    );
+    format::json(AdminUserResponse::new(&user))
+}
+
+/// Assigns one of the configured plans to the user
+#[debug_handler]
+async fn set_plan(
+    admin: Admin,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<SetPlanParams>,
+) -> Result<Response> {
+    if let Some(plan) = &params.plan {
+        if !Plans::from_context(&ctx).exists(plan) {
+            return bad_request(format!("unknown plan: {plan}"));
+        }
+    }
+
+    let user = load_user(&ctx, &pid)
+        .await?
+        .into_active_model()
+        .set_plan(&ctx.db, params.plan)
+        .await?;
+
+    tracing::info!(
+        admin_pid = admin.user.pid.to_string(),
+        pid = user.pid.to_string(),
+        plan = user.plan,
+        "plan changed by admin"
+    );
+    format::json(AdminUserResponse::new(&user))
+}
+
+#[debug_handler]
+async fn remove(
+    admin: Admin,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = load_user(&ctx, &pid).await?;
+    if user.id == admin.user.id {
+        return bad_request("you cannot delete your own account");
+    }
+
+    let pid = user.pid.to_string();
+    account::delete(&ctx, user).await?;
+
+    tracing::info!(
+        admin_pid = admin.user.pid.to_string(),
+        pid,
+        "user deleted by admin"
+    );
+    format::empty()
+}
+
+/// Issues a short-lived token that lets the admin act as the given user. The
+/// token carries an `impersonator` claim, and the start of the session is
+/// recorded in the impersonation log.
+#[debug_handler]
+async fn impersonate(
+    admin: Admin,
+    client: ClientInfo,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = load_user(&ctx, &pid).await?;
+    if user.id == admin.user.id {
+        return bad_request("you cannot impersonate yourself");
+    }
+    if user.is_admin {
+        return bad_request("administrators cannot be impersonated");
+    }
+
+    let impersonation = Impersonation::start(&admin.user);
+    let jwt_secret = ctx.config.get_jwt_config()?;
+    let token = user
+        .generate_impersonation_jwt(&jwt_secret.secret, &impersonation)
+        .or_else(|_| unauthorized("unauthorized!"))?;
+
+    impersonation_logs::Model::record(
+        &ctx.db,
+        impersonation_logs::EVENT_START,
+        &admin.user,
+        &user,
+        client.ip_address,
+    )
+    .await?;
+
+    tracing::info!(
+        admin_pid = admin.user.pid.to_string(),
+        pid = user.pid.to_string(),
+        "impersonation started"
+    );
+    format::json(ImpersonationResponse::new(
+        &user,
+        &admin.user,
+        &token,
+        impersonation.expires_at,
+    ))
+}
+
+/// Lists the impersonation events recorded for the given user
+#[debug_handler]
+async fn impersonations(
+    _admin: Admin,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = load_user(&ctx, &pid).await?;
+    let logs = impersonation_logs::Model::find_by_user(&ctx.db, &user).await?;
+
+    format::json(
+        logs.iter()
+            .map(ImpersonationLogResponse::new)
+            .collect::<Vec<_>>(),
+    )
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/admin/users")
+        .add("/", get(list))
+        .add("/{pid}", get(get_one))
+        .add("/{pid}", delete(remove))
+        .add("/{pid}/verify", post(verify))
+        .add("/{pid}/reset-password", post(reset_password))
+        .add("/{pid}/disable", post(disable))
+        .add("/{pid}/enable", post(enable))
+        .add("/{pid}/plan", put(set_plan))
+        .add("/{pid}/impersonate", post(impersonate))
+        .add("/{pid}/impersonations", get(impersonations))
+}
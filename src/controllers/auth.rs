@@ -1,7 +1,14 @@
 use crate::{
-    mailers::auth::AuthMailer,
+    common::{
+        events::{self, EventHub},
+        webhooks,
+    },
+    extractors::{client_info::ClientInfo, current_user::CurrentUser},
+    mailers::{self, auth::AuthMailer},
     models::{
         _entities::users,
+        audit_events,
+        impersonation_logs::{self, Impersonation},
         users::{LoginParams, RegisterParams},
     },
     views::auth::{CurrentResponse, LoginResponse},
@@ -20,6 +27,49 @@ fn _get_allow_email_domain_re() -> &'static Regex {
     })
 }
 
+/// Records an authentication event for the user along with where the request
+/// came from
+async fn audit(
+    ctx: &AppContext,
+    client: &ClientInfo,
+    user: &users::Model,
+    event: &str,
+    detail: Option<&str>,
+) -> Result<()> {
+    audit_events::Model::record(
+        &ctx.db,
+        user,
+        event,
+        detail,
+        client.ip_address.clone(),
+        client.user_agent.clone(),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Pushes an event to the open event streams of the user
+fn publish(ctx: &AppContext, user: &users::Model, kind: &str, data: serde_json::Value) {
+    EventHub::from_context(ctx).publish(&user.pid.to_string(), kind, data);
+}
+
+/// Emails the user when they sign in from a browser that never signed in to
+/// their account before. Must run before the sign in itself is audited.
+async fn notify_if_new_device(
+    ctx: &AppContext,
+    client: &ClientInfo,
+    user: &users::Model,
+) -> Result<()> {
+    let Some(user_agent) = client.user_agent.as_deref() else {
+        return Ok(());
+    };
+    if !audit_events::Model::has_signed_in_with(&ctx.db, user, user_agent).await? {
+        AuthMailer::send_new_device_login(ctx, user, client.ip_address.as_deref(), user_agent)
+            .await?;
+    }
+    Ok(())
+}
+
 #[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
 #[ts(export)]
 pub struct ForgotParams {
@@ -49,6 +99,7 @@ pub struct ResendVerificationParams {
 #[debug_handler]
 async fn register(
     State(ctx): State<AppContext>,
+    client: ClientInfo,
     Json(params): Json<RegisterParams>,
 ) -> Result<Response> {
     let res = users::Model::create_with_password(&ctx.db, &params).await;
@@ -65,10 +116,31 @@ async fn register(
         }
     };
 
-    let user = user
+    let mut user = user
         .into_active_model()
         .set_email_verification_sent(&ctx.db)
         .await?;
+    // Emails are sent in the language the browser asked for at registration
+    // until the user picks a locale.
+    if let Some(locale) = client
+        .accept_language
+        .as_deref()
+        .and_then(mailers::accept_language)
+    {
+        let mut preferences = user.preferences();
+        preferences.locale = Some(locale.to_string());
+        user = user
+            .into_active_model()
+            .set_preferences(&ctx.db, &preferences)
+            .await?;
+    }
+    audit(&ctx, &client, &user, audit_events::EVENT_REGISTER, None).await?;
+    webhooks::emit(
+        &ctx,
+        webhooks::EVENT_USER_REGISTERED,
+        webhooks::user_data(&user),
+    )
+    .await;
 
     AuthMailer::send_welcome(&ctx, &user).await?;
     let jwt_secret = ctx.config.get_jwt_config()?;
@@ -83,7 +155,11 @@ async fn register(
 /// Verify register user. if the user not verified his email, he can't login to
 /// the system.
 #[debug_handler]
-async fn verify(State(ctx): State<AppContext>, Path(token): Path<String>) -> Result<Response> {
+async fn verify(
+    State(ctx): State<AppContext>,
+    client: ClientInfo,
+    Path(token): Path<String>,
+) -> Result<Response> {
     let Ok(user) = users::Model::find_by_verification_token(&ctx.db, &token).await else {
         return unauthorized("invalid token");
     };
@@ -93,6 +169,20 @@ async fn verify(State(ctx): State<AppContext>, Path(token): Path<String>) -> Res
     } else {
         let active_model = user.into_active_model();
         let user = active_model.verified(&ctx.db).await?;
+        audit(&ctx, &client, &user, audit_events::EVENT_VERIFY_EMAIL, None).await?;
+        AuthMailer::send_email_verified(&ctx, &user).await?;
+        publish(
+            &ctx,
+            &user,
+            events::EVENT_EMAIL_VERIFIED,
+            serde_json::json!({ "email_verified_at": user.email_verified_at }),
+        );
+        webhooks::emit(
+            &ctx,
+            webhooks::EVENT_USER_VERIFIED,
+            webhooks::user_data(&user),
+        )
+        .await;
         tracing::info!(pid = user.pid.to_string(), "user verified");
     }
 
@@ -106,6 +196,7 @@ async fn verify(State(ctx): State<AppContext>, Path(token): Path<String>) -> Res
 #[debug_handler]
 async fn forgot(
     State(ctx): State<AppContext>,
+    client: ClientInfo,
     Json(params): Json<ForgotParams>,
 ) -> Result<Response> {
     let Ok(user) = users::Model::find_by_email(&ctx.db, &params.email).await else {
@@ -118,6 +209,14 @@ async fn forgot(
         .into_active_model()
         .set_forgot_password_sent(&ctx.db)
         .await?;
+    audit(
+        &ctx,
+        &client,
+        &user,
+        audit_events::EVENT_PASSWORD_RESET_REQUESTED,
+        None,
+    )
+    .await?;
 
     AuthMailer::forgot_password(&ctx, &user).await?;
 
@@ -126,7 +225,11 @@ async fn forgot(
 
 /// reset user password by the given parameters
 #[debug_handler]
-async fn reset(State(ctx): State<AppContext>, Json(params): Json<ResetParams>) -> Result<Response> {
+async fn reset(
+    State(ctx): State<AppContext>,
+    client: ClientInfo,
+    Json(params): Json<ResetParams>,
+) -> Result<Response> {
     let Ok(user) = users::Model::find_by_reset_token(&ctx.db, &params.token).await else {
         // we don't want to expose our users email. if the email is invalid we still
         // returning success to the caller
@@ -134,16 +237,42 @@ async fn reset(State(ctx): State<AppContext>, Json(params): Json<ResetParams>) -
 
         return format::json(());
     };
-    user.into_active_model()
+    let user = user
+        .into_active_model()
         .reset_password(&ctx.db, &params.password)
         .await?;
+    audit(
+        &ctx,
+        &client,
+        &user,
+        audit_events::EVENT_PASSWORD_RESET,
+        None,
+    )
+    .await?;
+    AuthMailer::send_password_changed(&ctx, &user).await?;
+    publish(
+        &ctx,
+        &user,
+        events::EVENT_PASSWORD_RESET,
+        serde_json::json!({}),
+    );
+    webhooks::emit(
+        &ctx,
+        webhooks::EVENT_USER_PASSWORD_RESET,
+        webhooks::user_data(&user),
+    )
+    .await;
 
     format::json(())
 }
 
 /// Creates a user login and returns a token
 #[debug_handler]
-async fn login(State(ctx): State<AppContext>, Json(params): Json<LoginParams>) -> Result<Response> {
+async fn login(
+    State(ctx): State<AppContext>,
+    client: ClientInfo,
+    Json(params): Json<LoginParams>,
+) -> Result<Response> {
     let Ok(user) = users::Model::find_by_email(&ctx.db, &params.email).await else {
         tracing::debug!(
             email = params.email,
@@ -155,27 +284,131 @@ async fn login(State(ctx): State<AppContext>, Json(params): Json<LoginParams>) -
     let valid = user.verify_password(&params.password);
 
     if !valid {
+        audit(
+            &ctx,
+            &client,
+            &user,
+            audit_events::EVENT_LOGIN_FAILED,
+            Some("invalid_password"),
+        )
+        .await?;
+        return unauthorized("unauthorized!");
+    }
+
+    if user.is_pending_deletion() {
+        tracing::info!(
+            pid = user.pid.to_string(),
+            "login attempt for account pending deletion"
+        );
+        audit(
+            &ctx,
+            &client,
+            &user,
+            audit_events::EVENT_LOGIN_FAILED,
+            Some("pending_deletion"),
+        )
+        .await?;
+        return unauthorized("unauthorized!");
+    }
+
+    if user.is_disabled() {
+        tracing::info!(
+            pid = user.pid.to_string(),
+            "login attempt for disabled account"
+        );
+        audit(
+            &ctx,
+            &client,
+            &user,
+            audit_events::EVENT_LOGIN_FAILED,
+            Some("disabled"),
+        )
+        .await?;
         return unauthorized("unauthorized!");
     }
 
+    if let Err(suspended) = user.check_suspension() {
+        tracing::info!(
+            pid = user.pid.to_string(),
+            "login attempt for suspended account"
+        );
+        audit(
+            &ctx,
+            &client,
+            &user,
+            audit_events::EVENT_LOGIN_FAILED,
+            Some("suspended"),
+        )
+        .await?;
+        return Err(suspended.into());
+    }
+
     let jwt_secret = ctx.config.get_jwt_config()?;
 
     let token = user
         .generate_jwt(&jwt_secret.secret, jwt_secret.expiration)
         .or_else(|_| unauthorized("unauthorized!"))?;
 
+    let user = user.into_active_model().logged_in(&ctx.db).await?;
+    notify_if_new_device(&ctx, &client, &user).await?;
+    audit(&ctx, &client, &user, audit_events::EVENT_LOGIN, None).await?;
+
     format::json(LoginResponse::new(&user, &token))
 }
 
 #[debug_handler]
-async fn current(auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
-    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
+async fn current(auth: CurrentUser, State(ctx): State<AppContext>) -> Result<Response> {
+    let user = auth.user;
 
     let jwt_secret = ctx.config.get_jwt_config()?;
+
+    let (Some(impersonation), Some(impersonator)) =
+        (Impersonation::from_claims(&auth.claims), auth.impersonator)
+    else {
+        let token = user
+            .generate_jwt(&jwt_secret.secret, jwt_secret.expiration)
+            .or_else(|_| unauthorized("unauthorized!"))?;
+        return format::json(CurrentResponse::new(&user, &token));
+    };
+
+    // the refreshed token never outlives the original session
     let token = user
-        .generate_jwt(&jwt_secret.secret, jwt_secret.expiration)
+        .generate_impersonation_jwt(&jwt_secret.secret, &impersonation)
         .or_else(|_| unauthorized("unauthorized!"))?;
-    format::json(CurrentResponse::new(&user, &token))
+    format::json(CurrentResponse::new(&user, &token).with_impersonator(&impersonator))
+}
+
+/// Ends an impersonation session. Must be called with the impersonation token;
+/// the event is recorded in the impersonation log and the client is expected
+/// to drop the token.
+#[debug_handler]
+async fn stop_impersonation(
+    auth: auth::JWT,
+    client: ClientInfo,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let Some(impersonation) = Impersonation::from_claims(&auth.claims) else {
+        return bad_request("not impersonating");
+    };
+
+    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
+    let impersonator = users::Model::find_by_pid(&ctx.db, &impersonation.impersonator_pid).await?;
+
+    impersonation_logs::Model::record(
+        &ctx.db,
+        impersonation_logs::EVENT_STOP,
+        &impersonator,
+        &user,
+        client.ip_address,
+    )
+    .await?;
+
+    tracing::info!(
+        admin_pid = impersonator.pid.to_string(),
+        pid = user.pid.to_string(),
+        "impersonation stopped"
+    );
+    format::json(())
 }
 
 /// Magic link authentication provides a secure and passwordless way to log in to the application.
@@ -194,6 +427,7 @@ async fn current(auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Respo
 /// This flow enhances security by avoiding traditional passwords and providing a seamless login experience.
 async fn magic_link(
     State(ctx): State<AppContext>,
+    client: ClientInfo,
     Json(params): Json<MagicLinkParams>,
 ) -> Result<Response> {
     // let email_regex = get_allow_email_domain_re();
@@ -212,7 +446,23 @@ async fn magic_link(
         return format::empty_json();
     };
 
+    if user.is_pending_deletion() || user.is_disabled() || user.is_suspended() {
+        tracing::info!(
+            pid = user.pid.to_string(),
+            "magic link requested for account that cannot log in"
+        );
+        return format::empty_json();
+    }
+
     let user = user.into_active_model().create_magic_link(&ctx.db).await?;
+    audit(
+        &ctx,
+        &client,
+        &user,
+        audit_events::EVENT_MAGIC_LINK_REQUESTED,
+        None,
+    )
+    .await?;
     AuthMailer::send_magic_link(&ctx, &user).await?;
 
     format::empty_json()
@@ -222,6 +472,7 @@ async fn magic_link(
 async fn magic_link_verify(
     Path(token): Path<String>,
     State(ctx): State<AppContext>,
+    client: ClientInfo,
 ) -> Result<Response> {
     let Ok(user) = users::Model::find_by_magic_token(&ctx.db, &token).await else {
         // we don't want to expose our users email. if the email is invalid we still
@@ -229,7 +480,33 @@ async fn magic_link_verify(
         return unauthorized("unauthorized!");
     };
 
-    let user = user.into_active_model().clear_magic_link(&ctx.db).await?;
+    if user.is_pending_deletion() || user.is_disabled() {
+        return unauthorized("unauthorized!");
+    }
+    user.check_suspension()?;
+
+    let user = user
+        .into_active_model()
+        .clear_magic_link(&ctx.db)
+        .await?
+        .into_active_model()
+        .logged_in(&ctx.db)
+        .await?;
+    notify_if_new_device(&ctx, &client, &user).await?;
+    audit(
+        &ctx,
+        &client,
+        &user,
+        audit_events::EVENT_MAGIC_LINK_USED,
+        None,
+    )
+    .await?;
+    publish(
+        &ctx,
+        &user,
+        events::EVENT_MAGIC_LINK_USED,
+        serde_json::json!({}),
+    );
 
     let jwt_secret = ctx.config.get_jwt_config()?;
 
@@ -243,6 +520,7 @@ async fn magic_link_verify(
 #[debug_handler]
 async fn resend_verification_email(
     State(ctx): State<AppContext>,
+    client: ClientInfo,
     Json(params): Json<ResendVerificationParams>,
 ) -> Result<Response> {
     let Ok(user) = users::Model::find_by_email(&ctx.db, &params.email).await else {
@@ -265,6 +543,14 @@ async fn resend_verification_email(
         .into_active_model()
         .set_email_verification_sent(&ctx.db)
         .await?;
+    audit(
+        &ctx,
+        &client,
+        &user,
+        audit_events::EVENT_RESEND_VERIFICATION,
+        None,
+    )
+    .await?;
 
     AuthMailer::send_welcome(&ctx, &user).await?;
     tracing::info!(pid = user.pid.to_string(), "Verification email re-sent");
@@ -281,6 +567,7 @@ pub fn routes() -> Routes {
         .add("/forgot", post(forgot))
         .add("/reset", post(reset))
         .add("/current", get(current))
+        .add("/stop-impersonation", post(stop_impersonation))
         .add("/magic-link", post(magic_link))
         .add("/magic-link/{token}", get(magic_link_verify))
         .add("/resend-verification-mail", post(resend_verification_email))
@@ -0,0 +1,142 @@
+use crate::{
+    common::{
+        avatar::{self, AVATAR_CONTENT_TYPE, AVATAR_SIZES},
+        plans::{Plans, STORAGE_AVATARS},
+    },
+    extractors::api_user::ApiUser,
+    views::profile::AvatarUrls,
+};
+use axum::{body::Bytes, debug_handler, extract::Multipart, http::header, response::IntoResponse};
+use loco_rs::prelude::*;
+use uuid::Uuid;
+
+/// Removes the stored thumbnails of a previous avatar. Failures are only
+/// logged since the user no longer references them.
+async fn delete_thumbnails(ctx: &AppContext, key: &str) {
+    if let Err(err) = avatar::delete(ctx, key).await {
+        tracing::warn!(key, error = err.to_string(), "could not delete avatar");
+    }
+}
+
+/// Accepts a multipart upload with an `avatar` field, stores square
+/// thumbnails of it and makes it the avatar of the current user. The
+/// thumbnails count against the storage quota of the plan, replacing those of
+/// the previous avatar.
+#[debug_handler]
+async fn upload(
+    auth: ApiUser,
+    State(ctx): State<AppContext>,
+    mut multipart: Multipart,
+) -> Result<Response> {
+    let user = auth.user;
+
+    let mut upload = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|err| Error::BadRequest(err.to_string()))?
+    {
+        if field.name() == Some("avatar") {
+            let content_type = field.content_type().map(ToString::to_string);
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|err| Error::BadRequest(err.to_string()))?;
+            upload = Some((content_type, bytes));
+            break;
+        }
+    }
+    let Some((content_type, bytes)) = upload else {
+        return bad_request("missing `avatar` field");
+    };
+
+    avatar::validate_upload(content_type.as_deref(), &bytes)?;
+    let thumbnails = tokio::task::spawn_blocking(move || avatar::thumbnails(&bytes))
+        .await
+        .map_err(|err| Error::Message(err.to_string()))??;
+
+    let plans = Plans::from_context(&ctx);
+    let stored_bytes = thumbnails
+        .iter()
+        .map(|thumbnail| thumbnail.bytes.len() as u64)
+        .sum();
+    plans
+        .check_storage(&ctx.db, &user, STORAGE_AVATARS, stored_bytes)
+        .await?;
+
+    let key = Uuid::new_v4().to_string();
+    for thumbnail in thumbnails {
+        ctx.storage
+            .upload(
+                &avatar::storage_path(&key, thumbnail.size),
+                &Bytes::from(thumbnail.bytes),
+            )
+            .await?;
+    }
+
+    let previous_key = user.avatar_key.clone();
+    let user = user
+        .into_active_model()
+        .set_avatar(&ctx.db, Some(key))
+        .await?;
+    if let Some(previous_key) = previous_key {
+        delete_thumbnails(&ctx, &previous_key).await;
+    }
+    plans
+        .record_storage(&ctx.db, &user, STORAGE_AVATARS, stored_bytes)
+        .await?;
+
+    tracing::info!(pid = user.pid.to_string(), "avatar updated");
+    format::json(AvatarUrls::new(&user))
+}
+
+#[debug_handler]
+async fn remove(auth: ApiUser, State(ctx): State<AppContext>) -> Result<Response> {
+    let user = auth.user;
+
+    if let Some(key) = user.avatar_key.clone() {
+        let user = user.into_active_model().set_avatar(&ctx.db, None).await?;
+        delete_thumbnails(&ctx, &key).await;
+        Plans::from_context(&ctx)
+            .record_storage(&ctx.db, &user, STORAGE_AVATARS, 0)
+            .await?;
+        tracing::info!(pid = user.pid.to_string(), "avatar removed");
+    }
+
+    format::empty()
+}
+
+/// Serves a stored thumbnail. Thumbnails are never modified in place, a new
+/// upload gets a new key, so they can be cached indefinitely.
+#[debug_handler]
+async fn show(
+    Path((key, size)): Path<(String, String)>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    if Uuid::parse_str(&key).is_err() || !AVATAR_SIZES.iter().any(|(name, _)| *name == size) {
+        return Err(Error::NotFound);
+    }
+
+    let bytes: Vec<u8> = ctx
+        .storage
+        .download(&avatar::storage_path(&key, &size))
+        .await
+        .map_err(|_| Error::NotFound)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, AVATAR_CONTENT_TYPE),
+            (header::CACHE_CONTROL, "public, max-age=31536000, immutable"),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/avatars")
+        .add("/", post(upload))
+        .add("/", delete(remove))
+        .add("/{key}/{size}", get(show))
+}
@@ -0,0 +1,157 @@
+//! Inbound bounce and complaint notifications from the mail provider. Every
+//! permanent bounce and every complaint suppresses the address, so no more
+//! emails are sent to it.
+
+use crate::{
+    common::settings::Settings,
+    models::email_suppressions::{self, REASON_BOUNCE, REASON_COMPLAINT},
+};
+use axum::{debug_handler, extract::Query};
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Deserialize)]
+pub struct EventsTokenParams {
+    pub token: Option<String>,
+}
+
+/// Kind of a generic notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailEventKind {
+    Bounce,
+    Complaint,
+}
+
+/// A notification in the generic format, for providers without a dedicated
+/// endpoint or for forwarding from other systems.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EmailEventParams {
+    #[serde(rename = "type")]
+    pub kind: EmailEventKind,
+    pub email: String,
+    /// Temporary bounces do not suppress the address. Defaults to `true`.
+    #[serde(default = "permanent")]
+    pub permanent: bool,
+    pub detail: Option<String>,
+}
+
+const fn permanent() -> bool {
+    true
+}
+
+/// One event of a `SendGrid` event webhook post. Only the fields used here
+/// are read.
+#[derive(Debug, Deserialize)]
+pub struct SendGridEvent {
+    pub email: String,
+    pub event: String,
+    /// `bounce` or `blocked` on bounce events. Blocked messages are
+    /// temporary failures.
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// Rejects the request unless it carries the configured token. Digests are
+/// compared so the comparison takes the same time for every token.
+fn authorize(ctx: &AppContext, params: &EventsTokenParams) -> Result<()> {
+    let expected = Settings::from_config(&ctx.config)?
+        .mail
+        .events_token
+        .filter(|token| !token.is_empty());
+    let authorized = match (expected, params.token.as_deref()) {
+        (Some(expected), Some(token)) => {
+            Sha256::digest(expected.as_bytes()) == Sha256::digest(token.as_bytes())
+        }
+        _ => false,
+    };
+    if authorized {
+        Ok(())
+    } else {
+        unauthorized("invalid email events token")
+    }
+}
+
+async fn suppress(
+    ctx: &AppContext,
+    email: &str,
+    reason: &str,
+    source: &str,
+    detail: Option<&str>,
+) -> Result<()> {
+    let suppression =
+        email_suppressions::Model::suppress(&ctx.db, email, reason, source, detail).await?;
+    tracing::info!(
+        suppression_pid = suppression.pid.to_string(),
+        reason,
+        source,
+        "email address suppressed"
+    );
+    Ok(())
+}
+
+/// Receives a notification in the generic format
+#[debug_handler]
+async fn generic(
+    State(ctx): State<AppContext>,
+    Query(token): Query<EventsTokenParams>,
+    Json(params): Json<EmailEventParams>,
+) -> Result<Response> {
+    authorize(&ctx, &token)?;
+
+    let reason = match params.kind {
+        EmailEventKind::Bounce if !params.permanent => {
+            tracing::info!("temporary bounce ignored");
+            return format::json(());
+        }
+        EmailEventKind::Bounce => REASON_BOUNCE,
+        EmailEventKind::Complaint => REASON_COMPLAINT,
+    };
+    suppress(
+        &ctx,
+        &params.email,
+        reason,
+        "generic",
+        params.detail.as_deref(),
+    )
+    .await?;
+    format::json(())
+}
+
+/// Receives a `SendGrid` event webhook post. Events other than bounces and
+/// spam reports are ignored.
+#[debug_handler]
+async fn sendgrid(
+    State(ctx): State<AppContext>,
+    Query(token): Query<EventsTokenParams>,
+    Json(events): Json<Vec<SendGridEvent>>,
+) -> Result<Response> {
+    authorize(&ctx, &token)?;
+
+    for event in events {
+        let reason = match (event.event.as_str(), event.kind.as_deref()) {
+            ("bounce", Some("blocked")) => continue,
+            ("bounce", _) => REASON_BOUNCE,
+            ("spamreport", _) => REASON_COMPLAINT,
+            _ => continue,
+        };
+        suppress(
+            &ctx,
+            &event.email,
+            reason,
+            "sendgrid",
+            event.reason.as_deref(),
+        )
+        .await?;
+    }
+    format::json(())
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/email-events")
+        .add("/", post(generic))
+        .add("/sendgrid", post(sendgrid))
+}
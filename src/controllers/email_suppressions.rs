@@ -0,0 +1,94 @@
+use crate::{
+    common::search,
+    extractors::admin::Admin,
+    models::{_entities::email_suppressions, email_suppressions::Model},
+    views::emails::EmailSuppressionListResponse,
+};
+use axum::{debug_handler, extract::Query};
+use loco_rs::prelude::*;
+use sea_orm::{QueryFilter, QueryOrder, QueryTrait};
+use serde::{Deserialize, Serialize};
+
+/// Query parameters accepted by the suppression list. Every filter is
+/// optional.
+#[derive(Debug, Default, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct SearchSuppressionsParams {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    /// Part of the suppressed address
+    pub email: Option<String>,
+    /// `bounce` or `complaint`
+    pub reason: Option<String>,
+}
+
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Lists suppressed addresses, newest first
+#[debug_handler]
+async fn search(
+    _admin: Admin,
+    State(ctx): State<AppContext>,
+    Query(params): Query<SearchSuppressionsParams>,
+) -> Result<Response> {
+    let mut condition = query::condition();
+    if let Some(reason) = params.reason.as_deref().filter(|value| !value.is_empty()) {
+        condition = condition.eq(email_suppressions::Column::Reason, reason);
+    }
+
+    let pagination = query::PaginationQuery {
+        page: params.page.unwrap_or(1).into(),
+        page_size: params
+            .page_size
+            .unwrap_or(25)
+            .clamp(1, MAX_PAGE_SIZE)
+            .into(),
+    };
+    let page = query::paginate(
+        &ctx.db,
+        email_suppressions::Entity::find()
+            .apply_if(
+                params.email.as_deref().filter(|value| !value.is_empty()),
+                |query, email| {
+                    query.filter(search::contains_ignore_case(
+                        email_suppressions::Column::Email,
+                        email,
+                    ))
+                },
+            )
+            .order_by_desc(email_suppressions::Column::Id),
+        Some(condition.build()),
+        &pagination,
+    )
+    .await?;
+
+    format::json(EmailSuppressionListResponse::new(&page))
+}
+
+/// Lifts a suppression so emails to the address are sent again
+#[debug_handler]
+async fn lift(
+    admin: Admin,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let suppression = Model::find_by_pid(&ctx.db, &pid)
+        .await
+        .map_err(|_| Error::NotFound)?;
+    let suppression_pid = suppression.pid.to_string();
+    suppression.lift(&ctx.db).await?;
+
+    tracing::info!(
+        admin_pid = admin.user.pid.to_string(),
+        suppression_pid,
+        "email suppression lifted by admin"
+    );
+    format::json(())
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/admin/email-suppressions")
+        .add("/", get(search))
+        .add("/{pid}", delete(lift))
+}
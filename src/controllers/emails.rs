@@ -0,0 +1,111 @@
+use crate::{
+    common::search,
+    extractors::admin::Admin,
+    mailers::auth::AuthMailer,
+    models::{
+        _entities::email_outbox,
+        email_outbox::{STATUS_QUEUED, STATUS_SENDING},
+    },
+    views::emails::{EmailMessageListResponse, EmailMessageResponse},
+};
+use axum::{debug_handler, extract::Query};
+use loco_rs::prelude::*;
+use sea_orm::{QueryFilter, QueryOrder, QueryTrait};
+use serde::{Deserialize, Serialize};
+
+/// Query parameters accepted by the outbox search. Every filter is optional.
+#[derive(Debug, Default, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct SearchEmailsParams {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    /// Part of the recipient address
+    pub recipient: Option<String>,
+    pub template: Option<String>,
+    /// `queued`, `sending`, `sent`, `failed` or `suppressed`
+    pub status: Option<String>,
+}
+
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Searches the email outbox, newest first
+#[debug_handler]
+async fn search(
+    _admin: Admin,
+    State(ctx): State<AppContext>,
+    Query(params): Query<SearchEmailsParams>,
+) -> Result<Response> {
+    let mut condition = query::condition();
+    if let Some(template) = params.template.as_deref().filter(|value| !value.is_empty()) {
+        condition = condition.eq(email_outbox::Column::Template, template);
+    }
+    if let Some(status) = params.status.as_deref().filter(|value| !value.is_empty()) {
+        condition = condition.eq(email_outbox::Column::Status, status);
+    }
+
+    let pagination = query::PaginationQuery {
+        page: params.page.unwrap_or(1).into(),
+        page_size: params
+            .page_size
+            .unwrap_or(25)
+            .clamp(1, MAX_PAGE_SIZE)
+            .into(),
+    };
+    let page = query::paginate(
+        &ctx.db,
+        email_outbox::Entity::find()
+            .apply_if(
+                params
+                    .recipient
+                    .as_deref()
+                    .filter(|value| !value.is_empty()),
+                |query, recipient| {
+                    query.filter(search::contains_ignore_case(
+                        email_outbox::Column::Recipient,
+                        recipient,
+                    ))
+                },
+            )
+            .order_by_desc(email_outbox::Column::Id),
+        Some(condition.build()),
+        &pagination,
+    )
+    .await?;
+
+    format::json(EmailMessageListResponse::new(&page))
+}
+
+/// Sends a past message again as a new message, rendered again for the user
+/// with fresh one-time links. Messages that are still queued or being sent
+/// cannot be resent.
+#[debug_handler]
+async fn resend(
+    admin: Admin,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let message = email_outbox::Model::find_by_pid(&ctx.db, &pid)
+        .await
+        .map_err(|_| Error::NotFound)?;
+    if message.status == STATUS_QUEUED || message.status == STATUS_SENDING {
+        return bad_request("the message is still queued");
+    }
+
+    let resent = AuthMailer::resend(&ctx, &message).await?;
+
+    tracing::info!(
+        admin_pid = admin.user.pid.to_string(),
+        outbox_pid = message.pid.to_string(),
+        resent_pid = resent.pid.to_string(),
+        "email resent by admin"
+    );
+    let resent = email_outbox::Model::find_by_pid(&ctx.db, &resent.pid.to_string()).await?;
+    format::json(EmailMessageResponse::new(&resent))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/admin/emails")
+        .add("/", get(search))
+        .add("/{pid}/resend", post(resend))
+}
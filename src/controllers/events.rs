@@ -0,0 +1,93 @@
+use std::{convert::Infallible, time::Duration};
+
+use crate::{
+    common::events::{EventHub, Subscription, UserEvent},
+    extractors::current_user::CurrentUser,
+};
+use axum::{
+    debug_handler,
+    http::HeaderMap,
+    response::{
+        sse::{Event, KeepAlive},
+        IntoResponse, Sse,
+    },
+};
+use chrono::Utc;
+use loco_rs::{auth::jwt::UserClaims, prelude::*};
+
+/// Interval of the comment frames that keep idle connections open through
+/// proxies.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Name of the last frame sent before the stream closes because the token
+/// expired. Clients should refresh their token and reconnect.
+const EVENT_TOKEN_EXPIRED: &str = "token_expired";
+
+/// Seconds until the token of the request expires.
+fn seconds_until_expiry(claims: &UserClaims) -> Result<u64> {
+    // `exp` is private on the claims, read it back from their serialized form
+    let exp = serde_json::to_value(claims)?
+        .get("exp")
+        .and_then(serde_json::Value::as_u64)
+        .ok_or_else(|| Error::Unauthorized("token has no expiration".to_string()))?;
+    let now = u64::try_from(Utc::now().timestamp()).unwrap_or_default();
+    Ok(exp.saturating_sub(now))
+}
+
+fn frame(event: &UserEvent) -> Event {
+    Event::default()
+        .id(event.id.to_string())
+        .event(&event.kind)
+        .data(event.data.to_string())
+}
+
+/// Streams the events of the current user as Server-Sent Events.
+///
+/// Reconnecting clients send the `Last-Event-ID` header to replay the events
+/// they missed, as long as they are still buffered. The stream ends with a
+/// `token_expired` event when the token used to open it expires. Like every
+/// authenticated route, it cannot be opened for an account that is disabled,
+/// suspended or pending deletion.
+#[debug_handler]
+async fn stream(
+    auth: CurrentUser,
+    State(ctx): State<AppContext>,
+    headers: HeaderMap,
+) -> Result<Response> {
+    let user = auth.user;
+
+    let remaining = seconds_until_expiry(&auth.claims)?;
+    if remaining == 0 {
+        return unauthorized("token expired");
+    }
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(remaining);
+
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    let subscription = EventHub::from_context(&ctx).subscribe(&user.pid.to_string(), last_event_id);
+
+    let events = futures_util::stream::unfold(
+        Some(subscription),
+        move |subscription: Option<Subscription>| async move {
+            let mut subscription = subscription?;
+            match tokio::time::timeout_at(deadline, subscription.next()).await {
+                Ok(Some(event)) => Some((Ok::<_, Infallible>(frame(&event)), Some(subscription))),
+                Ok(None) => None,
+                Err(_) => Some((
+                    Ok(Event::default().event(EVENT_TOKEN_EXPIRED).data("{}")),
+                    None,
+                )),
+            }
+        },
+    );
+
+    Ok(Sse::new(events)
+        .keep_alive(KeepAlive::new().interval(HEARTBEAT_INTERVAL))
+        .into_response())
+}
+
+pub fn routes() -> Routes {
+    Routes::new().prefix("/api/events").add("/", get(stream))
+}
@@ -0,0 +1,127 @@
+use crate::{
+    common::feature_flags::{FeatureFlags, FlagRule},
+    extractors::{admin::Admin, api_user::ApiUser},
+    models::feature_flags,
+    views::flags::{FeatureFlagResponse, FlagsResponse},
+};
+use axum::debug_handler;
+use loco_rs::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Changes to the rollout of a flag. Omitted fields keep their current
+/// value, or the configured default when the flag is not stored yet.
+#[derive(Debug, Default, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct UpdateFeatureFlagParams {
+    pub enabled: Option<bool>,
+    pub percentage: Option<u8>,
+    pub user_pids: Option<Vec<String>>,
+}
+
+static KEY_RE: OnceLock<Regex> = OnceLock::new();
+
+fn check_key(key: &str) -> Result<()> {
+    let re = KEY_RE.get_or_init(|| {
+        Regex::new(r"^[a-z0-9][a-z0-9_.-]{0,63}$").expect("Failed to compile regex")
+    });
+    if re.is_match(key) {
+        Ok(())
+    } else {
+        Err(Error::BadRequest(format!("invalid flag key: {key}")))
+    }
+}
+
+/// Returns every known flag evaluated for the current user
+#[debug_handler]
+async fn current(auth: ApiUser, State(ctx): State<AppContext>) -> Result<Response> {
+    let flags = FeatureFlags::from_context(&ctx)
+        .evaluate(&ctx.db, Some(&auth.user))
+        .await?;
+    format::json(FlagsResponse { flags })
+}
+
+/// Lists the effective rule of every known flag
+#[debug_handler]
+async fn list(_admin: Admin, State(ctx): State<AppContext>) -> Result<Response> {
+    let stored = feature_flags::Model::list(&ctx.db).await?;
+    let flags = FeatureFlags::from_context(&ctx)
+        .rules(&ctx.db)
+        .await?
+        .into_iter()
+        .map(|(key, rule)| {
+            let overridden = stored.iter().any(|flag| flag.key == key);
+            FeatureFlagResponse::new(key, rule, overridden)
+        })
+        .collect::<Vec<_>>();
+    format::json(flags)
+}
+
+/// Stores a rule for the flag, overriding the configured default
+#[debug_handler]
+async fn update(
+    admin: Admin,
+    Path(key): Path<String>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<UpdateFeatureFlagParams>,
+) -> Result<Response> {
+    check_key(&key)?;
+
+    let current = FeatureFlags::from_context(&ctx)
+        .rule(&ctx.db, &key)
+        .await?
+        .unwrap_or_default();
+    let rule = FlagRule {
+        enabled: params.enabled.unwrap_or(current.enabled),
+        percentage: params.percentage.unwrap_or(current.percentage),
+        user_pids: params.user_pids.unwrap_or(current.user_pids),
+    };
+    rule.validate()?;
+
+    let flag = feature_flags::Model::upsert(&ctx.db, &key, &rule).await?;
+
+    tracing::info!(
+        admin_pid = admin.user.pid.to_string(),
+        key,
+        enabled = rule.enabled,
+        percentage = rule.percentage,
+        "feature flag updated by admin"
+    );
+    format::json(FeatureFlagResponse::new(
+        flag.key.clone(),
+        flag.rule(),
+        true,
+    ))
+}
+
+/// Removes the stored rule so the flag falls back to the configured default
+#[debug_handler]
+async fn reset(
+    admin: Admin,
+    Path(key): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    if !feature_flags::Model::remove(&ctx.db, &key).await? {
+        return Err(Error::NotFound);
+    }
+
+    tracing::info!(
+        admin_pid = admin.user.pid.to_string(),
+        key,
+        "feature flag reset by admin"
+    );
+    format::empty()
+}
+
+pub fn routes() -> Routes {
+    Routes::new().prefix("/api/flags").add("/", get(current))
+}
+
+pub fn admin_routes() -> Routes {
+    Routes::new()
+        .prefix("/api/admin/flags")
+        .add("/", get(list))
+        .add("/{key}", patch(update))
+        .add("/{key}", delete(reset))
+}
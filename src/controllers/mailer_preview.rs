@@ -0,0 +1,102 @@
+//! Development only previews of the mailer templates, rendered with sample
+//! data. Only mounted in the development and test environments.
+
+use crate::mailers::{auth::AuthEmail, preview};
+use axum::{debug_handler, http::header};
+use loco_rs::{mailer::Email, prelude::*};
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders the template and builds the response from it. Render errors become
+/// a plain text response with the full cause so a missing local is obvious in
+/// the browser.
+fn respond(
+    ctx: &AppContext,
+    template: &str,
+    build: impl FnOnce(Email) -> Result<Response>,
+) -> Result<Response> {
+    match preview::render(ctx, template) {
+        Ok(email) => build(email),
+        Err(Error::NotFound) => Err(Error::NotFound),
+        Err(err) => {
+            tracing::error!(template, error = err.to_string(), "mailer preview failed");
+            Ok((
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+                format!("failed to render mailer template `{template}`:\n\n{err}"),
+            )
+                .into_response())
+        }
+    }
+}
+
+/// Lists every template with links to its previews
+#[debug_handler]
+async fn index() -> Result<Response> {
+    let items = AuthEmail::TEMPLATES
+        .iter()
+        .map(|name| {
+            format!(
+                "<li><a href=\"/_dev/mailers/{name}\">{name}</a> \
+                 (<a href=\"/_dev/mailers/{name}/html\">html</a>, \
+                 <a href=\"/_dev/mailers/{name}/text\">text</a>)</li>"
+            )
+        })
+        .collect::<String>();
+    format::html(&format!(
+        "<!doctype html><html><head><title>Mailer previews</title></head>\
+         <body><h1>Mailer previews</h1><ul>{items}</ul></body></html>"
+    ))
+}
+
+/// Shows the subject, and the html and text parts side by side
+#[debug_handler]
+async fn show(Path(template): Path<String>, State(ctx): State<AppContext>) -> Result<Response> {
+    respond(&ctx, &template, |email| {
+        format::html(&format!(
+            "<!doctype html><html><head><title>{name}</title><style>\
+             body {{ font-family: sans-serif; margin: 1rem; }} \
+             main {{ display: flex; gap: 1rem; }} \
+             main > section {{ flex: 1; min-width: 0; }} \
+             iframe {{ width: 100%; height: 80vh; border: 1px solid #ccc; }} \
+             pre {{ white-space: pre-wrap; border: 1px solid #ccc; padding: 0.5rem; }}\
+             </style></head><body>\
+         <p><a href=\"/_dev/mailers\">All templates</a></p>\
+         <h1>{name}</h1><p><strong>Subject:</strong> {subject}</p>\
+         <main><section><h2>HTML</h2><iframe srcdoc=\"{html}\"></iframe></section>\
+         <section><h2>Text</h2><pre>{text}</pre></section></main>\
+         </body></html>",
+            name = escape(&template),
+            subject = escape(&email.subject),
+            html = escape(&email.html),
+            text = escape(&email.text),
+        ))
+    })
+}
+
+/// The html part on its own
+#[debug_handler]
+async fn html(Path(template): Path<String>, State(ctx): State<AppContext>) -> Result<Response> {
+    respond(&ctx, &template, |email| format::html(&email.html))
+}
+
+/// The text part on its own
+#[debug_handler]
+async fn text(Path(template): Path<String>, State(ctx): State<AppContext>) -> Result<Response> {
+    respond(&ctx, &template, |email| format::text(&email.text))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/_dev/mailers")
+        .add("/", get(index))
+        .add("/{template}", get(show))
+        .add("/{template}/html", get(html))
+        .add("/{template}/text", get(text))
+}
@@ -1 +1,15 @@
+pub mod account;
+pub mod admin;
 pub mod auth;
+pub mod avatars;
+pub mod email_events;
+pub mod email_suppressions;
+pub mod emails;
+pub mod events;
+pub mod flags;
+pub mod mailer_preview;
+pub mod notifications;
+pub mod preferences;
+pub mod profile;
+pub mod usage;
+pub mod webhooks;
@@ -0,0 +1,83 @@
+use crate::{
+    extractors::api_user::ApiUser,
+    models::notifications,
+    views::notifications::{NotificationListResponse, NotificationResponse, UnreadCountResponse},
+};
+use axum::{debug_handler, extract::Query};
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct ListNotificationsParams {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    /// Only return notifications that have not been read yet
+    pub unread: Option<bool>,
+}
+
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Lists the notifications of the current user, newest first
+#[debug_handler]
+async fn list(
+    auth: ApiUser,
+    State(ctx): State<AppContext>,
+    Query(params): Query<ListNotificationsParams>,
+) -> Result<Response> {
+    let pagination = query::PaginationQuery {
+        page: params.page.unwrap_or(1).into(),
+        page_size: params
+            .page_size
+            .unwrap_or(25)
+            .clamp(1, MAX_PAGE_SIZE)
+            .into(),
+    };
+    let page = notifications::Model::paginate_by_user(
+        &ctx.db,
+        &auth.user,
+        params.unread.unwrap_or(false),
+        &pagination,
+    )
+    .await?;
+
+    format::json(NotificationListResponse::new(&page))
+}
+
+#[debug_handler]
+async fn unread_count(auth: ApiUser, State(ctx): State<AppContext>) -> Result<Response> {
+    let unread = notifications::Model::unread_count(&ctx.db, &auth.user).await?;
+    format::json(UnreadCountResponse { unread })
+}
+
+#[debug_handler]
+async fn mark_read(
+    auth: ApiUser,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let notification = notifications::Model::find_by_pid(&ctx.db, &auth.user, &pid)
+        .await
+        .map_err(|_| Error::NotFound)?
+        .into_active_model()
+        .mark_read(&ctx.db)
+        .await?;
+    format::json(NotificationResponse::new(&notification))
+}
+
+/// Marks every notification of the current user as read and returns the new
+/// unread count
+#[debug_handler]
+async fn mark_all_read(auth: ApiUser, State(ctx): State<AppContext>) -> Result<Response> {
+    notifications::Model::mark_all_read(&ctx.db, &auth.user).await?;
+    format::json(UnreadCountResponse { unread: 0 })
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/notifications")
+        .add("/", get(list))
+        .add("/unread-count", get(unread_count))
+        .add("/read-all", post(mark_all_read))
+        .add("/{pid}/read", post(mark_read))
+}
@@ -0,0 +1,47 @@
+use crate::{extractors::api_user::ApiUser, models::preferences::Preferences};
+use axum::debug_handler;
+use loco_rs::prelude::*;
+use serde_json::Value;
+
+#[debug_handler(state = AppContext)]
+async fn get_preferences(auth: ApiUser) -> Result<Response> {
+    format::json(auth.user.preferences())
+}
+
+/// Merges the body into the stored preferences. Keys that are left out keep
+/// their value and `null` resets a key to its default. Unknown keys and
+/// values of the wrong type are rejected with a 400.
+#[debug_handler]
+async fn update_preferences(
+    auth: ApiUser,
+    State(ctx): State<AppContext>,
+    Json(patch): Json<Value>,
+) -> Result<Response> {
+    let user = auth.user;
+    let preferences: Preferences = user
+        .preferences()
+        .merge(&patch)
+        .map_err(|err| Error::BadRequest(err.to_string()))?;
+
+    let user = match user
+        .into_active_model()
+        .set_preferences(&ctx.db, &preferences)
+        .await
+    {
+        Ok(user) => user,
+        Err(ModelError::Validation(errors)) => {
+            return Err(Error::ValidationError((*errors).clone()));
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    tracing::info!(pid = user.pid.to_string(), "preferences updated");
+    format::json(user.preferences())
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/preferences")
+        .add("/", get(get_preferences))
+        .add("/", patch(update_preferences))
+}
@@ -0,0 +1,43 @@
+use crate::{
+    extractors::api_user::ApiUser, models::users::UpdateProfileParams,
+    views::profile::ProfileResponse,
+};
+use axum::debug_handler;
+use loco_rs::prelude::*;
+
+#[debug_handler(state = AppContext)]
+async fn get_profile(auth: ApiUser) -> Result<Response> {
+    format::json(ProfileResponse::new(&auth.user))
+}
+
+/// Updates the profile of the current user. Validation failures are returned
+/// as a 400 with the offending fields.
+#[debug_handler]
+async fn update_profile(
+    auth: ApiUser,
+    State(ctx): State<AppContext>,
+    Json(params): Json<UpdateProfileParams>,
+) -> Result<Response> {
+    let user = match auth
+        .user
+        .into_active_model()
+        .update_profile(&ctx.db, &params)
+        .await
+    {
+        Ok(user) => user,
+        Err(ModelError::Validation(errors)) => {
+            return Err(Error::ValidationError((*errors).clone()));
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    tracing::info!(pid = user.pid.to_string(), "profile updated");
+    format::json(ProfileResponse::new(&user))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/profile")
+        .add("/", get(get_profile))
+        .add("/", patch(update_profile))
+}
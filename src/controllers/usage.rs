@@ -0,0 +1,22 @@
+use crate::{common::plans::Plans, extractors::api_user::ApiUser, views::usage::UsageResponse};
+use axum::debug_handler;
+use loco_rs::prelude::*;
+
+/// Returns the plan of the current user and the usage of each of its
+/// quotas. Not metered itself, so the dashboard keeps working once the
+/// request quota is used up.
+#[debug_handler]
+async fn current(auth: ApiUser, State(ctx): State<AppContext>) -> Result<Response> {
+    let user = auth.user;
+    let plans = Plans::from_context(&ctx);
+    let usage = plans.usage(&ctx.db, &user).await?;
+    format::json(UsageResponse::new(
+        plans.plan_key(&user),
+        plans.plan(&user),
+        usage,
+    ))
+}
+
+pub fn routes() -> Routes {
+    Routes::new().prefix("/api/usage").add("/", get(current))
+}
@@ -0,0 +1,221 @@
+use crate::{
+    common::webhooks::{self, EVENT_TYPES},
+    extractors::admin::Admin,
+    models::{webhook_deliveries, webhook_endpoints},
+    views::webhooks::{
+        WebhookDeliveryListResponse, WebhookDeliveryResponse, WebhookEndpointResponse,
+    },
+};
+use axum::{debug_handler, extract::Query};
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+use validator::ValidationError;
+
+fn validate_url(url: &str) -> Result<(), ValidationError> {
+    match url::Url::parse(url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.has_host() => Ok(()),
+        _ => Err(ValidationError::new("url").with_message("Must be a valid http(s) URL.".into())),
+    }
+}
+
+fn validate_event_types(event_types: &[String]) -> Result<(), ValidationError> {
+    if event_types.is_empty() {
+        return Err(ValidationError::new("event_types")
+            .with_message("Subscribe to at least one event type.".into()));
+    }
+    if event_types
+        .iter()
+        .all(|event| EVENT_TYPES.contains(&event.as_str()))
+    {
+        Ok(())
+    } else {
+        Err(ValidationError::new("event_types").with_message(
+            format!("Event types must be one of: {}.", EVENT_TYPES.join(", ")).into(),
+        ))
+    }
+}
+
+/// Registers an endpoint. A signing secret is generated unless one is given.
+#[derive(Debug, Deserialize, Serialize, Validate, ts_rs::TS)]
+#[ts(export)]
+pub struct CreateWebhookParams {
+    #[validate(custom(function = "validate_url"))]
+    pub url: String,
+    #[validate(custom(function = "validate_event_types"))]
+    pub event_types: Vec<String>,
+    #[validate(length(min = 16, message = "Secret must be at least 16 characters long."))]
+    pub secret: Option<String>,
+}
+
+/// Omitted fields are left untouched
+#[derive(Debug, Default, Deserialize, Serialize, Validate, ts_rs::TS)]
+#[ts(export)]
+pub struct UpdateWebhookParams {
+    #[validate(custom(function = "validate_url"))]
+    pub url: Option<String>,
+    #[validate(custom(function = "validate_event_types"))]
+    pub event_types: Option<Vec<String>>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct ListDeliveriesParams {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+const MAX_PAGE_SIZE: u32 = 100;
+
+async fn load_endpoint(ctx: &AppContext, pid: &str) -> Result<webhook_endpoints::Model> {
+    webhook_endpoints::Model::find_by_pid(&ctx.db, pid)
+        .await
+        .map_err(|_| Error::NotFound)
+}
+
+#[debug_handler]
+async fn list(_admin: Admin, State(ctx): State<AppContext>) -> Result<Response> {
+    let endpoints = webhook_endpoints::Model::list(&ctx.db).await?;
+    format::json(
+        endpoints
+            .iter()
+            .map(WebhookEndpointResponse::new)
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Registers an endpoint and returns it along with its signing secret, which
+/// is not shown again
+#[debug_handler]
+async fn create(
+    admin: Admin,
+    State(ctx): State<AppContext>,
+    Json(params): Json<CreateWebhookParams>,
+) -> Result<Response> {
+    params.validate()?;
+
+    let secret = params
+        .secret
+        .unwrap_or_else(webhook_endpoints::generate_secret);
+    let endpoint =
+        webhook_endpoints::Model::create(&ctx.db, &params.url, &params.event_types, secret).await?;
+
+    tracing::info!(
+        admin_pid = admin.user.pid.to_string(),
+        endpoint = endpoint.pid.to_string(),
+        "webhook endpoint registered by admin"
+    );
+    format::json(WebhookEndpointResponse::with_secret(&endpoint))
+}
+
+#[debug_handler]
+async fn update(
+    admin: Admin,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<UpdateWebhookParams>,
+) -> Result<Response> {
+    params.validate()?;
+
+    let endpoint = load_endpoint(&ctx, &pid)
+        .await?
+        .into_active_model()
+        .update_endpoint(&ctx.db, params.url, params.event_types, params.active)
+        .await?;
+
+    tracing::info!(
+        admin_pid = admin.user.pid.to_string(),
+        endpoint = endpoint.pid.to_string(),
+        "webhook endpoint updated by admin"
+    );
+    format::json(WebhookEndpointResponse::new(&endpoint))
+}
+
+/// Removes the endpoint along with its delivery log
+#[debug_handler]
+async fn remove(
+    admin: Admin,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let endpoint = load_endpoint(&ctx, &pid).await?;
+    endpoint.clone().delete(&ctx.db).await?;
+
+    tracing::info!(
+        admin_pid = admin.user.pid.to_string(),
+        endpoint = endpoint.pid.to_string(),
+        "webhook endpoint removed by admin"
+    );
+    format::empty()
+}
+
+/// Lists the delivery log of the endpoint, newest first
+#[debug_handler]
+async fn deliveries(
+    _admin: Admin,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+    Query(params): Query<ListDeliveriesParams>,
+) -> Result<Response> {
+    let endpoint = load_endpoint(&ctx, &pid).await?;
+
+    let pagination = query::PaginationQuery {
+        page: params.page.unwrap_or(1).into(),
+        page_size: params
+            .page_size
+            .unwrap_or(25)
+            .clamp(1, MAX_PAGE_SIZE)
+            .into(),
+    };
+    let page =
+        webhook_deliveries::Model::paginate_by_endpoint(&ctx.db, &endpoint, &pagination).await?;
+
+    format::json(WebhookDeliveryListResponse::new(&page))
+}
+
+/// Sends the payload of a past delivery again as a new delivery. The payload,
+/// including its event id, is unchanged so receivers can deduplicate.
+#[debug_handler]
+async fn redeliver(
+    admin: Admin,
+    Path(pid): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let delivery = webhook_deliveries::Model::find_by_pid(&ctx.db, &pid)
+        .await
+        .map_err(|_| Error::NotFound)?;
+    let endpoint = webhook_endpoints::Entity::find_by_id(delivery.webhook_endpoint_id)
+        .one(&ctx.db)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    let redelivery = webhook_deliveries::Model::create(
+        &ctx.db,
+        &endpoint,
+        &delivery.event,
+        delivery.payload.clone(),
+    )
+    .await?;
+    webhooks::enqueue(&ctx, &redelivery).await?;
+
+    tracing::info!(
+        admin_pid = admin.user.pid.to_string(),
+        delivery = delivery.pid.to_string(),
+        redelivery = redelivery.pid.to_string(),
+        "webhook redelivered by admin"
+    );
+    let redelivery =
+        webhook_deliveries::Model::find_by_pid(&ctx.db, &redelivery.pid.to_string()).await?;
+    format::json(WebhookDeliveryResponse::new(&redelivery))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/admin/webhooks")
+        .add("/", get(list))
+        .add("/", post(create))
+        .add("/{pid}", patch(update))
+        .add("/{pid}", delete(remove))
+        .add("/{pid}/deliveries", get(deliveries))
+        .add("/deliveries/{pid}/redeliver", post(redeliver))
+}
@@ -0,0 +1,54 @@
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::{request::Parts, StatusCode},
+};
+use loco_rs::{auth::jwt::UserClaims, controller::ErrorDetail, prelude::*};
+
+use super::current_user::CurrentUser;
+use crate::models::{impersonation_logs::Impersonation, users};
+
+/// Authenticates the request like [`CurrentUser`] and only lets it through
+/// when the user behind the token is an administrator. Anyone else, including
+/// an administrator using an impersonation token, gets a 403.
+pub struct Admin {
+    pub claims: UserClaims,
+    pub user: users::Model,
+}
+
+impl<S> FromRequestParts<S> for Admin
+where
+    AppContext: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Error> {
+        let auth = CurrentUser::from_request_parts(parts, state).await?;
+
+        if !auth.user.is_admin {
+            tracing::info!(
+                pid = auth.user.pid.to_string(),
+                "non admin user attempted to access an admin endpoint"
+            );
+            return Err(Error::CustomError(
+                StatusCode::FORBIDDEN,
+                ErrorDetail::new("forbidden", "Admin access is required"),
+            ));
+        }
+
+        if Impersonation::from_claims(&auth.claims).is_some() {
+            return Err(Error::CustomError(
+                StatusCode::FORBIDDEN,
+                ErrorDetail::new(
+                    "forbidden",
+                    "Admin access is not available while impersonating a user",
+                ),
+            ));
+        }
+
+        Ok(Self {
+            claims: auth.claims,
+            user: auth.user,
+        })
+    }
+}
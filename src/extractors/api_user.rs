@@ -0,0 +1,79 @@
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::request::Parts,
+};
+use loco_rs::{controller::extractor::auth::extract_token_from_header, prelude::*};
+
+use super::current_user::CurrentUser;
+use crate::models::users::{self, AccountSuspended, API_KEY_PREFIX};
+
+/// Authenticates the request with either an API key or a JWT. A bearer token
+/// with the API key prefix is looked up as an API key, anything else goes
+/// through [`CurrentUser`]. Either way the account must be usable, so a
+/// suspended account gets the same typed error with both credentials: the
+/// one [`users::Model`] wraps when looking up a suspended account's API key
+/// is unwrapped here.
+///
+/// Use it on data routes that do not depend on the session, such as the
+/// impersonation claims. A user already authenticated by the metering
+/// middleware is reused.
+#[derive(Clone)]
+pub struct ApiUser {
+    pub user: users::Model,
+}
+
+impl<S> FromRequestParts<S> for ApiUser
+where
+    AppContext: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Error> {
+        if let Some(auth) = parts.extensions.get::<Self>() {
+            return Ok(auth.clone());
+        }
+
+        let is_api_key = extract_token_from_header(&parts.headers)
+            .is_ok_and(|token| token.starts_with(API_KEY_PREFIX));
+        if !is_api_key {
+            let auth = CurrentUser::from_request_parts(parts, state).await?;
+            return Ok(Self { user: auth.user });
+        }
+
+        let api_key = extract_token_from_header(&parts.headers)?;
+        let ctx = AppContext::from_ref(state);
+        let user = <users::Model as Authenticable>::find_by_api_key(&ctx.db, &api_key)
+            .await
+            .map_err(api_key_error)?;
+        user.check_usable()?;
+
+        Ok(Self { user })
+    }
+}
+
+/// Maps the lookup error like loco's `ApiToken` does, except that a
+/// suspended account keeps its typed error.
+fn api_key_error(err: ModelError) -> Error {
+    match err {
+        ModelError::EntityNotFound => Error::Unauthorized("not found".to_string()),
+        ModelError::Any(err) => match err.downcast::<AccountSuspended>() {
+            Ok(suspended) => (*suspended).into(),
+            Err(err) => {
+                tracing::error!(error = err.to_string(), "API key authentication error");
+                Error::Unauthorized("could not authorize".to_string())
+            }
+        },
+        ModelError::DbErr(err) => {
+            tracing::error!(
+                error = err.to_string(),
+                "database error during API key authentication"
+            );
+            Error::InternalServerError
+        }
+        err => {
+            tracing::error!(error = err.to_string(), "API key authentication error");
+            Error::Unauthorized("could not authorize".to_string())
+        }
+    }
+}
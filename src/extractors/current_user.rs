@@ -0,0 +1,58 @@
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::request::Parts,
+};
+use loco_rs::{auth::jwt::UserClaims, prelude::*};
+
+use crate::models::{impersonation_logs::Impersonation, users};
+
+/// Authenticates the request with a JWT and loads the user behind it. Every
+/// authenticated route goes through this extractor, so tokens of disabled,
+/// suspended or pending deletion accounts stop working everywhere at once.
+/// Impersonation tokens also stop working once the impersonator is no longer
+/// an enabled administrator.
+pub struct CurrentUser {
+    pub claims: UserClaims,
+    pub user: users::Model,
+    /// The administrator behind an impersonation token
+    pub impersonator: Option<users::Model>,
+}
+
+impl<S> FromRequestParts<S> for CurrentUser
+where
+    AppContext: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Error> {
+        let auth = auth::JWTWithUser::<users::Model>::from_request_parts(parts, state).await?;
+        auth.user.check_usable()?;
+
+        let impersonator = match Impersonation::from_claims(&auth.claims) {
+            Some(impersonation) => {
+                let ctx = AppContext::from_ref(state);
+                let impersonator =
+                    users::Model::find_by_pid(&ctx.db, &impersonation.impersonator_pid)
+                        .await
+                        .map_err(|_| Error::Unauthorized("unauthorized!".to_string()))?;
+                if !impersonator.is_admin || impersonator.is_disabled() {
+                    tracing::info!(
+                        pid = auth.claims.pid,
+                        impersonator_pid = impersonation.impersonator_pid,
+                        "impersonation token of a former admin rejected"
+                    );
+                    return Err(Error::Unauthorized("unauthorized!".to_string()));
+                }
+                Some(impersonator)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            claims: auth.claims,
+            user: auth.user,
+            impersonator,
+        })
+    }
+}
@@ -0,0 +1,5 @@
+pub mod admin;
+pub mod api_user;
+pub mod client_info;
+pub mod current_user;
+pub mod sensitive;
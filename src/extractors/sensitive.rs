@@ -0,0 +1,54 @@
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::{request::Parts, StatusCode},
+};
+use loco_rs::{auth::jwt::UserClaims, controller::ErrorDetail, prelude::*};
+
+use super::current_user::CurrentUser;
+use crate::models::{impersonation_logs::Impersonation, users};
+
+/// JWT extractor for sensitive actions, currently account deletion and data
+/// export. Authenticates like [`CurrentUser`] and rejects tokens issued
+/// through admin impersonation.
+///
+/// There is no authenticated endpoint to change the password or the email
+/// address. Passwords are only changed through `/api/auth/reset` with the
+/// token emailed to the user, which an impersonating admin never receives,
+/// and the email address cannot be changed at all. Any future endpoint for
+/// either must use this extractor.
+pub struct SensitiveJWT {
+    pub claims: UserClaims,
+    pub user: users::Model,
+}
+
+impl<S> FromRequestParts<S> for SensitiveJWT
+where
+    AppContext: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Error> {
+        let auth = CurrentUser::from_request_parts(parts, state).await?;
+
+        if let Some(impersonation) = Impersonation::from_claims(&auth.claims) {
+            tracing::info!(
+                pid = auth.claims.pid,
+                impersonator_pid = impersonation.impersonator_pid,
+                "sensitive action blocked while impersonating"
+            );
+            return Err(Error::CustomError(
+                StatusCode::FORBIDDEN,
+                ErrorDetail::new(
+                    "forbidden",
+                    "This action is not allowed while impersonating a user",
+                ),
+            ));
+        }
+
+        Ok(Self {
+            claims: auth.claims,
+            user: auth.user,
+        })
+    }
+}
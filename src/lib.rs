@@ -1,8 +1,11 @@
 pub mod app;
+pub mod common;
 pub mod controllers;
 pub mod data;
+pub mod extractors;
 pub mod initializers;
 pub mod mailers;
+pub mod middlewares;
 pub mod models;
 pub mod tasks;
 pub mod views;
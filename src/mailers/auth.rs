@@ -1,99 +1,401 @@
 // auth mailer
 #![allow(non_upper_case_globals)]
 
-use loco_rs::{environment::Environment, prelude::*};
+use chrono::Utc;
+use loco_rs::{mailer::Email, prelude::*};
 use serde_json::json;
 
-use crate::models::users;
+use crate::{
+    common::{
+        data_export::{self, EXPORT_LINK_EXPIRATION_HOURS},
+        links::Links,
+        settings::Settings,
+    },
+    mailers::{self, layout, outbox},
+    models::{data_exports, email_outbox, users},
+};
 
 static welcome: Dir<'_> = include_dir!("src/mailers/auth/welcome");
 static forgot: Dir<'_> = include_dir!("src/mailers/auth/forgot");
 static magic_link: Dir<'_> = include_dir!("src/mailers/auth/magic_link");
+static account_deletion: Dir<'_> = include_dir!("src/mailers/auth/account_deletion");
+static data_export: Dir<'_> = include_dir!("src/mailers/auth/data_export");
+static password_changed: Dir<'_> = include_dir!("src/mailers/auth/password_changed");
+static new_device_login: Dir<'_> = include_dir!("src/mailers/auth/new_device_login");
+static email_verified: Dir<'_> = include_dir!("src/mailers/auth/email_verified");
+static verification_warning: Dir<'_> = include_dir!("src/mailers/auth/verification_warning");
+
+/// Every email sent by [`AuthMailer`], with the data it needs beyond the
+/// user it is sent to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthEmail {
+    Welcome,
+    ForgotPassword,
+    MagicLink,
+    AccountDeletion,
+    DataExport {
+        download_url: String,
+    },
+    PasswordChanged,
+    NewDeviceLogin {
+        ip_address: Option<String>,
+        user_agent: String,
+    },
+    EmailVerified,
+    VerificationWarning {
+        days_left: i64,
+    },
+}
+
+impl AuthEmail {
+    /// Names of every template, as recorded in the email outbox
+    pub const TEMPLATES: [&'static str; 9] = [
+        "welcome",
+        "forgot",
+        "magic_link",
+        "account_deletion",
+        "data_export",
+        "password_changed",
+        "new_device_login",
+        "email_verified",
+        "verification_warning",
+    ];
+
+    /// Name of the template directory below `src/mailers/auth`, which holds
+    /// one subdirectory per locale
+    #[must_use]
+    pub const fn template(&self) -> &'static str {
+        match self {
+            Self::Welcome => "welcome",
+            Self::ForgotPassword => "forgot",
+            Self::MagicLink => "magic_link",
+            Self::AccountDeletion => "account_deletion",
+            Self::DataExport { .. } => "data_export",
+            Self::PasswordChanged => "password_changed",
+            Self::NewDeviceLogin { .. } => "new_device_login",
+            Self::EmailVerified => "email_verified",
+            Self::VerificationWarning { .. } => "verification_warning",
+        }
+    }
+
+    const fn dir(&self) -> &'static Dir<'static> {
+        match self {
+            Self::Welcome => &welcome,
+            Self::ForgotPassword => &forgot,
+            Self::MagicLink => &magic_link,
+            Self::AccountDeletion => &account_deletion,
+            Self::DataExport { .. } => &data_export,
+            Self::PasswordChanged => &password_changed,
+            Self::NewDeviceLogin { .. } => &new_device_login,
+            Self::EmailVerified => &email_verified,
+            Self::VerificationWarning { .. } => &verification_warning,
+        }
+    }
+}
 
 #[allow(clippy::module_name_repetitions)]
 pub struct AuthMailer {}
 impl Mailer for AuthMailer {}
 impl AuthMailer {
-    fn host(ctx: &AppContext) -> String {
-        match ctx.environment {
-            Environment::Development => "http://localhost:3000".to_string(),
-            _ => ctx.config.server.full_url(),
-        }
+    /// The values the templates of the email can reference. Every link is
+    /// built by [`Links`].
+    ///
+    /// # Errors
+    ///
+    /// When the user lacks the token the email links to
+    pub fn locals(
+        ctx: &AppContext,
+        user: &users::Model,
+        email: &AuthEmail,
+    ) -> Result<serde_json::Value> {
+        let links = Links::from_context(ctx)?;
+        let token = |token: &Option<String>| token.as_deref().unwrap_or_default().to_string();
+
+        Ok(match email {
+            AuthEmail::Welcome => json!({
+              "name": user.name,
+              "verifyUrl": links.verify_email(&token(&user.email_verification_token)).to_string(),
+            }),
+            AuthEmail::ForgotPassword => json!({
+              "name": user.name,
+              "resetUrl": links.reset_password(&token(&user.reset_token)).to_string(),
+            }),
+            AuthEmail::MagicLink => {
+                let token = user
+                    .magic_link_token
+                    .as_deref()
+                    .ok_or_else(|| Error::string("the user model not contains magic link token"))?;
+                json!({
+                  "name": user.name,
+                  "magicLinkUrl": links.magic_link(token).to_string(),
+                })
+            }
+            AuthEmail::AccountDeletion => json!({
+              "name": user.name,
+              "cancelUrl": links.cancel_deletion(&token(&user.deletion_token)).to_string(),
+              "graceDays": users::DELETION_GRACE_PERIOD_DAYS,
+            }),
+            AuthEmail::DataExport { download_url } => json!({
+              "name": user.name,
+              "downloadUrl": download_url,
+              "expiresHours": EXPORT_LINK_EXPIRATION_HOURS,
+            }),
+            AuthEmail::PasswordChanged => json!({
+              "name": user.name,
+              "time": Utc::now().to_rfc2822(),
+              "forgotUrl": links.forgot_password().to_string(),
+            }),
+            AuthEmail::NewDeviceLogin {
+                ip_address,
+                user_agent,
+            } => json!({
+              "name": user.name,
+              "time": Utc::now().to_rfc2822(),
+              "userAgent": user_agent,
+              "ipAddress": ip_address.as_deref().unwrap_or("unknown"),
+              "forgotUrl": links.forgot_password().to_string(),
+            }),
+            AuthEmail::EmailVerified => json!({
+              "name": user.name,
+            }),
+            AuthEmail::VerificationWarning { days_left } => json!({
+              "name": user.name,
+              "verifyUrl": links.verify_email(&token(&user.email_verification_token)).to_string(),
+              "daysLeft": days_left,
+            }),
+        })
     }
 
-    /// Sending welcome email the the given user
+    /// Renders the email to the user, in their locale, without sending it
     ///
     /// # Errors
     ///
-    /// When email sending is failed
-    pub async fn send_welcome(ctx: &AppContext, user: &users::Model) -> Result<()> {
-        Self::mail_template(
-            ctx,
-            &welcome,
-            mailer::Args {
-                to: user.email.to_string(),
-                locals: json!({
-                  "name": user.name,
-                  "verifyToken": user.email_verification_token,
-                  "domain": ctx.config.server.full_url()
-                }),
-                ..Default::default()
-            },
+    /// When the email cannot be rendered
+    pub fn render(ctx: &AppContext, user: &users::Model, email: &AuthEmail) -> Result<Email> {
+        let settings = Settings::from_config(&ctx.config)?.mail;
+        let locale = user.preferences().locale;
+        let (locale, dir) =
+            mailers::localized(email.dir(), locale.as_deref(), &settings.default_locale)?;
+        let locals = Self::locals(ctx, user, email)?;
+        layout::render(
+            dir,
+            locale,
+            &settings.branding,
+            &Self::opts().from,
+            &user.email,
+            &locals,
         )
-        .await?;
+    }
 
+    /// Renders the email, records it in the outbox and queues it on the mail
+    /// worker. Sending happens in the worker, so a failing mail server never
+    /// fails the caller. The worker does not send to suppressed addresses.
+    async fn deliver(ctx: &AppContext, user: &users::Model, email: &AuthEmail) -> Result<()> {
+        Self::record(ctx, user, email).await?;
         Ok(())
     }
 
+    /// Like [`Self::deliver`], returning the recorded message
+    async fn record(
+        ctx: &AppContext,
+        user: &users::Model,
+        email: &AuthEmail,
+    ) -> Result<email_outbox::Model> {
+        let rendered = Self::render(ctx, user, email)?;
+        let message =
+            email_outbox::Model::create(&ctx.db, user, email.template(), &rendered).await?;
+        outbox::enqueue(ctx, &message).await;
+        Ok(message)
+    }
+
+    /// Sends a past message again as a new message. The email is rendered
+    /// again for the user, with fresh tokens for the links that expire, since
+    /// the stored copy has its one-time links redacted. Only the new device
+    /// notice, whose details are not kept elsewhere, is copied as stored.
+    ///
+    /// # Errors
+    ///
+    /// When the email can no longer be sent, such as a deletion notice for an
+    /// account that is not pending deletion, or when it cannot be rendered or
+    /// queued
+    pub async fn resend(
+        ctx: &AppContext,
+        message: &email_outbox::Model,
+    ) -> Result<email_outbox::Model> {
+        let user = users::Entity::find_by_id(message.user_id)
+            .one(&ctx.db)
+            .await?
+            .ok_or(Error::NotFound)?;
+        let unavailable = |reason: &str| Err(Error::BadRequest(reason.to_string()));
+
+        let (user, email) = match message.template.as_str() {
+            "welcome" | "verification_warning" => {
+                if user.email_verified_at.is_some() {
+                    return unavailable("the email address is already verified");
+                }
+                let email = if message.template == "welcome" {
+                    AuthEmail::Welcome
+                } else {
+                    let settings = Settings::from_config(&ctx.config)?.unverified_accounts;
+                    AuthEmail::VerificationWarning {
+                        days_left: settings.notice_days(),
+                    }
+                };
+                let user = user
+                    .into_active_model()
+                    .set_email_verification_sent(&ctx.db)
+                    .await?;
+                (user, email)
+            }
+            "forgot" => {
+                let user = user
+                    .into_active_model()
+                    .set_forgot_password_sent(&ctx.db)
+                    .await?;
+                (user, AuthEmail::ForgotPassword)
+            }
+            "magic_link" => {
+                let user = user.into_active_model().create_magic_link(&ctx.db).await?;
+                (user, AuthEmail::MagicLink)
+            }
+            "account_deletion" => {
+                if user.deletion_token.is_none() {
+                    return unavailable("the account is not pending deletion");
+                }
+                (user, AuthEmail::AccountDeletion)
+            }
+            "data_export" => {
+                let Some(export) = data_exports::Model::find_by_user(&ctx.db, &user)
+                    .await?
+                    .into_iter()
+                    .next()
+                else {
+                    return unavailable("the data export has expired");
+                };
+                let download_url = data_export::signed_url(ctx, &export.pid.to_string())?;
+                (user, AuthEmail::DataExport { download_url })
+            }
+            "password_changed" => (user, AuthEmail::PasswordChanged),
+            "email_verified" => (user, AuthEmail::EmailVerified),
+            _ => {
+                let copy = message.copy(&ctx.db).await?;
+                outbox::enqueue(ctx, &copy).await;
+                return Ok(copy);
+            }
+        };
+        Self::record(ctx, &user, &email).await
+    }
+
+    /// Sending welcome email the the given user
+    ///
+    /// # Errors
+    ///
+    /// When the email cannot be rendered or queued
+    pub async fn send_welcome(ctx: &AppContext, user: &users::Model) -> Result<()> {
+        Self::deliver(ctx, user, &AuthEmail::Welcome).await
+    }
+
     /// Sending forgot password email
     ///
     /// # Errors
     ///
-    /// When email sending is failed
+    /// When the email cannot be rendered or queued
     pub async fn forgot_password(ctx: &AppContext, user: &users::Model) -> Result<()> {
-        let host = Self::host(ctx);
-        Self::mail_template(
-            ctx,
-            &forgot,
-            mailer::Args {
-                to: user.email.to_string(),
-                locals: json!({
-                  "name": user.name,
-                  "resetToken": user.reset_token,
-                  "host": host,
-                }),
-                ..Default::default()
-            },
-        )
-        .await?;
-
-        Ok(())
+        Self::deliver(ctx, user, &AuthEmail::ForgotPassword).await
     }
 
     /// Sends a magic link authentication email to the user.
     ///
     /// # Errors
     ///
-    /// When email sending is failed
+    /// When the email cannot be rendered or queued
     pub async fn send_magic_link(ctx: &AppContext, user: &users::Model) -> Result<()> {
-        let host = Self::host(ctx);
-        Self::mail_template(
-            ctx,
-            &magic_link,
-            mailer::Args {
-                to: user.email.to_string(),
-                locals: json!({
-                  "name": user.name,
-                  "token": user.magic_link_token.clone().ok_or_else(|| Error::string(
-                            "the user model not contains magic link token",
-                    ))?,
-                  "host": host
-                }),
-                ..Default::default()
-            },
-        )
-        .await?;
+        Self::deliver(ctx, user, &AuthEmail::MagicLink).await
+    }
 
-        Ok(())
+    /// Sends the account deletion notice with a link to cancel it.
+    ///
+    /// # Errors
+    ///
+    /// When the email cannot be rendered or queued
+    pub async fn send_account_deletion(ctx: &AppContext, user: &users::Model) -> Result<()> {
+        Self::deliver(ctx, user, &AuthEmail::AccountDeletion).await
+    }
+
+    /// Sends the link to download a personal data export.
+    ///
+    /// # Errors
+    ///
+    /// When the email cannot be rendered or queued
+    pub async fn send_data_export(
+        ctx: &AppContext,
+        user: &users::Model,
+        download_url: &str,
+    ) -> Result<()> {
+        let email = AuthEmail::DataExport {
+            download_url: download_url.to_string(),
+        };
+        Self::deliver(ctx, user, &email).await
+    }
+
+    /// Notifies the user that their password was changed. This notice is
+    /// critical and cannot be opted out of.
+    ///
+    /// # Errors
+    ///
+    /// When the email cannot be rendered or queued
+    pub async fn send_password_changed(ctx: &AppContext, user: &users::Model) -> Result<()> {
+        Self::deliver(ctx, user, &AuthEmail::PasswordChanged).await
+    }
+
+    /// Notifies the user of a sign in from a browser they have not used
+    /// before, unless they opted out.
+    ///
+    /// # Errors
+    ///
+    /// When the email cannot be rendered or queued
+    pub async fn send_new_device_login(
+        ctx: &AppContext,
+        user: &users::Model,
+        ip_address: Option<&str>,
+        user_agent: &str,
+    ) -> Result<()> {
+        if !user.preferences().notifications.new_login {
+            return Ok(());
+        }
+
+        let email = AuthEmail::NewDeviceLogin {
+            ip_address: ip_address.map(ToString::to_string),
+            user_agent: user_agent.to_string(),
+        };
+        Self::deliver(ctx, user, &email).await
+    }
+
+    /// Confirms that the email address was verified, unless the user opted
+    /// out.
+    ///
+    /// # Errors
+    ///
+    /// When the email cannot be rendered or queued
+    pub async fn send_email_verified(ctx: &AppContext, user: &users::Model) -> Result<()> {
+        if !user.preferences().notifications.email_verified {
+            return Ok(());
+        }
+
+        Self::deliver(ctx, user, &AuthEmail::EmailVerified).await
+    }
+
+    /// Warns a user who never verified their email address that the account
+    /// will be deleted in `days_left` days.
+    ///
+    /// # Errors
+    ///
+    /// When the email cannot be rendered or queued
+    pub async fn send_verification_warning(
+        ctx: &AppContext,
+        user: &users::Model,
+        days_left: i64,
+    ) -> Result<()> {
+        Self::deliver(ctx, user, &AuthEmail::VerificationWarning { days_left }).await
     }
 }
@@ -0,0 +1,8 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Dear {{ name }},</p>
+<p>We received a request to delete your account. It will be permanently deleted in {{ graceDays }} days.</p>
+<p>If you did not request this, or changed your mind, you can cancel the deletion by clicking the button below:</p>
+{{ ui::button(url=cancelUrl, label="Cancel Account Deletion") }}
+{% endblock content %}
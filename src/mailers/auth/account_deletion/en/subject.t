@@ -0,0 +1 @@
+Your account is scheduled for deletion
@@ -0,0 +1,4 @@
+Your account will be permanently deleted in {{graceDays}} days.
+  Cancel the deletion with the link below:
+
+  {{cancelUrl}}
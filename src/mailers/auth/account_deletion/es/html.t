@@ -0,0 +1,8 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Hola {{ name }},</p>
+<p>Recibimos una solicitud para eliminar tu cuenta. Se eliminará de forma permanente en {{ graceDays }} días.</p>
+<p>Si no la solicitaste, o cambiaste de opinión, puedes cancelar la eliminación con el siguiente botón:</p>
+{{ ui::button(url=cancelUrl, label="Cancelar la eliminación de la cuenta") }}
+{% endblock content %}
@@ -0,0 +1 @@
+Tu cuenta está programada para ser eliminada
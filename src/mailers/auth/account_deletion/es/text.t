@@ -0,0 +1,4 @@
+Tu cuenta se eliminará de forma permanente en {{graceDays}} días.
+  Cancela la eliminación con el siguiente enlace:
+
+  {{cancelUrl}}
@@ -0,0 +1,8 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Dear {{ name }},</p>
+<p>The export of your personal data you requested is ready. You can download it by clicking the button below:</p>
+{{ ui::button(url=downloadUrl, label="Download Your Data") }}
+<p>The link expires in {{ expiresHours }} hours.</p>
+{% endblock content %}
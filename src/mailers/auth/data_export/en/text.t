@@ -0,0 +1,4 @@
+The export of your personal data is ready.
+  Download it with the link below, it expires in {{expiresHours}} hours:
+
+  {{downloadUrl}}
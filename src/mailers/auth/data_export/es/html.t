@@ -0,0 +1,8 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Hola {{ name }},</p>
+<p>La exportación de tus datos personales que solicitaste está lista. Puedes descargarla con el siguiente botón:</p>
+{{ ui::button(url=downloadUrl, label="Descargar tus datos") }}
+<p>El enlace caduca en {{ expiresHours }} horas.</p>
+{% endblock content %}
@@ -0,0 +1 @@
+Tu exportación de datos está lista
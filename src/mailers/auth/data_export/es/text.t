@@ -0,0 +1,4 @@
+La exportación de tus datos personales está lista.
+  Descárgala con el siguiente enlace, caduca en {{expiresHours}} horas:
+
+  {{downloadUrl}}
@@ -0,0 +1,6 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Dear {{ name }},</p>
+<p>Thanks for verifying your email address. Your account is all set.</p>
+{% endblock content %}
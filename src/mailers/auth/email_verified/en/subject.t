@@ -0,0 +1 @@
+Your email address is verified
@@ -0,0 +1 @@
+Thanks for verifying your email address, {{name}}. Your account is all set.
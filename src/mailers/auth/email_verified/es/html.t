@@ -0,0 +1,6 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Hola {{ name }},</p>
+<p>Gracias por verificar tu correo electrónico. Tu cuenta está lista.</p>
+{% endblock content %}
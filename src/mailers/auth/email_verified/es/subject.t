@@ -0,0 +1 @@
+Tu correo electrónico está verificado
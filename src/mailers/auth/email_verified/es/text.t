@@ -0,0 +1 @@
+Gracias por verificar tu correo electrónico, {{name}}. Tu cuenta está lista.
@@ -0,0 +1,8 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Hey {{ name }},</p>
+<p>Forgot your password? No worries! You can reset it by clicking the button below:</p>
+{{ ui::button(url=resetUrl, label="Reset Your Password") }}
+<p>If you didn't request a password reset, please ignore this email.</p>
+{% endblock content %}
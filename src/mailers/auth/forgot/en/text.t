@@ -0,0 +1,3 @@
+Reset your password with this link:
+
+{{resetUrl}}
@@ -0,0 +1,8 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Hola {{ name }},</p>
+<p>¿Olvidaste tu contraseña? ¡No te preocupes! Puedes restablecerla con el siguiente botón:</p>
+{{ ui::button(url=resetUrl, label="Restablecer tu contraseña") }}
+<p>Si no solicitaste restablecer tu contraseña, ignora este correo.</p>
+{% endblock content %}
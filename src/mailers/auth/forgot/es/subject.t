@@ -0,0 +1 @@
+Tu enlace para restablecer la contraseña
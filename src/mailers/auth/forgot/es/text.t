@@ -0,0 +1,3 @@
+Restablece tu contraseña con este enlace:
+
+{{resetUrl}}
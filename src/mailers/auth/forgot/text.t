@@ -1,3 +0,0 @@
-Reset your password with this link:
-
-<a href="{{host}}/forgot?token={{resetToken}}" target="_blank">
@@ -0,0 +1,8 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Hey {{ name }},</p>
+<p>Sign in to your account with the button below:</p>
+{{ ui::button(url=magicLinkUrl, label="Sign In") }}
+<p>If you didn't request this link, please ignore this email.</p>
+{% endblock content %}
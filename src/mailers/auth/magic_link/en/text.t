@@ -0,0 +1,2 @@
+Magic link with this link:
+{{magicLinkUrl}}
@@ -0,0 +1,8 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Hola {{ name }},</p>
+<p>Inicia sesión en tu cuenta con el siguiente botón:</p>
+{{ ui::button(url=magicLinkUrl, label="Iniciar sesión") }}
+<p>Si no solicitaste este enlace, ignora este correo.</p>
+{% endblock content %}
@@ -0,0 +1 @@
+Tu enlace mágico
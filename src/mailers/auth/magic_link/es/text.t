@@ -0,0 +1,2 @@
+Inicia sesión con este enlace:
+{{magicLinkUrl}}
@@ -1,8 +0,0 @@
-;<html>
-<body>
-<p>Magic link example:</p>
-<a href="{{host}}/magic-link?token={{token}}" target="_blank">
-Verify Your Account
-</a>
-</body>
-</html>
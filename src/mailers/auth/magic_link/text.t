@@ -1,2 +0,0 @@
-Magic link with this link:
-{{host}}/magic-link?token={{token}}
@@ -0,0 +1,13 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Dear {{ name }},</p>
+<p>Your account was just signed in to from a new browser or device:</p>
+<ul>
+  <li>Time: {{ time }}</li>
+  <li>Browser: {{ userAgent }}</li>
+  <li>IP address: {{ ipAddress }}</li>
+</ul>
+<p>If this was you, there is nothing else to do. If not, reset your password right away by clicking the button below:</p>
+{{ ui::button(url=forgotUrl, label="Reset Your Password") }}
+{% endblock content %}
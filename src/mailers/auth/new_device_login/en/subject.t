@@ -0,0 +1 @@
+New sign-in to your account
@@ -0,0 +1,8 @@
+Your account was just signed in to from a new browser or device.
+  Time: {{time}}
+  Browser: {{userAgent}}
+  IP address: {{ipAddress}}
+
+  If this was not you, reset your password right away:
+
+  {{forgotUrl}}
@@ -0,0 +1,13 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Hola {{ name }},</p>
+<p>Se acaba de iniciar sesión en tu cuenta desde un navegador o dispositivo nuevo:</p>
+<ul>
+  <li>Hora: {{ time }}</li>
+  <li>Navegador: {{ userAgent }}</li>
+  <li>Dirección IP: {{ ipAddress }}</li>
+</ul>
+<p>Si fuiste tú, no tienes que hacer nada más. Si no, restablece tu contraseña de inmediato con el siguiente botón:</p>
+{{ ui::button(url=forgotUrl, label="Restablecer tu contraseña") }}
+{% endblock content %}
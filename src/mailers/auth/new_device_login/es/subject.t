@@ -0,0 +1 @@
+Nuevo inicio de sesión en tu cuenta
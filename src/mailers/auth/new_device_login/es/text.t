@@ -0,0 +1,8 @@
+Se acaba de iniciar sesión en tu cuenta desde un navegador o dispositivo nuevo.
+  Hora: {{time}}
+  Navegador: {{userAgent}}
+  Dirección IP: {{ipAddress}}
+
+  Si no fuiste tú, restablece tu contraseña de inmediato:
+
+  {{forgotUrl}}
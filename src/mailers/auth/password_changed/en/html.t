@@ -0,0 +1,8 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Dear {{ name }},</p>
+<p>The password of your account was changed on {{ time }}.</p>
+<p>If you did not make this change, reset your password right away by clicking the button below:</p>
+{{ ui::button(url=forgotUrl, label="Reset Your Password") }}
+{% endblock content %}
@@ -0,0 +1 @@
+Your password was changed
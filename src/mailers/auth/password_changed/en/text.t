@@ -0,0 +1,4 @@
+The password of your account was changed on {{time}}.
+  If you did not make this change, reset your password right away:
+
+  {{forgotUrl}}
@@ -0,0 +1,8 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Hola {{ name }},</p>
+<p>La contraseña de tu cuenta se cambió el {{ time }}.</p>
+<p>Si no hiciste este cambio, restablece tu contraseña de inmediato con el siguiente botón:</p>
+{{ ui::button(url=forgotUrl, label="Restablecer tu contraseña") }}
+{% endblock content %}
@@ -0,0 +1 @@
+Tu contraseña fue cambiada
@@ -0,0 +1,4 @@
+La contraseña de tu cuenta se cambió el {{time}}.
+  Si no hiciste este cambio, restablece tu contraseña de inmediato:
+
+  {{forgotUrl}}
@@ -0,0 +1,8 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Dear {{ name }},</p>
+<p>You signed up for {{ brand.product_name }}, but your email address is still not verified.</p>
+<p>Your account will be deleted in {{ daysLeft }} days unless you verify it by clicking the button below:</p>
+{{ ui::button(url=verifyUrl, label="Verify Your Account") }}
+{% endblock content %}
@@ -0,0 +1 @@
+Verify your email or your account will be deleted
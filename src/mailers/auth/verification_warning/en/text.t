@@ -0,0 +1,4 @@
+Dear {{name}}, your email address is still not verified.
+  Your account will be deleted in {{daysLeft}} days unless you verify it with the link below:
+
+  {{verifyUrl}}
@@ -0,0 +1,8 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Hola {{ name }},</p>
+<p>Te registraste en {{ brand.product_name }}, pero tu dirección de correo todavía no está verificada.</p>
+<p>Tu cuenta será eliminada en {{ daysLeft }} días si no la verificas con el siguiente botón:</p>
+{{ ui::button(url=verifyUrl, label="Verificar tu cuenta") }}
+{% endblock content %}
@@ -0,0 +1 @@
+Verifica tu correo o tu cuenta será eliminada
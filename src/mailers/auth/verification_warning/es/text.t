@@ -0,0 +1,4 @@
+Hola {{name}}, tu dirección de correo todavía no está verificada.
+  Tu cuenta será eliminada en {{daysLeft}} días si no la verificas con el siguiente enlace:
+
+  {{verifyUrl}}
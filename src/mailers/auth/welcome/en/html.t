@@ -0,0 +1,8 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Dear {{ name }},</p>
+<p>Welcome to {{ brand.product_name }}! You can now log in to your account.</p>
+<p>Before you get started, please verify your account by clicking the button below:</p>
+{{ ui::button(url=verifyUrl, label="Verify Your Account") }}
+{% endblock content %}
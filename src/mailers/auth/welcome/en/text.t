@@ -1,4 +1,4 @@
 Welcome {{name}}, you can now log in.
   Verify your account with the link below:
 
-  {{domain}}/api/auth/verify/{{verifyToken}}
+  {{verifyUrl}}
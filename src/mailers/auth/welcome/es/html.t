@@ -0,0 +1,8 @@
+{% extends "layout.html" %}
+{% import "partials/button.html" as ui %}
+{% block content %}
+<p>Hola {{ name }},</p>
+<p>¡Bienvenido a {{ brand.product_name }}! Ya puedes iniciar sesión en tu cuenta.</p>
+<p>Antes de empezar, verifica tu cuenta con el siguiente botón:</p>
+{{ ui::button(url=verifyUrl, label="Verificar tu cuenta") }}
+{% endblock content %}
@@ -0,0 +1 @@
+Bienvenido, {{name}}
@@ -0,0 +1,4 @@
+Bienvenido, {{name}}, ya puedes iniciar sesión.
+  Verifica tu cuenta con el siguiente enlace:
+
+  {{verifyUrl}}
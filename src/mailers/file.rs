@@ -0,0 +1,148 @@
+//! Mail transport that writes every email into a directory instead of
+//! sending it, so development needs no mail server and the emails can be
+//! opened in a mail client.
+//!
+//! Each email is written as `<id>.eml`, the message exactly as it would go
+//! over SMTP, and listed as one JSON line in `index.jsonl` with its plain
+//! text body, so readers do not have to decode the MIME parts.
+
+use std::path::{Path, PathBuf};
+
+use lettre::{message::MultiPart, Message};
+use loco_rs::{mailer::Email, Error, Result};
+use tokio::{fs, io::AsyncWriteExt, sync::Mutex};
+
+use super::Delivery;
+
+/// Name of the index file in the mail directory
+pub const INDEX: &str = "index.jsonl";
+
+/// Serializes appends to the index within the process
+static INDEX_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Writes emails into a directory. See the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct FileTransport {
+    path: PathBuf,
+}
+
+fn io_err(path: &Path, err: &std::io::Error) -> Error {
+    Error::Message(format!("mail file {}: {err}", path.display()))
+}
+
+impl FileTransport {
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Directory the emails are written to
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes the email and adds it to the index. Returns its id.
+    ///
+    /// # Errors
+    ///
+    /// When an address cannot be parsed or a file cannot be written
+    pub async fn send(&self, email: &Email) -> Result<String> {
+        let from = email
+            .from
+            .clone()
+            .unwrap_or_else(|| loco_rs::mailer::DEFAULT_FROM_SENDER.to_string());
+        let parse_err = |err: lettre::address::AddressError| {
+            Error::Message(format!("invalid email address: {err}"))
+        };
+        let mut builder = Message::builder()
+            .from(from.parse().map_err(parse_err)?)
+            .to(email.to.parse().map_err(parse_err)?);
+        if let Some(bcc) = &email.bcc {
+            builder = builder.bcc(bcc.parse().map_err(parse_err)?);
+        }
+        if let Some(cc) = &email.cc {
+            builder = builder.cc(cc.parse().map_err(parse_err)?);
+        }
+        if let Some(reply_to) = &email.reply_to {
+            builder = builder.reply_to(reply_to.parse().map_err(parse_err)?);
+        }
+        let message = builder
+            .subject(email.subject.clone())
+            .multipart(MultiPart::alternative_plain_html(
+                email.text.clone(),
+                email.html.clone(),
+            ))
+            .map_err(|err| Error::Message(format!("could not build email: {err}")))?;
+
+        let delivery = Delivery::new(from, email);
+
+        fs::create_dir_all(&self.path)
+            .await
+            .map_err(|err| io_err(&self.path, &err))?;
+        let file = self.path.join(format!("{}.eml", delivery.id));
+        fs::write(&file, message.formatted())
+            .await
+            .map_err(|err| io_err(&file, &err))?;
+
+        let mut line = serde_json::to_string(&delivery)?;
+        line.push('\n');
+        let index = self.path.join(INDEX);
+        let _guard = INDEX_LOCK.lock().await;
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&index)
+            .await
+            .map_err(|err| io_err(&index, &err))?
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|err| io_err(&index, &err))?;
+
+        Ok(delivery.id)
+    }
+
+    /// Every email in the index, oldest first. Empty when nothing was sent.
+    ///
+    /// # Errors
+    ///
+    /// When the index cannot be read or parsed
+    pub async fn deliveries(&self) -> Result<Vec<Delivery>> {
+        let index = self.path.join(INDEX);
+        let content = match fs::read_to_string(&index).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(io_err(&index, &err)),
+        };
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(Error::from))
+            .collect()
+    }
+
+    /// The indexed email with the given id
+    ///
+    /// # Errors
+    ///
+    /// When the index cannot be read or parsed
+    pub async fn find(&self, id: &str) -> Result<Option<Delivery>> {
+        Ok(self
+            .deliveries()
+            .await?
+            .into_iter()
+            .find(|delivery| delivery.id == id))
+    }
+
+    /// The raw `.eml` content of the email with the given id
+    ///
+    /// # Errors
+    ///
+    /// When the file cannot be read
+    pub async fn read(&self, id: &str) -> Result<String> {
+        let file = self.path.join(format!("{id}.eml"));
+        fs::read_to_string(&file)
+            .await
+            .map_err(|err| io_err(&file, &err))
+    }
+}
@@ -0,0 +1,87 @@
+//! The shared HTML layout of mailer templates.
+//!
+//! An `html.t` template extends `layout.html` and fills its `content` block.
+//! It can import the `button` macro from `partials/button.html`. The header
+//! and the localized footer are included by the layout. Every template sees
+//! the `brand` values from [`Branding`] and the `locale` it is rendered in.
+//! The CSS of the layout is inlined into style attributes after rendering,
+//! since many email clients drop `<style>` tags.
+
+use css_inline::CSSInliner;
+use include_dir::{include_dir, Dir};
+use loco_rs::{mailer::Email, Error, Result};
+use tera::{Context, Tera};
+
+use super::{error_chain, render_file, template, Branding};
+
+static LAYOUT: Dir<'_> = include_dir!("src/mailers/layout");
+
+/// Renders the `subject.t`, `text.t` and `html.t` templates of a mailer
+/// directory into an email, with the HTML part wrapped in the layout.
+///
+/// # Errors
+///
+/// When a template is missing or cannot be rendered, or the CSS cannot be
+/// inlined
+pub fn render(
+    dir: &Dir<'_>,
+    locale: &str,
+    branding: &Branding,
+    from: &str,
+    to: &str,
+    locals: &serde_json::Value,
+) -> Result<Email> {
+    let mut context =
+        Context::from_serialize(locals).map_err(|err| Error::Message(err.to_string()))?;
+    context.insert("brand", branding);
+    context.insert("locale", locale);
+
+    Ok(Email {
+        from: Some(from.to_string()),
+        to: to.to_string(),
+        subject: render_file(dir, "subject.t", &context)?,
+        text: render_file(dir, "text.t", &context)?,
+        html: inline_css(&render_html(dir, locale, &context)?)?,
+        ..Default::default()
+    })
+}
+
+fn render_html(dir: &Dir<'_>, locale: &str, context: &Context) -> Result<String> {
+    let tera_err = |err: tera::Error| Error::Message(format!("html.t: {}", error_chain(&err)));
+
+    let mut tera = Tera::default();
+    // Matches how the text and subject templates are rendered.
+    tera.autoescape_on(vec![]);
+    tera.add_raw_templates(vec![
+        ("layout.html", template(&LAYOUT, "layout.html")?),
+        (
+            "partials/header.html",
+            template(&LAYOUT, "partials/header.html")?,
+        ),
+        (
+            "partials/button.html",
+            template(&LAYOUT, "partials/button.html")?,
+        ),
+        (
+            "partials/footer.html",
+            template(&LAYOUT, &format!("partials/footer/{locale}.html"))?,
+        ),
+        ("html.t", template(dir, "html.t")?),
+    ])
+    .map_err(tera_err)?;
+    tera.render("html.t", context).map_err(tera_err)
+}
+
+/// Moves the rules of `<style>` tags into the style attributes of the
+/// elements they match.
+///
+/// # Errors
+///
+/// When the HTML cannot be parsed
+pub fn inline_css(html: &str) -> Result<String> {
+    CSSInliner::options()
+        .load_remote_stylesheets(false)
+        .build()
+        .inline(html)
+        .map_err(|err| Error::Message(format!("could not inline mailer CSS: {err}")))
+}
@@ -0,0 +1,51 @@
+//! Mail transport that keeps every email in memory instead of sending it, so
+//! tests can read what was sent without a mail server or files shared with
+//! other runs.
+//!
+//! Registered in the shared store at boot, get it with
+//! [`MemoryTransport::from_context`]. Each application context has its own.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use loco_rs::{app::AppContext, mailer::Email};
+
+use super::Delivery;
+
+/// Keeps emails in memory. See the [module docs](self).
+#[derive(Debug, Clone, Default)]
+pub struct MemoryTransport {
+    deliveries: Arc<Mutex<Vec<Delivery>>>,
+}
+
+impl MemoryTransport {
+    /// The transport of the application. Falls back to a fresh transport,
+    /// which nothing reads, when none was registered.
+    #[must_use]
+    pub fn from_context(ctx: &AppContext) -> Self {
+        ctx.shared_store.get::<Self>().unwrap_or_default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Delivery>> {
+        self.deliveries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Keeps the email. Returns its id.
+    pub fn send(&self, email: &Email) -> String {
+        let from = email
+            .from
+            .clone()
+            .unwrap_or_else(|| loco_rs::mailer::DEFAULT_FROM_SENDER.to_string());
+        let delivery = Delivery::new(from, email);
+        let id = delivery.id.clone();
+        self.lock().push(delivery);
+        id
+    }
+
+    /// Every email kept, oldest first
+    #[must_use]
+    pub fn deliveries(&self) -> Vec<Delivery> {
+        self.lock().clone()
+    }
+}
@@ -1 +1,254 @@
+use std::{path::PathBuf, time::Duration};
+
+use chrono::{DateTime, Utc};
+use include_dir::Dir;
+use loco_rs::{mailer::Email, Error, Result};
+use serde::{Deserialize, Serialize};
+use tera::{Context, Tera};
+use uuid::Uuid;
+
 pub mod auth;
+pub mod file;
+pub mod layout;
+pub mod memory;
+pub mod outbox;
+pub mod preview;
+
+/// Locales every mailer template is translated to, each in a subdirectory of
+/// the template named after it.
+pub const LOCALES: [&str; 2] = ["en", "es"];
+
+/// Longest a single send may take, the default SMTP timeout. Attempts that
+/// take longer are abandoned as failed.
+pub const SEND_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Values the mailer layout brands emails with, read from
+/// `settings.mail.branding`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Branding {
+    pub product_name: String,
+    /// Shown in the header instead of the product name when set
+    pub logo_url: Option<String>,
+    /// Linked from the footer when set
+    pub support_email: Option<String>,
+    /// Color of the header and buttons
+    pub primary_color: String,
+    pub text_color: String,
+    pub background_color: String,
+}
+
+impl Default for Branding {
+    fn default() -> Self {
+        Self {
+            product_name: "Loco".to_string(),
+            logo_url: None,
+            support_email: None,
+            primary_color: "#e11d48".to_string(),
+            text_color: "#1f2937".to_string(),
+            background_color: "#f3f4f6".to_string(),
+        }
+    }
+}
+
+/// Transport, retry behaviour, default locale, branding and inbound
+/// notifications of mail, read from `settings.mail`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MailSettings {
+    pub transport: MailTransport,
+    /// Attempts before an email is given up on
+    pub max_attempts: u32,
+    /// Wait before the first retry. Doubles after every failed attempt.
+    pub retry_base_ms: u64,
+    /// Locale used when the recipient has none or it is not supported
+    pub default_locale: String,
+    pub branding: Branding,
+    /// Secret that bounce and complaint notifications must pass as the
+    /// `token` query parameter. Notifications are rejected while unset.
+    pub events_token: Option<String>,
+}
+
+impl Default for MailSettings {
+    fn default() -> Self {
+        Self {
+            transport: MailTransport::default(),
+            max_attempts: 3,
+            retry_base_ms: 1000,
+            default_locale: LOCALES[0].to_string(),
+            branding: Branding::default(),
+            events_token: None,
+        }
+    }
+}
+
+/// How emails leave the application.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(tag = "driver", rename_all = "snake_case")]
+pub enum MailTransport {
+    /// Sent with the `mailer` section of the configuration: over SMTP, or
+    /// to its in-memory stub when `mailer.stub` is set.
+    #[default]
+    Smtp,
+    /// Written below `path` by [`file::FileTransport`].
+    File { path: PathBuf },
+    /// Kept in the process by [`memory::MemoryTransport`], for tests.
+    Memory,
+}
+
+/// An email sent by [`file::FileTransport`] or [`memory::MemoryTransport`]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Delivery {
+    /// Also the name of the `.eml` file of the file transport, without
+    /// extension. Recorded as the provider message id of the outbox message.
+    pub id: String,
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+    pub sent_at: DateTime<Utc>,
+}
+
+impl Delivery {
+    /// A delivery of the email from `from`, with a new id
+    #[must_use]
+    pub fn new(from: String, email: &Email) -> Self {
+        let sent_at = Utc::now();
+        Self {
+            id: format!("{}-{}", sent_at.format("%Y%m%dT%H%M%S"), Uuid::new_v4()),
+            from,
+            to: email.to.clone(),
+            subject: email.subject.clone(),
+            text: email.text.clone(),
+            sent_at,
+        }
+    }
+}
+
+impl MailSettings {
+    /// Wait after the given failed attempt, counting from 1
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(self.retry_base_ms.saturating_mul(factor))
+    }
+
+    /// How long a dispatcher keeps its claim on a message: long enough for
+    /// every attempt and the waits between them. A message still being sent
+    /// after that is taken to be abandoned and can be claimed again.
+    #[must_use]
+    pub fn claim_lease(&self) -> Duration {
+        let attempts = self.max_attempts.max(1);
+        (1..attempts)
+            .map(|attempt| self.backoff(attempt))
+            .sum::<Duration>()
+            + SEND_TIMEOUT.saturating_mul(attempts)
+    }
+}
+
+/// The supported locale matching a language tag, trying the full tag first
+/// and then its primary language, so `es-MX` falls back to `es`.
+#[must_use]
+pub fn supported_locale(tag: &str) -> Option<&'static str> {
+    let tag = tag.trim();
+    let primary = tag.split('-').next().unwrap_or(tag);
+    [tag, primary].into_iter().find_map(|candidate| {
+        LOCALES
+            .into_iter()
+            .find(|locale| locale.eq_ignore_ascii_case(candidate))
+    })
+}
+
+/// The preferred supported locale of an `Accept-Language` header, honouring
+/// quality values.
+#[must_use]
+pub fn accept_language(header: &str) -> Option<&'static str> {
+    let mut languages = header
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let tag = parts.next()?.trim();
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            (quality > 0.0).then_some((tag, quality))
+        })
+        .collect::<Vec<_>>();
+    languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+    languages
+        .into_iter()
+        .find_map(|(tag, _)| supported_locale(tag))
+}
+
+/// The templates of a mailer directory in the given locale, falling back to
+/// the default locale when the locale is missing or not supported. Returns
+/// the locale the templates are in along with them.
+///
+/// # Errors
+///
+/// When the directory has no templates for the default locale
+pub fn localized<'a>(
+    dir: &'a Dir<'a>,
+    locale: Option<&str>,
+    default_locale: &'a str,
+) -> Result<(&'a str, &'a Dir<'a>)> {
+    let child = |locale: &str| dir.get_dir(dir.path().join(locale));
+    let templates = locale
+        .and_then(supported_locale)
+        .and_then(child)
+        .or_else(|| child(default_locale))
+        .ok_or_else(|| {
+            Error::Message(format!(
+                "no mailer templates for locale {default_locale} in {}",
+                dir.path().display()
+            ))
+        })?;
+    let locale = templates
+        .path()
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(default_locale);
+    Ok((locale, templates))
+}
+
+fn template<'a>(dir: &'a Dir<'a>, name: &str) -> Result<&'a str> {
+    dir.get_file(dir.path().join(name))
+        .and_then(|file| file.contents_utf8())
+        .ok_or_else(|| Error::Message(format!("no mailer template file found {name}")))
+}
+
+fn render_file(dir: &Dir<'_>, name: &str, context: &Context) -> Result<String> {
+    Tera::one_off(template(dir, name)?, context, false)
+        .map_err(|err| Error::Message(format!("{name}: {}", error_chain(&err))))
+}
+
+/// Tera reports the actual problem, such as a variable missing from the
+/// context, in the source of its errors rather than the top level message.
+fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// Renders the `subject.t`, `text.t` and `html.t` templates of a mailer
+/// directory into an email, without sending it.
+///
+/// # Errors
+///
+/// When a template is missing or cannot be rendered
+pub fn render(dir: &Dir<'_>, from: &str, to: &str, locals: &serde_json::Value) -> Result<Email> {
+    let context = Context::from_serialize(locals).map_err(|err| Error::Message(err.to_string()))?;
+    Ok(Email {
+        from: Some(from.to_string()),
+        to: to.to_string(),
+        subject: render_file(dir, "subject.t", &context)?,
+        text: render_file(dir, "text.t", &context)?,
+        html: render_file(dir, "html.t", &context)?,
+        ..Default::default()
+    })
+}
@@ -0,0 +1,269 @@
+//! Delivery of the messages recorded in the `email_outbox` table.
+//!
+//! [`dispatch`] first claims a message for a limited time, so concurrent
+//! dispatchers never send it twice, then sends it without holding any lock
+//! or transaction. A message whose dispatcher died mid-send is claimed again
+//! once the claim expires. Messages to addresses suppressed by then are
+//! marked `suppressed` instead of being sent.
+//!
+//! Once a message is sent or has failed for good, the one-time secrets in
+//! its stored bodies, such as password reset tokens and link signatures, are
+//! replaced by [`REDACTED`] so the outbox never keeps usable links.
+
+use loco_rs::{
+    mailer::{Email, EmailSender},
+    prelude::*,
+};
+use sea_orm::EntityTrait;
+use uuid::Uuid;
+
+use super::{
+    auth::AuthMailer, file::FileTransport, memory::MemoryTransport, MailSettings, MailTransport,
+    SEND_TIMEOUT,
+};
+use crate::{
+    common::settings::Settings,
+    models::{email_outbox, email_suppressions, users},
+    workers::mailer::{MailWorker, MailWorkerArgs},
+};
+
+/// Messages loaded per round by [`drain`]
+const DRAIN_BATCH: u64 = 100;
+
+/// Stands in for the secrets removed from stored messages
+pub const REDACTED: &str = "[redacted]";
+
+/// Query parameter of signed links, see [`crate::common::links`]
+const SIGNATURE_PARAM: &str = "signature=";
+
+/// One-time tokens of the user that emails link to. Tokens rotated since a
+/// message was rendered no longer work, so only the current ones matter.
+fn secrets(user: &users::Model) -> Vec<&str> {
+    [
+        &user.email_verification_token,
+        &user.reset_token,
+        &user.magic_link_token,
+        &user.deletion_token,
+    ]
+    .into_iter()
+    .filter_map(Option::as_deref)
+    .filter(|token| !token.is_empty())
+    .collect()
+}
+
+/// Replaces the secrets and the signatures of signed links in a body
+fn redact(body: &str, secrets: &[&str]) -> String {
+    let body = secrets.iter().fold(body.to_string(), |body, secret| {
+        body.replace(secret, REDACTED)
+    });
+
+    let mut redacted = String::with_capacity(body.len());
+    let mut rest = body.as_str();
+    while let Some(at) = rest.find(SIGNATURE_PARAM) {
+        let (before, after) = rest.split_at(at + SIGNATURE_PARAM.len());
+        redacted.push_str(before);
+        let end = after
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(after.len());
+        if end > 0 {
+            redacted.push_str(REDACTED);
+        }
+        rest = &after[end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+/// How a claimed message was handled
+enum Outcome {
+    Sent {
+        attempts: u32,
+        /// Error of the previous attempt, if it was retried
+        last_error: Option<String>,
+        provider_message_id: Option<String>,
+    },
+    Failed {
+        attempts: u32,
+        error: String,
+    },
+    Suppressed,
+}
+
+/// Where messages are sent, chosen by `settings.mail.transport`
+enum Sender<'a> {
+    Mailer(&'a EmailSender),
+    File(FileTransport),
+    Memory(MemoryTransport),
+}
+
+impl Sender<'_> {
+    /// Sends the email, returning the message id the transport assigned, if
+    /// any. Gives up after [`SEND_TIMEOUT`], which the claim lease relies on.
+    async fn send(&self, email: &Email) -> Result<Option<String>> {
+        let send = async {
+            match self {
+                Self::Mailer(sender) => {
+                    sender.mail(email).await?;
+                    Ok(None)
+                }
+                Self::File(transport) => transport.send(email).await.map(Some),
+                Self::Memory(transport) => Ok(Some(transport.send(email))),
+            }
+        };
+        tokio::time::timeout(SEND_TIMEOUT, send)
+            .await
+            .map_err(|_| {
+                Error::Message(format!(
+                    "sending timed out after {}s",
+                    SEND_TIMEOUT.as_secs()
+                ))
+            })?
+    }
+}
+
+/// Sends the email, retrying with exponential backoff until the mail server
+/// accepts it or the attempts run out
+async fn send(pid: Uuid, sender: &Sender<'_>, email: &Email, settings: &MailSettings) -> Outcome {
+    let mut attempt = 1;
+    let mut last_error = None;
+    loop {
+        match sender.send(email).await {
+            Ok(provider_message_id) => {
+                tracing::info!(outbox_pid = pid.to_string(), attempt, "email sent");
+                return Outcome::Sent {
+                    attempts: attempt,
+                    last_error,
+                    provider_message_id,
+                };
+            }
+            Err(err) if attempt >= settings.max_attempts.max(1) => {
+                tracing::error!(
+                    outbox_pid = pid.to_string(),
+                    attempt,
+                    error = err.to_string(),
+                    "email could not be sent"
+                );
+                return Outcome::Failed {
+                    attempts: attempt,
+                    error: err.to_string(),
+                };
+            }
+            Err(err) => {
+                tracing::warn!(
+                    outbox_pid = pid.to_string(),
+                    attempt,
+                    error = err.to_string(),
+                    "email failed, retrying"
+                );
+                last_error = Some(err.to_string());
+                tokio::time::sleep(settings.backoff(attempt)).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Queues a recorded message on the mail worker. A failure to queue is
+/// logged rather than returned, the message stays `queued` and is picked up
+/// by the next [`drain`].
+pub async fn enqueue(ctx: &AppContext, message: &email_outbox::Model) {
+    let args = MailWorkerArgs {
+        outbox_pid: message.pid.to_string(),
+    };
+    if let Err(err) = MailWorker::perform_later(ctx, args).await {
+        tracing::error!(
+            outbox_pid = message.pid.to_string(),
+            error = err.to_string(),
+            "could not queue email"
+        );
+    }
+}
+
+/// Sends a queued message, retrying with exponential backoff until the mail
+/// server accepts it or the attempts run out, and records the outcome.
+/// Messages to suppressed addresses are marked `suppressed` without sending.
+/// Returns `None` when the message is not queued or is being sent by another
+/// dispatcher.
+///
+/// # Errors
+///
+/// When DB query error
+pub async fn dispatch(ctx: &AppContext, pid: Uuid) -> Result<Option<email_outbox::Model>> {
+    let settings = Settings::from_config(&ctx.config)?.mail;
+    let sender = match &settings.transport {
+        MailTransport::Smtp => Sender::Mailer(ctx.mailer.as_ref().ok_or_else(|| {
+            Error::Message("attempting to send email but no email sender configured".to_string())
+        })?),
+        MailTransport::File { path } => Sender::File(FileTransport::new(path)),
+        MailTransport::Memory => Sender::Memory(MemoryTransport::from_context(ctx)),
+    };
+
+    let Some(message) = email_outbox::Model::claim(&ctx.db, pid, settings.claim_lease()).await?
+    else {
+        return Ok(None);
+    };
+
+    let outcome = if email_suppressions::Model::is_suppressed(&ctx.db, &message.recipient).await? {
+        tracing::warn!(
+            outbox_pid = pid.to_string(),
+            template = message.template,
+            "email not sent, the recipient address is suppressed"
+        );
+        Outcome::Suppressed
+    } else {
+        let email = message.email(&AuthMailer::opts().from);
+        send(pid, &sender, &email, &settings).await
+    };
+
+    let user = users::Entity::find_by_id(message.user_id)
+        .one(&ctx.db)
+        .await?;
+    let secrets = user.as_ref().map(secrets).unwrap_or_default();
+    let text = redact(&message.text, &secrets);
+    let html = redact(&message.html, &secrets);
+    let message = message.into_active_model().redact(text, html);
+    let message = match outcome {
+        Outcome::Sent {
+            attempts,
+            last_error,
+            provider_message_id,
+        } => {
+            message
+                .mark_sent(
+                    &ctx.db,
+                    attempts,
+                    last_error.as_deref(),
+                    provider_message_id,
+                )
+                .await?
+        }
+        Outcome::Failed { attempts, error } => {
+            message.mark_failed(&ctx.db, attempts, &error).await?
+        }
+        Outcome::Suppressed => message.mark_suppressed(&ctx.db).await?,
+    };
+    Ok(Some(message))
+}
+
+/// Dispatches every queued message, oldest first. Returns how many were
+/// handled by this call.
+///
+/// # Errors
+///
+/// When DB query error
+pub async fn drain(ctx: &AppContext) -> Result<usize> {
+    let mut after = 0;
+    let mut handled = 0;
+    loop {
+        let batch = email_outbox::Model::queued(&ctx.db, after, DRAIN_BATCH).await?;
+        let Some(last) = batch.last() else {
+            return Ok(handled);
+        };
+        after = last.id;
+        for message in batch {
+            if dispatch(ctx, message.pid).await?.is_some() {
+                handled += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Renders the auth mailer templates with sample data, for the development
+//! preview routes and the `preview_mailer` task.
+
+use chrono::{TimeZone, Utc};
+use loco_rs::{mailer::Email, prelude::*};
+
+use crate::{
+    mailers::auth::{AuthEmail, AuthMailer},
+    models::users,
+};
+
+/// The email of each template, with sample data where the email needs more
+/// than the user.
+#[must_use]
+pub fn sample(template: &str) -> Option<AuthEmail> {
+    Some(match template {
+        "welcome" => AuthEmail::Welcome,
+        "forgot" => AuthEmail::ForgotPassword,
+        "magic_link" => AuthEmail::MagicLink,
+        "account_deletion" => AuthEmail::AccountDeletion,
+        "data_export" => AuthEmail::DataExport {
+            download_url: "http://localhost:5150/api/account/export/sample".to_string(),
+        },
+        "password_changed" => AuthEmail::PasswordChanged,
+        "new_device_login" => AuthEmail::NewDeviceLogin {
+            ip_address: Some("203.0.113.7".to_string()),
+            user_agent: "Mozilla/5.0 (X11; Linux x86_64) Firefox/130.0".to_string(),
+        },
+        "email_verified" => AuthEmail::EmailVerified,
+        "verification_warning" => AuthEmail::VerificationWarning { days_left: 7 },
+        _ => return None,
+    })
+}
+
+/// A user that is never stored, with every token the templates link to set.
+#[must_use]
+pub fn sample_user() -> users::Model {
+    let now = Utc
+        .with_ymd_and_hms(2026, 1, 1, 12, 0, 0)
+        .unwrap()
+        .fixed_offset();
+    users::Model {
+        created_at: now,
+        updated_at: now,
+        id: 0,
+        pid: Uuid::nil(),
+        email: "preview@example.com".to_string(),
+        password: String::new(),
+        api_key: "lo-preview".to_string(),
+        name: "Preview User".to_string(),
+        reset_token: Some("sample-reset-token".to_string()),
+        reset_sent_at: Some(now),
+        email_verification_token: Some("sample-verification-token".to_string()),
+        email_verification_sent_at: Some(now),
+        email_verified_at: None,
+        magic_link_token: Some("sample-magic-link-token".to_string()),
+        magic_link_expiration: Some(now),
+        deletion_token: Some("sample-deletion-token".to_string()),
+        deletion_requested_at: Some(now),
+        is_admin: false,
+        disabled_at: None,
+        display_name: None,
+        timezone: None,
+        bio: None,
+        avatar_key: None,
+        suspended_at: None,
+        suspension_reason: None,
+        suspended_until: None,
+        preferences: None,
+        plan: None,
+        last_login_at: None,
+    }
+}
+
+/// Renders a template with sample data.
+///
+/// # Errors
+///
+/// [`Error::NotFound`] for an unknown template, or the render error, which
+/// names any local the template references but is not set
+pub fn render(ctx: &AppContext, template: &str) -> Result<Email> {
+    let email = sample(template).ok_or(Error::NotFound)?;
+    AuthMailer::render(ctx, &sample_user(), &email)
+}
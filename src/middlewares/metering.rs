@@ -0,0 +1,72 @@
+use axum::{
+    extract::{FromRequestParts, Request, State},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Router as AXRouter,
+};
+use loco_rs::{app::AppContext, controller::middleware::MiddlewareLayer, Result};
+
+use crate::{common::plans::Plans, extractors::api_user::ApiUser};
+
+/// Authenticated routes that are not counted: the session endpoints, what the
+/// frontend polls, and the usage report, which has to stay readable once the
+/// quota is used up.
+const UNMETERED: &[&str] = &[
+    "/api/auth/",
+    "/api/notifications/unread-count",
+    "/api/usage",
+];
+
+fn is_metered(path: &str) -> bool {
+    path.starts_with("/api/") && !UNMETERED.iter().any(|prefix| path.starts_with(prefix))
+}
+
+/// Counts every authenticated API request, made with a JWT or an API key,
+/// against the daily request quota of the plan of the user. Requests over
+/// the quota are rejected with a 429 before reaching the handler.
+///
+/// The authenticated user is kept in the request so [`ApiUser`] does not
+/// load it again. Requests that do not authenticate are passed through
+/// untouched and left for the handler to reject.
+pub struct Metering {
+    ctx: AppContext,
+}
+
+impl Metering {
+    #[must_use]
+    pub fn new(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+}
+
+impl MiddlewareLayer for Metering {
+    fn name(&self) -> &'static str {
+        "metering"
+    }
+
+    fn config(&self) -> serde_json::Result<serde_json::Value> {
+        Ok(serde_json::json!({ "unmetered": UNMETERED }))
+    }
+
+    fn apply(&self, app: AXRouter<AppContext>) -> Result<AXRouter<AppContext>> {
+        Ok(app.layer(middleware::from_fn_with_state(self.ctx.clone(), meter)))
+    }
+}
+
+async fn meter(State(ctx): State<AppContext>, request: Request, next: Next) -> Response {
+    if !is_metered(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let (mut parts, body) = request.into_parts();
+    if let Ok(auth) = ApiUser::from_request_parts(&mut parts, &ctx).await {
+        if let Err(err) = Plans::from_context(&ctx)
+            .consume_request(&ctx.db, &auth.user)
+            .await
+        {
+            return err.into_response();
+        }
+        parts.extensions.insert(auth);
+    }
+    next.run(Request::from_parts(parts, body)).await
+}
@@ -1,2 +1,12 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.0.0
+pub use super::audit_events::Entity as AuditEvents;
+pub use super::data_exports::Entity as DataExports;
+pub use super::email_outbox::Entity as EmailOutbox;
+pub use super::email_suppressions::Entity as EmailSuppressions;
+pub use super::feature_flags::Entity as FeatureFlags;
+pub use super::impersonation_logs::Entity as ImpersonationLogs;
+pub use super::notifications::Entity as Notifications;
+pub use super::usage_counters::Entity as UsageCounters;
 pub use super::users::Entity as Users;
+pub use super::webhook_deliveries::Entity as WebhookDeliveries;
+pub use super::webhook_endpoints::Entity as WebhookEndpoints;
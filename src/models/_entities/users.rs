@@ -24,6 +24,22 @@ pub struct Model {
     pub email_verified_at: Option<DateTimeWithTimeZone>,
     pub magic_link_token: Option<String>,
     pub magic_link_expiration: Option<DateTimeWithTimeZone>,
+    pub deletion_token: Option<String>,
+    pub deletion_requested_at: Option<DateTimeWithTimeZone>,
+    pub is_admin: bool,
+    pub disabled_at: Option<DateTimeWithTimeZone>,
+    pub display_name: Option<String>,
+    pub timezone: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub bio: Option<String>,
+    pub avatar_key: Option<String>,
+    pub suspended_at: Option<DateTimeWithTimeZone>,
+    pub suspension_reason: Option<String>,
+    pub suspended_until: Option<DateTimeWithTimeZone>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub preferences: Option<Json>,
+    pub plan: Option<String>,
+    pub last_login_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,132 @@
+use loco_rs::{model::query::PageResponse, prelude::*};
+use sea_orm::{PaginatorTrait, QueryOrder};
+
+pub use super::_entities::audit_events::{self, ActiveModel, Entity, Model};
+use super::users;
+
+pub const EVENT_REGISTER: &str = "register";
+pub const EVENT_VERIFY_EMAIL: &str = "verify_email";
+pub const EVENT_RESEND_VERIFICATION: &str = "resend_verification";
+pub const EVENT_LOGIN: &str = "login";
+pub const EVENT_LOGIN_FAILED: &str = "login_failed";
+pub const EVENT_MAGIC_LINK_REQUESTED: &str = "magic_link_requested";
+pub const EVENT_MAGIC_LINK_USED: &str = "magic_link_used";
+pub const EVENT_PASSWORD_RESET_REQUESTED: &str = "password_reset_requested";
+pub const EVENT_PASSWORD_RESET: &str = "password_reset";
+
+/// Events that prove the user had access to the account from a browser.
+const SIGN_IN_EVENTS: &[&str] = &[EVENT_REGISTER, EVENT_LOGIN, EVENT_MAGIC_LINK_USED];
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Records an authentication event for the given user. `detail` carries
+    /// extra context, such as the reason of a failed login.
+    ///
+    /// # Errors
+    ///
+    /// When could not save the event into the DB
+    pub async fn record(
+        db: &DatabaseConnection,
+        user: &users::Model,
+        event: &str,
+        detail: Option<&str>,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+    ) -> ModelResult<Self> {
+        let event = ActiveModel {
+            event: ActiveValue::set(event.to_string()),
+            detail: ActiveValue::set(detail.map(ToString::to_string)),
+            ip_address: ActiveValue::set(ip_address),
+            user_agent: ActiveValue::set(user_agent),
+            user_id: ActiveValue::set(user.id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+
+        Ok(event)
+    }
+
+    /// Lists a page of the events of the given user, newest first
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn paginate_by_user(
+        db: &DatabaseConnection,
+        user: &users::Model,
+        pagination: &query::PaginationQuery,
+    ) -> Result<PageResponse<Self>> {
+        query::paginate(
+            db,
+            Entity::find().order_by_desc(audit_events::Column::Id),
+            Some(
+                query::condition()
+                    .eq(audit_events::Column::UserId, user.id)
+                    .build(),
+            ),
+            pagination,
+        )
+        .await
+    }
+
+    /// Lists every event of the given user, newest first
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_user(
+        db: &DatabaseConnection,
+        user: &users::Model,
+    ) -> ModelResult<Vec<Self>> {
+        let events = Entity::find()
+            .filter(
+                query::condition()
+                    .eq(audit_events::Column::UserId, user.id)
+                    .build(),
+            )
+            .order_by_desc(audit_events::Column::Id)
+            .all(db)
+            .await?;
+        Ok(events)
+    }
+
+    /// Whether the user has registered or signed in with the given user
+    /// agent before
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn has_signed_in_with(
+        db: &DatabaseConnection,
+        user: &users::Model,
+        user_agent: &str,
+    ) -> ModelResult<bool> {
+        let count = Entity::find()
+            .filter(
+                query::condition()
+                    .eq(audit_events::Column::UserId, user.id)
+                    .eq(audit_events::Column::UserAgent, user_agent)
+                    .build(),
+            )
+            .filter(audit_events::Column::Event.is_in(SIGN_IN_EVENTS.iter().copied()))
+            .count(db)
+            .await?;
+        Ok(count > 0)
+    }
+}
@@ -0,0 +1,89 @@
+use chrono::{offset::Local, DateTime};
+use loco_rs::prelude::*;
+use sea_orm::QueryOrder;
+use uuid::Uuid;
+
+pub use super::_entities::data_exports::{self, ActiveModel, Entity, Model};
+use super::users;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            Ok(this)
+        } else if self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+/// Export archives kept in storage. The pid is the export id used in the
+/// storage path and the download link, and the row is what lets expired
+/// archives be found and deleted.
+impl Model {
+    /// Records a new export of the given user. Its pid is the export id.
+    ///
+    /// # Errors
+    ///
+    /// When could not save the export into the DB
+    pub async fn create(db: &DatabaseConnection, user: &users::Model) -> ModelResult<Self> {
+        let export = ActiveModel {
+            user_id: ActiveValue::set(user.id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(export)
+    }
+
+    /// Lists every export of the given user, newest first
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_user(
+        db: &DatabaseConnection,
+        user: &users::Model,
+    ) -> ModelResult<Vec<Self>> {
+        let exports = Entity::find()
+            .filter(
+                query::condition()
+                    .eq(data_exports::Column::UserId, user.id)
+                    .build(),
+            )
+            .order_by_desc(data_exports::Column::Id)
+            .all(db)
+            .await?;
+        Ok(exports)
+    }
+
+    /// Lists the exports created before `cutoff`, oldest first
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_created_before(
+        db: &DatabaseConnection,
+        cutoff: DateTime<Local>,
+    ) -> ModelResult<Vec<Self>> {
+        let exports = Entity::find()
+            .filter(
+                query::condition()
+                    .lt(data_exports::Column::CreatedAt, cutoff)
+                    .build(),
+            )
+            .order_by_asc(data_exports::Column::Id)
+            .all(db)
+            .await?;
+        Ok(exports)
+    }
+}
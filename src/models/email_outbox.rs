@@ -0,0 +1,266 @@
+use chrono::offset::Local;
+use loco_rs::{mailer::Email, prelude::*};
+use sea_orm::{sea_query::Expr, Condition, QueryOrder, QuerySelect};
+use uuid::Uuid;
+
+pub use super::_entities::email_outbox::{self, ActiveModel, Entity, Model};
+use super::users;
+
+pub const STATUS_QUEUED: &str = "queued";
+/// Claimed by a dispatcher until `locked_until`
+pub const STATUS_SENDING: &str = "sending";
+pub const STATUS_SENT: &str = "sent";
+pub const STATUS_FAILED: &str = "failed";
+/// Not sent, the recipient address was suppressed when it was dispatched
+pub const STATUS_SUPPRESSED: &str = "suppressed";
+
+/// Longest error kept on a message, in characters.
+const MAX_ERROR: usize = 1024;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            Ok(this)
+        } else if self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+/// Messages a dispatcher may claim: queued ones, and ones whose dispatcher
+/// gave up its claim by dying mid-send
+fn claimable() -> Condition {
+    Condition::any()
+        .add(email_outbox::Column::Status.eq(STATUS_QUEUED))
+        .add(
+            Condition::all()
+                .add(email_outbox::Column::Status.eq(STATUS_SENDING))
+                .add(email_outbox::Column::LockedUntil.lt(Local::now())),
+        )
+}
+
+impl Model {
+    /// Records a rendered email to the user as queued
+    ///
+    /// # Errors
+    ///
+    /// When could not save the message into the DB
+    pub async fn create(
+        db: &DatabaseConnection,
+        user: &users::Model,
+        template: &str,
+        email: &Email,
+    ) -> ModelResult<Self> {
+        let message = ActiveModel {
+            template: ActiveValue::set(template.to_string()),
+            recipient: ActiveValue::set(email.to.clone()),
+            subject: ActiveValue::set(email.subject.clone()),
+            text: ActiveValue::set(email.text.clone()),
+            html: ActiveValue::set(email.html.clone()),
+            status: ActiveValue::set(STATUS_QUEUED.to_string()),
+            attempts: ActiveValue::set(0),
+            user_id: ActiveValue::set(user.id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(message)
+    }
+
+    /// Queues the same content again as a new message, keeping this one as
+    /// history
+    ///
+    /// # Errors
+    ///
+    /// When could not save the message into the DB
+    pub async fn copy(&self, db: &DatabaseConnection) -> ModelResult<Self> {
+        let message = ActiveModel {
+            template: ActiveValue::set(self.template.clone()),
+            recipient: ActiveValue::set(self.recipient.clone()),
+            subject: ActiveValue::set(self.subject.clone()),
+            text: ActiveValue::set(self.text.clone()),
+            html: ActiveValue::set(self.html.clone()),
+            status: ActiveValue::set(STATUS_QUEUED.to_string()),
+            attempts: ActiveValue::set(0),
+            user_id: ActiveValue::set(self.user_id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(message)
+    }
+
+    /// Finds a message by pid
+    ///
+    /// # Errors
+    ///
+    /// When the message does not exist or DB query error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let pid = Uuid::parse_str(pid).map_err(|_| ModelError::EntityNotFound)?;
+        Entity::find()
+            .filter(
+                query::condition()
+                    .eq(email_outbox::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or(ModelError::EntityNotFound)
+    }
+
+    /// Lists every message to the given user, newest first
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_user(
+        db: &DatabaseConnection,
+        user: &users::Model,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(
+                query::condition()
+                    .eq(email_outbox::Column::UserId, user.id)
+                    .build(),
+            )
+            .order_by_desc(email_outbox::Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    /// Queued messages after the given id, oldest first, along with messages
+    /// whose claim expired
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn queued(
+        db: &DatabaseConnection,
+        after_id: i32,
+        limit: u64,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(claimable())
+            .filter(email_outbox::Column::Id.gt(after_id))
+            .order_by_asc(email_outbox::Column::Id)
+            .limit(limit)
+            .all(db)
+            .await?)
+    }
+
+    /// Claims a message for sending during `lease`, in a single statement so
+    /// that two dispatchers never claim the same message. Returns `None` when
+    /// the message is not queued or another dispatcher holds it.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn claim(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        lease: std::time::Duration,
+    ) -> ModelResult<Option<Self>> {
+        let now = Local::now();
+        let locked_until = now + chrono::Duration::from_std(lease).unwrap_or(chrono::Duration::MAX);
+        let claimed = Entity::update_many()
+            .col_expr(email_outbox::Column::Status, Expr::value(STATUS_SENDING))
+            .col_expr(email_outbox::Column::LockedUntil, Expr::value(locked_until))
+            .col_expr(email_outbox::Column::UpdatedAt, Expr::value(now))
+            .filter(email_outbox::Column::Pid.eq(pid))
+            .filter(claimable())
+            .exec_with_returning(db)
+            .await?;
+        Ok(claimed.into_iter().next())
+    }
+
+    /// The stored content as an email to send
+    #[must_use]
+    pub fn email(&self, from: &str) -> Email {
+        Email {
+            from: Some(from.to_string()),
+            to: self.recipient.clone(),
+            subject: self.subject.clone(),
+            text: self.text.clone(),
+            html: self.html.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl ActiveModel {
+    /// Replaces the stored bodies by copies without their one-time secrets.
+    /// Nothing changes when the bodies hold none.
+    #[must_use]
+    pub fn redact(mut self, text: String, html: String) -> Self {
+        let unchanged =
+            self.text.try_as_ref() == Some(&text) && self.html.try_as_ref() == Some(&html);
+        if !unchanged {
+            self.text = ActiveValue::set(text);
+            self.html = ActiveValue::set(html);
+            self.redacted_at = ActiveValue::set(Some(Local::now().into()));
+        }
+        self
+    }
+
+    /// Marks the message as sent after `attempts` attempts. `last_error` is
+    /// the error of the previous attempt, if it was retried.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn mark_sent<C: ConnectionTrait>(
+        mut self,
+        db: &C,
+        attempts: u32,
+        last_error: Option<&str>,
+        provider_message_id: Option<String>,
+    ) -> ModelResult<Model> {
+        self.status = ActiveValue::set(STATUS_SENT.to_string());
+        self.attempts = ActiveValue::set(i32::try_from(attempts).unwrap_or(i32::MAX));
+        self.last_error =
+            ActiveValue::set(last_error.map(|error| error.chars().take(MAX_ERROR).collect()));
+        self.provider_message_id = ActiveValue::set(provider_message_id);
+        self.sent_at = ActiveValue::set(Some(Local::now().into()));
+        self.locked_until = ActiveValue::set(None);
+        self.update(db).await.map_err(ModelError::from)
+    }
+
+    /// Marks the message as failed for good after `attempts` attempts
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn mark_failed<C: ConnectionTrait>(
+        mut self,
+        db: &C,
+        attempts: u32,
+        error: &str,
+    ) -> ModelResult<Model> {
+        self.status = ActiveValue::set(STATUS_FAILED.to_string());
+        self.attempts = ActiveValue::set(i32::try_from(attempts).unwrap_or(i32::MAX));
+        self.last_error = ActiveValue::set(Some(error.chars().take(MAX_ERROR).collect()));
+        self.locked_until = ActiveValue::set(None);
+        self.update(db).await.map_err(ModelError::from)
+    }
+
+    /// Marks the message as not sent because its recipient is suppressed
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn mark_suppressed<C: ConnectionTrait>(mut self, db: &C) -> ModelResult<Model> {
+        self.status = ActiveValue::set(STATUS_SUPPRESSED.to_string());
+        self.locked_until = ActiveValue::set(None);
+        self.update(db).await.map_err(ModelError::from)
+    }
+}
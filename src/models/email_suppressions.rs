@@ -0,0 +1,129 @@
+use loco_rs::prelude::*;
+use uuid::Uuid;
+
+pub use super::_entities::email_suppressions::{self, ActiveModel, Entity, Model};
+
+/// The address bounced permanently
+pub const REASON_BOUNCE: &str = "bounce";
+/// The recipient marked an email as spam
+pub const REASON_COMPLAINT: &str = "complaint";
+
+/// Longest detail kept on a suppression, in characters.
+const MAX_DETAIL: usize = 1024;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            Ok(this)
+        } else if self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+/// Addresses are compared without case
+fn normalize(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+impl Model {
+    /// Stops emails to the address. Suppressing an address again replaces
+    /// the reason of the existing suppression.
+    ///
+    /// # Errors
+    ///
+    /// When could not save the suppression into the DB
+    pub async fn suppress(
+        db: &DatabaseConnection,
+        email: &str,
+        reason: &str,
+        source: &str,
+        detail: Option<&str>,
+    ) -> ModelResult<Self> {
+        let detail = detail.map(|detail| detail.chars().take(MAX_DETAIL).collect::<String>());
+        let suppression = match Self::find_by_email(db, email).await? {
+            Some(existing) => {
+                let mut suppression = existing.into_active_model();
+                suppression.reason = ActiveValue::set(reason.to_string());
+                suppression.source = ActiveValue::set(source.to_string());
+                suppression.detail = ActiveValue::set(detail);
+                suppression.update(db).await?
+            }
+            None => {
+                ActiveModel {
+                    email: ActiveValue::set(normalize(email)),
+                    reason: ActiveValue::set(reason.to_string()),
+                    source: ActiveValue::set(source.to_string()),
+                    detail: ActiveValue::set(detail),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await?
+            }
+        };
+        Ok(suppression)
+    }
+
+    /// Finds the suppression of an address, if any
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_email(db: &DatabaseConnection, email: &str) -> ModelResult<Option<Self>> {
+        Ok(Entity::find()
+            .filter(
+                query::condition()
+                    .eq(email_suppressions::Column::Email, normalize(email))
+                    .build(),
+            )
+            .one(db)
+            .await?)
+    }
+
+    /// Whether emails to the address are suppressed
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn is_suppressed(db: &DatabaseConnection, email: &str) -> ModelResult<bool> {
+        Ok(Self::find_by_email(db, email).await?.is_some())
+    }
+
+    /// Finds a suppression by pid
+    ///
+    /// # Errors
+    ///
+    /// When the suppression does not exist or DB query error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let pid = Uuid::parse_str(pid).map_err(|_| ModelError::EntityNotFound)?;
+        Entity::find()
+            .filter(
+                query::condition()
+                    .eq(email_suppressions::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or(ModelError::EntityNotFound)
+    }
+
+    /// Lifts the suppression so emails to the address are sent again
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn lift(self, db: &DatabaseConnection) -> ModelResult<()> {
+        self.delete(db).await?;
+        Ok(())
+    }
+}
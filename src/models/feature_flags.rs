@@ -0,0 +1,106 @@
+use loco_rs::prelude::*;
+use sea_orm::QueryOrder;
+
+pub use super::_entities::feature_flags::{self, ActiveModel, Entity, Model};
+use crate::common::feature_flags::FlagRule;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Lists every stored flag ordered by key.
+    ///
+    /// # Errors
+    ///
+    /// When has DB query error
+    pub async fn list(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        Ok(Entity::find()
+            .order_by_asc(feature_flags::Column::Key)
+            .all(db)
+            .await?)
+    }
+
+    /// Finds a stored flag by key.
+    ///
+    /// # Errors
+    ///
+    /// When has DB query error
+    pub async fn find_by_key(db: &DatabaseConnection, key: &str) -> ModelResult<Option<Self>> {
+        Ok(Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(feature_flags::Column::Key, key)
+                    .build(),
+            )
+            .one(db)
+            .await?)
+    }
+
+    /// Stores the rule of a flag, replacing the previous one.
+    ///
+    /// # Errors
+    ///
+    /// When has DB query error
+    pub async fn upsert(db: &DatabaseConnection, key: &str, rule: &FlagRule) -> ModelResult<Self> {
+        let user_pids = serde_json::to_value(&rule.user_pids).map_err(ModelError::wrap)?;
+        let percentage = i16::from(rule.percentage);
+
+        let flag = match Self::find_by_key(db, key).await? {
+            Some(flag) => {
+                let mut flag = flag.into_active_model();
+                flag.enabled = ActiveValue::set(rule.enabled);
+                flag.percentage = ActiveValue::set(percentage);
+                flag.user_pids = ActiveValue::set(user_pids);
+                flag.update(db).await?
+            }
+            None => {
+                ActiveModel {
+                    key: ActiveValue::set(key.to_string()),
+                    enabled: ActiveValue::set(rule.enabled),
+                    percentage: ActiveValue::set(percentage),
+                    user_pids: ActiveValue::set(user_pids),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await?
+            }
+        };
+        Ok(flag)
+    }
+
+    /// Removes the stored rule of a flag. Returns whether there was one.
+    ///
+    /// # Errors
+    ///
+    /// When has DB query error
+    pub async fn remove(db: &DatabaseConnection, key: &str) -> ModelResult<bool> {
+        let result = Entity::delete_many()
+            .filter(feature_flags::Column::Key.eq(key))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// The rollout rule stored for this flag.
+    #[must_use]
+    pub fn rule(&self) -> FlagRule {
+        FlagRule {
+            enabled: self.enabled,
+            percentage: u8::try_from(self.percentage.clamp(0, 100)).unwrap_or_default(),
+            user_pids: serde_json::from_value(self.user_pids.clone()).unwrap_or_default(),
+        }
+    }
+}
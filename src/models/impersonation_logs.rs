@@ -0,0 +1,135 @@
+use chrono::offset::Local;
+use loco_rs::{auth::jwt::UserClaims, prelude::*};
+use sea_orm::QueryOrder;
+use serde_json::{Map, Value};
+
+pub use super::_entities::impersonation_logs::{self, ActiveModel, Entity, Model};
+use super::users;
+
+pub const IMPERSONATOR_CLAIM: &str = "impersonator";
+pub const IMPERSONATION_EXPIRES_AT_CLAIM: &str = "impersonation_expires_at";
+pub const IMPERSONATION_EXPIRATION_SECS: i64 = 15 * 60;
+
+pub const EVENT_START: &str = "start";
+pub const EVENT_STOP: &str = "stop";
+
+/// Impersonation state carried in the JWT claims of a token issued to an
+/// administrator on behalf of another user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Impersonation {
+    pub impersonator_pid: String,
+    /// unix timestamp after which the impersonation session is over
+    pub expires_at: i64,
+}
+
+impl Impersonation {
+    /// Starts a new impersonation session for the given administrator
+    #[must_use]
+    pub fn start(impersonator: &users::Model) -> Self {
+        Self {
+            impersonator_pid: impersonator.pid.to_string(),
+            expires_at: Local::now().timestamp() + IMPERSONATION_EXPIRATION_SECS,
+        }
+    }
+
+    /// Reads the impersonation state from the JWT claims, if any
+    #[must_use]
+    pub fn from_claims(claims: &UserClaims) -> Option<Self> {
+        let impersonator_pid = claims.claims.get(IMPERSONATOR_CLAIM)?.as_str()?;
+        let expires_at = claims
+            .claims
+            .get(IMPERSONATION_EXPIRES_AT_CLAIM)?
+            .as_i64()?;
+
+        Some(Self {
+            impersonator_pid: impersonator_pid.to_string(),
+            expires_at,
+        })
+    }
+
+    #[must_use]
+    pub fn claims(&self) -> Map<String, Value> {
+        let mut claims = Map::new();
+        claims.insert(
+            IMPERSONATOR_CLAIM.to_string(),
+            Value::String(self.impersonator_pid.clone()),
+        );
+        claims.insert(
+            IMPERSONATION_EXPIRES_AT_CLAIM.to_string(),
+            Value::from(self.expires_at),
+        );
+        claims
+    }
+
+    /// Seconds left until the session expires, never extended past the
+    /// original expiration
+    #[must_use]
+    pub fn remaining_secs(&self) -> u64 {
+        u64::try_from(self.expires_at - Local::now().timestamp()).unwrap_or(0)
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Records an impersonation start or stop event. Both users are kept by
+    /// pid only, so the log outlives their accounts.
+    ///
+    /// # Errors
+    ///
+    /// When could not save the event into the DB
+    pub async fn record(
+        db: &DatabaseConnection,
+        event: &str,
+        impersonator: &users::Model,
+        user: &users::Model,
+        ip_address: Option<String>,
+    ) -> ModelResult<Self> {
+        let log = ActiveModel {
+            event: ActiveValue::set(event.to_string()),
+            impersonator_pid: ActiveValue::set(impersonator.pid),
+            user_pid: ActiveValue::set(user.pid),
+            ip_address: ActiveValue::set(ip_address),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+
+        Ok(log)
+    }
+
+    /// Lists the impersonation events for the given user, newest first
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_user(
+        db: &DatabaseConnection,
+        user: &users::Model,
+    ) -> ModelResult<Vec<Self>> {
+        let logs = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(impersonation_logs::Column::UserPid, user.pid)
+                    .build(),
+            )
+            .order_by_desc(impersonation_logs::Column::Id)
+            .all(db)
+            .await?;
+        Ok(logs)
+    }
+}
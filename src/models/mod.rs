@@ -1,2 +1,13 @@
 pub mod _entities;
+pub mod audit_events;
+pub mod data_exports;
+pub mod email_outbox;
+pub mod email_suppressions;
+pub mod feature_flags;
+pub mod impersonation_logs;
+pub mod notifications;
+pub mod preferences;
+pub mod usage_counters;
 pub mod users;
+pub mod webhook_deliveries;
+pub mod webhook_endpoints;
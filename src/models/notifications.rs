@@ -0,0 +1,169 @@
+use chrono::offset::Local;
+use loco_rs::{model::query::PageResponse, prelude::*};
+use sea_orm::{sea_query::Expr, PaginatorTrait, QueryOrder};
+use uuid::Uuid;
+
+pub use super::_entities::notifications::{self, ActiveModel, Entity, Model};
+use super::users;
+
+pub const KIND_DATA_EXPORT_READY: &str = "data_export_ready";
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            Ok(this)
+        } else if self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Adds a notification to the inbox of the user. `kind` tells the
+    /// frontend how to render `payload`.
+    ///
+    /// Can be called from controllers, workers and tasks alike.
+    ///
+    /// # Errors
+    ///
+    /// When could not save the notification into the DB
+    pub async fn notify(
+        db: &DatabaseConnection,
+        user: &users::Model,
+        kind: &str,
+        payload: serde_json::Value,
+    ) -> ModelResult<Self> {
+        let notification = ActiveModel {
+            kind: ActiveValue::set(kind.to_string()),
+            payload: ActiveValue::set(payload),
+            user_id: ActiveValue::set(user.id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+
+        Ok(notification)
+    }
+
+    fn user_condition(user: &users::Model, unread_only: bool) -> query::ConditionBuilder {
+        let condition = query::condition().eq(notifications::Column::UserId, user.id);
+        if unread_only {
+            condition.is_null(notifications::Column::ReadAt)
+        } else {
+            condition
+        }
+    }
+
+    /// Lists a page of the notifications of the given user, newest first
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn paginate_by_user(
+        db: &DatabaseConnection,
+        user: &users::Model,
+        unread_only: bool,
+        pagination: &query::PaginationQuery,
+    ) -> Result<PageResponse<Self>> {
+        query::paginate(
+            db,
+            Entity::find().order_by_desc(notifications::Column::Id),
+            Some(Self::user_condition(user, unread_only).build()),
+            pagination,
+        )
+        .await
+    }
+
+    /// Lists every notification of the given user, newest first
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_user(
+        db: &DatabaseConnection,
+        user: &users::Model,
+    ) -> ModelResult<Vec<Self>> {
+        let notifications = Entity::find()
+            .filter(Self::user_condition(user, false).build())
+            .order_by_desc(notifications::Column::Id)
+            .all(db)
+            .await?;
+        Ok(notifications)
+    }
+
+    /// Finds a notification of the given user by pid
+    ///
+    /// # Errors
+    ///
+    /// When the notification does not exist or belongs to another user
+    pub async fn find_by_pid(
+        db: &DatabaseConnection,
+        user: &users::Model,
+        pid: &str,
+    ) -> ModelResult<Self> {
+        let pid = Uuid::parse_str(pid).map_err(|_| ModelError::EntityNotFound)?;
+        Entity::find()
+            .filter(
+                Self::user_condition(user, false)
+                    .eq(notifications::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or(ModelError::EntityNotFound)
+    }
+
+    /// Number of notifications the user has not read yet
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn unread_count(db: &DatabaseConnection, user: &users::Model) -> ModelResult<u64> {
+        Ok(Entity::find()
+            .filter(Self::user_condition(user, true).build())
+            .count(db)
+            .await?)
+    }
+
+    /// Marks every unread notification of the user as read and returns how
+    /// many were changed
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn mark_all_read(db: &DatabaseConnection, user: &users::Model) -> ModelResult<u64> {
+        let now: DateTimeWithTimeZone = Local::now().into();
+        let result = Entity::update_many()
+            .col_expr(notifications::Column::ReadAt, Expr::value(now))
+            .col_expr(notifications::Column::UpdatedAt, Expr::value(now))
+            .filter(Self::user_condition(user, true).build())
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
+
+impl ActiveModel {
+    /// Marks the notification as read. Already read notifications keep their
+    /// original `read_at`.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn mark_read(mut self, db: &DatabaseConnection) -> ModelResult<Model> {
+        if self.read_at.as_ref().is_none() {
+            self.read_at = ActiveValue::set(Some(Local::now().into()));
+        }
+        self.update(db).await.map_err(ModelError::from)
+    }
+}
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Color scheme of the frontend.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ts_rs::TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum Theme {
+    Light,
+    Dark,
+    #[default]
+    System,
+}
+
+/// Non-critical notifications a user can opt out of. Security notices such
+/// as password changes are always sent.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, ts_rs::TS)]
+#[serde(default)]
+#[ts(export)]
+pub struct NotificationPreferences {
+    pub new_login: bool,
+    pub email_verified: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            new_login: true,
+            email_verified: true,
+        }
+    }
+}
+
+/// Settings stored per user in the `preferences` column. Every field has a
+/// default so missing keys, including a `NULL` column, read as defaults.
+///
+/// Unknown keys are ignored when reading, so a document written by a newer
+/// version still reads after a rollback, but rejected in updates by
+/// [`Preferences::merge`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, ts_rs::TS)]
+#[serde(default)]
+#[ts(export)]
+pub struct Preferences {
+    pub theme: Theme,
+    pub locale: Option<String>,
+    pub notifications: NotificationPreferences,
+}
+
+impl Preferences {
+    /// Reads stored preferences, ignoring keys it does not know. A document
+    /// with a value of the wrong type is logged and replaced by the defaults
+    /// rather than failing requests.
+    #[must_use]
+    pub fn from_stored(value: Option<&Value>) -> Self {
+        let Some(value) = value else {
+            return Self::default();
+        };
+        serde_json::from_value(value.clone()).unwrap_or_else(|err| {
+            tracing::warn!(error = err.to_string(), "could not read stored preferences");
+            Self::default()
+        })
+    }
+
+    /// Applies a partial update following JSON merge patch semantics: objects
+    /// are merged key by key and `null` resets a key to its default.
+    ///
+    /// # Errors
+    ///
+    /// when the patch is not an object or the result has unknown keys or
+    /// values of the wrong type
+    pub fn merge(&self, patch: &Value) -> Result<Self, serde_json::Error> {
+        if !patch.is_object() {
+            return Err(serde::de::Error::custom("preferences must be an object"));
+        }
+        check_known_keys(&serde_json::to_value(Self::default())?, patch, "")?;
+        let mut merged = serde_json::to_value(self)?;
+        merge_patch(&mut merged, patch);
+        serde_json::from_value(merged)
+    }
+}
+
+/// Fails on the first key of `patch` that `known` does not have, looking
+/// into nested objects.
+fn check_known_keys(known: &Value, patch: &Value, path: &str) -> Result<(), serde_json::Error> {
+    let (Value::Object(known), Value::Object(patch)) = (known, patch) else {
+        return Ok(());
+    };
+    for (key, value) in patch {
+        let path = format!("{path}{key}");
+        let Some(known) = known.get(key) else {
+            return Err(serde::de::Error::custom(format!("unknown field `{path}`")));
+        };
+        check_known_keys(known, value, &format!("{path}."))?;
+    }
+    Ok(())
+}
+
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(serde_json::Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use loco_rs::prelude::*;
+use sea_orm::{
+    sea_query::{Expr, OnConflict},
+    QueryOrder, QuerySelect,
+};
+
+pub use super::_entities::usage_counters::{self, ActiveModel, Entity, Model};
+use super::users;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+fn counter_condition(user: &users::Model, metric: &str, bucket: &str) -> sea_orm::Condition {
+    query::condition()
+        .eq(usage_counters::Column::UserId, user.id)
+        .eq(usage_counters::Column::Metric, metric)
+        .eq(usage_counters::Column::Bucket, bucket)
+        .build()
+}
+
+/// Usage counters, keyed by user, metric and bucket. The bucket is the day
+/// for rates, such as `2026-10-18`, and the source for amounts, such as
+/// `avatars`.
+impl Model {
+    /// Creates the counter at zero if it does not exist yet
+    async fn ensure(
+        db: &DatabaseConnection,
+        user: &users::Model,
+        metric: &str,
+        bucket: &str,
+    ) -> ModelResult<()> {
+        Entity::insert(ActiveModel {
+            metric: ActiveValue::set(metric.to_string()),
+            bucket: ActiveValue::set(bucket.to_string()),
+            value: ActiveValue::set(0),
+            user_id: ActiveValue::set(user.id),
+            ..Default::default()
+        })
+        .on_conflict(
+            OnConflict::columns([
+                usage_counters::Column::UserId,
+                usage_counters::Column::Metric,
+                usage_counters::Column::Bucket,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .do_nothing()
+        .exec(db)
+        .await?;
+        Ok(())
+    }
+
+    /// Atomically adds `amount` to the counter unless that would take it
+    /// above `limit`. Returns the new value, or `None` when the limit would
+    /// be exceeded and nothing was added.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn increment_within(
+        db: &DatabaseConnection,
+        user: &users::Model,
+        metric: &str,
+        bucket: &str,
+        amount: i64,
+        limit: Option<i64>,
+    ) -> ModelResult<Option<i64>> {
+        Self::ensure(db, user, metric, bucket).await?;
+
+        let mut update = Entity::update_many()
+            .col_expr(
+                usage_counters::Column::Value,
+                Expr::col(usage_counters::Column::Value).add(amount),
+            )
+            .col_expr(
+                usage_counters::Column::UpdatedAt,
+                Expr::value(chrono::Utc::now()),
+            )
+            .filter(counter_condition(user, metric, bucket));
+        if let Some(limit) = limit {
+            update = update.filter(usage_counters::Column::Value.lte(limit - amount));
+        }
+        if update.exec(db).await?.rows_affected == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(Self::value(db, user, metric, bucket).await?))
+    }
+
+    /// Overwrites the counter
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn set(
+        db: &DatabaseConnection,
+        user: &users::Model,
+        metric: &str,
+        bucket: &str,
+        value: i64,
+    ) -> ModelResult<()> {
+        Self::ensure(db, user, metric, bucket).await?;
+        Entity::update_many()
+            .col_expr(usage_counters::Column::Value, Expr::value(value))
+            .col_expr(
+                usage_counters::Column::UpdatedAt,
+                Expr::value(chrono::Utc::now()),
+            )
+            .filter(counter_condition(user, metric, bucket))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    /// Current value of a counter, zero when it does not exist
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn value(
+        db: &DatabaseConnection,
+        user: &users::Model,
+        metric: &str,
+        bucket: &str,
+    ) -> ModelResult<i64> {
+        Ok(Entity::find()
+            .filter(counter_condition(user, metric, bucket))
+            .one(db)
+            .await?
+            .map_or(0, |counter| counter.value))
+    }
+
+    /// Lists every counter of the given user, by metric and bucket
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_user(
+        db: &DatabaseConnection,
+        user: &users::Model,
+    ) -> ModelResult<Vec<Self>> {
+        let counters = Entity::find()
+            .filter(
+                query::condition()
+                    .eq(usage_counters::Column::UserId, user.id)
+                    .build(),
+            )
+            .order_by_asc(usage_counters::Column::Metric)
+            .order_by_asc(usage_counters::Column::Bucket)
+            .all(db)
+            .await?;
+        Ok(counters)
+    }
+
+    /// Deletes the counters of a metric in every bucket that sorts before
+    /// `before`, such as the days before a cutoff day. Returns how many were
+    /// deleted.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn delete_buckets_before(
+        db: &DatabaseConnection,
+        metric: &str,
+        before: &str,
+    ) -> ModelResult<u64> {
+        let result = Entity::delete_many()
+            .filter(
+                query::condition()
+                    .eq(usage_counters::Column::Metric, metric)
+                    .lt(usage_counters::Column::Bucket, before)
+                    .build(),
+            )
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// Sum of a metric over all buckets except `except`
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn total(
+        db: &DatabaseConnection,
+        user: &users::Model,
+        metric: &str,
+        except: Option<&str>,
+    ) -> ModelResult<i64> {
+        let mut condition = query::condition()
+            .eq(usage_counters::Column::UserId, user.id)
+            .eq(usage_counters::Column::Metric, metric);
+        if let Some(bucket) = except {
+            condition = condition.ne(usage_counters::Column::Bucket, bucket);
+        }
+        let values: Vec<i64> = Entity::find()
+            .select_only()
+            .column(usage_counters::Column::Value)
+            .filter(condition.build())
+            .into_tuple()
+            .all(db)
+            .await?;
+        Ok(values.into_iter().sum())
+    }
+}
@@ -1,14 +1,48 @@
 use async_trait::async_trait;
+use axum::http::StatusCode;
 use chrono::{offset::Local, Duration};
-use loco_rs::{auth::jwt, hash, prelude::*};
+use loco_rs::{auth::jwt, controller::ErrorDetail, hash, prelude::*};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Map;
+use std::sync::OnceLock;
 use uuid::Uuid;
+use validator::ValidationError;
 
 pub use super::_entities::users::{self, ActiveModel, Entity, Model};
+use super::{impersonation_logs::Impersonation, preferences::Preferences};
 
 pub const MAGIC_LINK_LENGTH: i8 = 32;
 pub const MAGIC_LINK_EXPIRATION_MIN: i8 = 5;
+pub const DELETION_GRACE_PERIOD_DAYS: i64 = 30;
+
+/// Prefix of API keys, which tells them apart from JWTs in the `Authorization`
+/// header
+pub const API_KEY_PREFIX: &str = "lo-";
+
+/// Returned when a suspended account tries to authenticate.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("account suspended")]
+pub struct AccountSuspended {
+    pub reason: Option<String>,
+    pub until: Option<DateTimeWithTimeZone>,
+}
+
+impl From<AccountSuspended> for Error {
+    fn from(err: AccountSuspended) -> Self {
+        Self::CustomError(
+            StatusCode::FORBIDDEN,
+            ErrorDetail {
+                error: Some("account_suspended".to_string()),
+                description: Some(
+                    err.reason
+                        .unwrap_or_else(|| "This account has been suspended".to_string()),
+                ),
+                errors: Some(serde_json::json!({ "until": err.until })),
+            },
+        )
+    }
+}
 
 #[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
 #[ts(export)]
@@ -25,12 +59,56 @@ pub struct RegisterParams {
     pub name: String,
 }
 
+/// Profile fields that can be changed by the user. Omitted fields are left
+/// untouched and an empty string clears an optional field.
+#[derive(Debug, Default, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct UpdateProfileParams {
+    pub name: Option<String>,
+    pub display_name: Option<String>,
+    pub timezone: Option<String>,
+    pub locale: Option<String>,
+    pub bio: Option<String>,
+}
+
+static LOCALE_RE: OnceLock<Regex> = OnceLock::new();
+
+fn validate_timezone(timezone: &str) -> Result<(), ValidationError> {
+    timezone
+        .parse::<chrono_tz::Tz>()
+        .map(|_| ())
+        .map_err(|_| ValidationError::new("timezone").with_message("Unknown timezone.".into()))
+}
+
+fn validate_locale(locale: &str) -> Result<(), ValidationError> {
+    let re = LOCALE_RE
+        .get_or_init(|| Regex::new(r"^[a-z]{2,3}(-[A-Z]{2})?$").expect("Failed to compile regex"));
+    if re.is_match(locale) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("locale")
+            .with_message("Locale must look like `en` or `en-US`.".into()))
+    }
+}
+
 #[derive(Debug, Validate, Deserialize)]
 pub struct Validator {
     #[validate(length(min = 2, message = "Name must be at least 2 characters long."))]
     pub name: String,
     #[validate(email(message = "invalid email"))]
     pub email: String,
+    #[validate(length(
+        min = 1,
+        max = 64,
+        message = "Display name must be between 1 and 64 characters long."
+    ))]
+    pub display_name: Option<String>,
+    #[validate(custom(function = "validate_timezone"))]
+    pub timezone: Option<String>,
+    #[validate(custom(function = "validate_locale"))]
+    pub locale: Option<String>,
+    #[validate(length(max = 500, message = "Bio must be at most 500 characters long."))]
+    pub bio: Option<String>,
 }
 
 impl Validatable for ActiveModel {
@@ -38,6 +116,16 @@ impl Validatable for ActiveModel {
         Box::new(Validator {
             name: self.name.as_ref().to_owned(),
             email: self.email.as_ref().to_owned(),
+            display_name: self.display_name.try_as_ref().cloned().flatten(),
+            timezone: self.timezone.try_as_ref().cloned().flatten(),
+            locale: self
+                .preferences
+                .try_as_ref()
+                .and_then(Option::as_ref)
+                .and_then(|preferences| preferences.get("locale"))
+                .and_then(|locale| locale.as_str())
+                .map(ToString::to_string),
+            bio: self.bio.try_as_ref().cloned().flatten(),
         })
     }
 }
@@ -52,7 +140,7 @@ impl ActiveModelBehavior for super::_entities::users::ActiveModel {
         if insert {
             let mut this = self;
             this.pid = ActiveValue::Set(Uuid::new_v4());
-            this.api_key = ActiveValue::Set(format!("lo-{}", Uuid::new_v4()));
+            this.api_key = ActiveValue::Set(format!("{API_KEY_PREFIX}{}", Uuid::new_v4()));
             Ok(this)
         } else {
             Ok(self)
@@ -62,6 +150,8 @@ impl ActiveModelBehavior for super::_entities::users::ActiveModel {
 
 #[async_trait]
 impl Authenticable for Model {
+    /// Suspended accounts fail with [`AccountSuspended`] wrapped in
+    /// [`ModelError::Any`].
     async fn find_by_api_key(db: &DatabaseConnection, api_key: &str) -> ModelResult<Self> {
         let user = users::Entity::find()
             .filter(
@@ -71,7 +161,9 @@ impl Authenticable for Model {
             )
             .one(db)
             .await?;
-        user.ok_or_else(|| ModelError::EntityNotFound)
+        let user = user.ok_or_else(|| ModelError::EntityNotFound)?;
+        user.check_suspension().map_err(ModelError::wrap)?;
+        Ok(user)
     }
 
     async fn find_by_claims_key(db: &DatabaseConnection, claims_key: &str) -> ModelResult<Self> {
@@ -170,6 +262,64 @@ impl Model {
         user.ok_or_else(|| ModelError::EntityNotFound)
     }
 
+    /// finds a user by the provided account deletion token
+    ///
+    /// # Errors
+    ///
+    /// When could not find user by the given token or DB query error
+    pub async fn find_by_deletion_token(db: &DatabaseConnection, token: &str) -> ModelResult<Self> {
+        let user = users::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(users::Column::DeletionToken, token)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        user.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// finds all users whose deletion was requested before the grace period
+    /// started, meaning they are due to be purged
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_due_for_deletion(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let cutoff = Local::now() - Duration::days(DELETION_GRACE_PERIOD_DAYS);
+        let users = users::Entity::find()
+            .filter(
+                model::query::condition()
+                    .lte(users::Column::DeletionRequestedAt, cutoff)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(users)
+    }
+
+    /// finds all users whose email address is not verified and who were
+    /// created at or before the given time
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_unverified_created_before(
+        db: &DatabaseConnection,
+        cutoff: chrono::DateTime<Local>,
+    ) -> ModelResult<Vec<Self>> {
+        let users = users::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_null(users::Column::EmailVerifiedAt)
+                    .lte(users::Column::CreatedAt, cutoff)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(users)
+    }
+
     /// finds a user by the provided pid
     ///
     /// # Errors
@@ -256,6 +406,66 @@ impl Model {
         Ok(user)
     }
 
+    /// Whether the user has requested account deletion and is waiting for the
+    /// grace period to elapse
+    #[must_use]
+    pub const fn is_pending_deletion(&self) -> bool {
+        self.deletion_requested_at.is_some()
+    }
+
+    /// Whether the account was disabled by an administrator
+    #[must_use]
+    pub const fn is_disabled(&self) -> bool {
+        self.disabled_at.is_some()
+    }
+
+    /// Whether the account is currently suspended. A suspension without an
+    /// end date lasts until it is lifted.
+    #[must_use]
+    pub fn is_suspended(&self) -> bool {
+        self.suspended_at.is_some()
+            && self
+                .suspended_until
+                .is_none_or(|until| until > Local::now())
+    }
+
+    /// The typed preferences of the user, with defaults for anything unset.
+    #[must_use]
+    pub fn preferences(&self) -> Preferences {
+        Preferences::from_stored(self.preferences.as_ref())
+    }
+
+    /// Fails with [`AccountSuspended`] when the account is suspended.
+    ///
+    /// # Errors
+    ///
+    /// when the account is suspended
+    pub fn check_suspension(&self) -> Result<(), AccountSuspended> {
+        if self.is_suspended() {
+            Err(AccountSuspended {
+                reason: self.suspension_reason.clone(),
+                until: self.suspended_until,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Fails when the account cannot be used, whatever credential it is
+    /// reached with: disabled or pending deletion accounts are unauthorized
+    /// and suspended accounts get [`AccountSuspended`].
+    ///
+    /// # Errors
+    ///
+    /// when the account is disabled, pending deletion or suspended
+    pub fn check_usable(&self) -> Result<(), Error> {
+        if self.is_disabled() || self.is_pending_deletion() {
+            return Err(Error::Unauthorized("account is not active".to_string()));
+        }
+        self.check_suspension()?;
+        Ok(())
+    }
+
     /// Creates a JWT
     ///
     /// # Errors
@@ -266,6 +476,27 @@ impl Model {
             .generate_token(expiration, self.pid.to_string(), Map::new())
             .map_err(ModelError::from)
     }
+
+    /// Creates a JWT on behalf of an administrator impersonating this user.
+    /// The token carries the impersonator claim and expires with the
+    /// impersonation session.
+    ///
+    /// # Errors
+    ///
+    /// when could not convert user claims to jwt token
+    pub fn generate_impersonation_jwt(
+        &self,
+        secret: &str,
+        impersonation: &Impersonation,
+    ) -> ModelResult<String> {
+        jwt::JWT::new(secret)
+            .generate_token(
+                impersonation.remaining_secs(),
+                self.pid.to_string(),
+                impersonation.claims(),
+            )
+            .map_err(ModelError::from)
+    }
 }
 
 impl ActiveModel {
@@ -368,4 +599,190 @@ impl ActiveModel {
         self.magic_link_expiration = ActiveValue::set(None);
         self.update(db).await.map_err(ModelError::from)
     }
+
+    /// Records that the user logged in, with a password or a magic link.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn logged_in(mut self, db: &DatabaseConnection) -> ModelResult<Model> {
+        self.last_login_at = ActiveValue::set(Some(Local::now().into()));
+        self.update(db).await.map_err(ModelError::from)
+    }
+
+    /// Marks the user as pending deletion.
+    ///
+    /// Records the time of the request and generates a unique token that can
+    /// be used to cancel the deletion before the grace period elapses.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn request_deletion(mut self, db: &DatabaseConnection) -> ModelResult<Model> {
+        self.deletion_requested_at = ActiveValue::set(Some(Local::now().into()));
+        self.deletion_token = ActiveValue::Set(Some(Uuid::new_v4().to_string()));
+        self.update(db).await.map_err(ModelError::from)
+    }
+
+    /// Cancels a pending account deletion.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn cancel_deletion(mut self, db: &DatabaseConnection) -> ModelResult<Model> {
+        self.deletion_requested_at = ActiveValue::set(None);
+        self.deletion_token = ActiveValue::Set(None);
+        self.update(db).await.map_err(ModelError::from)
+    }
+
+    /// Disables the account, preventing the user from authenticating.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn disable(mut self, db: &DatabaseConnection) -> ModelResult<Model> {
+        self.disabled_at = ActiveValue::set(Some(Local::now().into()));
+        self.update(db).await.map_err(ModelError::from)
+    }
+
+    /// Re-enables a previously disabled account.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn enable(mut self, db: &DatabaseConnection) -> ModelResult<Model> {
+        self.disabled_at = ActiveValue::set(None);
+        self.update(db).await.map_err(ModelError::from)
+    }
+
+    /// Grants or revokes administrator access.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn set_admin(
+        mut self,
+        db: &DatabaseConnection,
+        is_admin: bool,
+    ) -> ModelResult<Model> {
+        self.is_admin = ActiveValue::set(is_admin);
+        self.update(db).await.map_err(ModelError::from)
+    }
+
+    /// Applies a profile update and saves it.
+    ///
+    /// Only the fields present in `params` are changed; an empty string
+    /// clears an optional field.
+    ///
+    /// # Errors
+    ///
+    /// when the profile does not pass validation or has DB query error
+    pub async fn update_profile(
+        mut self,
+        db: &DatabaseConnection,
+        params: &UpdateProfileParams,
+    ) -> ModelResult<Model> {
+        fn optional(value: &str) -> Option<String> {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        }
+
+        if let Some(name) = &params.name {
+            self.name = ActiveValue::set(name.trim().to_string());
+        }
+        if let Some(display_name) = &params.display_name {
+            self.display_name = ActiveValue::set(optional(display_name));
+        }
+        if let Some(timezone) = &params.timezone {
+            self.timezone = ActiveValue::set(optional(timezone));
+        }
+        if let Some(locale) = &params.locale {
+            let mut preferences =
+                Preferences::from_stored(self.preferences.try_as_ref().and_then(Option::as_ref));
+            preferences.locale = optional(locale);
+            self.preferences = ActiveValue::set(Some(
+                serde_json::to_value(preferences).map_err(ModelError::wrap)?,
+            ));
+        }
+        if let Some(bio) = &params.bio {
+            self.bio = ActiveValue::set(optional(bio));
+        }
+
+        self.validate()?;
+        self.update(db).await.map_err(ModelError::from)
+    }
+
+    /// Points the user at a new set of avatar thumbnails, or clears it.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn set_avatar(
+        mut self,
+        db: &DatabaseConnection,
+        avatar_key: Option<String>,
+    ) -> ModelResult<Model> {
+        self.avatar_key = ActiveValue::set(avatar_key);
+        self.update(db).await.map_err(ModelError::from)
+    }
+
+    /// Assigns a plan, or clears it to fall back to the default plan.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn set_plan(
+        mut self,
+        db: &DatabaseConnection,
+        plan: Option<String>,
+    ) -> ModelResult<Model> {
+        self.plan = ActiveValue::set(plan);
+        self.update(db).await.map_err(ModelError::from)
+    }
+
+    /// Suspends the account, optionally until the given time.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn suspend(
+        mut self,
+        db: &DatabaseConnection,
+        reason: Option<String>,
+        until: Option<DateTimeWithTimeZone>,
+    ) -> ModelResult<Model> {
+        self.suspended_at = ActiveValue::set(Some(Local::now().into()));
+        self.suspension_reason = ActiveValue::set(reason);
+        self.suspended_until = ActiveValue::set(until);
+        self.update(db).await.map_err(ModelError::from)
+    }
+
+    /// Lifts a suspension.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn unsuspend(mut self, db: &DatabaseConnection) -> ModelResult<Model> {
+        self.suspended_at = ActiveValue::set(None);
+        self.suspension_reason = ActiveValue::set(None);
+        self.suspended_until = ActiveValue::set(None);
+        self.update(db).await.map_err(ModelError::from)
+    }
+
+    /// Replaces the stored preferences.
+    ///
+    /// # Errors
+    ///
+    /// when the preferences do not pass validation or has DB query error
+    pub async fn set_preferences(
+        mut self,
+        db: &DatabaseConnection,
+        preferences: &Preferences,
+    ) -> ModelResult<Model> {
+        self.preferences = ActiveValue::set(Some(
+            serde_json::to_value(preferences).map_err(ModelError::wrap)?,
+        ));
+        self.validate()?;
+        self.update(db).await.map_err(ModelError::from)
+    }
 }
@@ -0,0 +1,225 @@
+use chrono::offset::Local;
+use loco_rs::{model::query::PageResponse, prelude::*};
+use sea_orm::{sea_query::Expr, Condition, QueryOrder, QuerySelect};
+use uuid::Uuid;
+
+pub use super::_entities::webhook_deliveries::{self, ActiveModel, Entity, Model};
+use super::webhook_endpoints;
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_SUCCEEDED: &str = "succeeded";
+pub const STATUS_FAILED: &str = "failed";
+
+/// Longest response body kept in the delivery log, in characters.
+const MAX_RESPONSE_BODY: usize = 1024;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            Ok(this)
+        } else if self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+/// Deliveries a worker may claim: pending ones whose next attempt is due and
+/// that no worker holds, or whose worker died mid-attempt
+fn due() -> Condition {
+    let now = Local::now();
+    Condition::all()
+        .add(webhook_deliveries::Column::Status.eq(STATUS_PENDING))
+        .add(
+            Condition::any()
+                .add(webhook_deliveries::Column::NextAttemptAt.is_null())
+                .add(webhook_deliveries::Column::NextAttemptAt.lte(now)),
+        )
+        .add(
+            Condition::any()
+                .add(webhook_deliveries::Column::LockedUntil.is_null())
+                .add(webhook_deliveries::Column::LockedUntil.lt(now)),
+        )
+}
+
+/// Outcome of a single delivery attempt
+#[derive(Debug, Default)]
+pub struct Attempt {
+    pub response_status: Option<u16>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+}
+
+impl Attempt {
+    #[must_use]
+    pub fn succeeded(&self) -> bool {
+        self.response_status
+            .is_some_and(|status| (200..300).contains(&status))
+    }
+}
+
+impl Model {
+    /// Queues a delivery of the event to the endpoint
+    ///
+    /// # Errors
+    ///
+    /// When could not save the delivery into the DB
+    pub async fn create(
+        db: &DatabaseConnection,
+        endpoint: &webhook_endpoints::Model,
+        event: &str,
+        payload: serde_json::Value,
+    ) -> ModelResult<Self> {
+        let delivery = ActiveModel {
+            event: ActiveValue::set(event.to_string()),
+            payload: ActiveValue::set(payload),
+            status: ActiveValue::set(STATUS_PENDING.to_string()),
+            attempts: ActiveValue::set(0),
+            webhook_endpoint_id: ActiveValue::set(endpoint.id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(delivery)
+    }
+
+    /// Finds a delivery by pid
+    ///
+    /// # Errors
+    ///
+    /// When the delivery does not exist or DB query error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let pid = Uuid::parse_str(pid).map_err(|_| ModelError::EntityNotFound)?;
+        Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(webhook_deliveries::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or(ModelError::EntityNotFound)
+    }
+
+    /// Deliveries due for an attempt with an id above `after_id`, oldest
+    /// first
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_due(
+        db: &DatabaseConnection,
+        after_id: i32,
+        limit: u64,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(Entity::find()
+            .filter(due())
+            .filter(webhook_deliveries::Column::Id.gt(after_id))
+            .order_by_asc(webhook_deliveries::Column::Id)
+            .limit(limit)
+            .all(db)
+            .await?)
+    }
+
+    /// Claims a delivery for one attempt during `lease`, in a single
+    /// statement so that two workers never send it at the same time. Returns
+    /// `None` when the delivery is not due or another worker holds it.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn claim(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        lease: std::time::Duration,
+    ) -> ModelResult<Option<Self>> {
+        let now = Local::now();
+        let locked_until = now + chrono::Duration::from_std(lease).unwrap_or(chrono::Duration::MAX);
+        let claimed = Entity::update_many()
+            .col_expr(
+                webhook_deliveries::Column::LockedUntil,
+                Expr::value(locked_until),
+            )
+            .col_expr(webhook_deliveries::Column::UpdatedAt, Expr::value(now))
+            .filter(webhook_deliveries::Column::Pid.eq(pid))
+            .filter(due())
+            .exec_with_returning(db)
+            .await?;
+        Ok(claimed.into_iter().next())
+    }
+
+    /// Lists a page of the deliveries to the endpoint, newest first
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn paginate_by_endpoint(
+        db: &DatabaseConnection,
+        endpoint: &webhook_endpoints::Model,
+        pagination: &query::PaginationQuery,
+    ) -> Result<PageResponse<Self>> {
+        query::paginate(
+            db,
+            Entity::find().order_by_desc(webhook_deliveries::Column::Id),
+            Some(
+                query::condition()
+                    .eq(webhook_deliveries::Column::WebhookEndpointId, endpoint.id)
+                    .build(),
+            ),
+            pagination,
+        )
+        .await
+    }
+}
+
+impl ActiveModel {
+    /// Logs an attempt and releases the claim on the delivery. The delivery
+    /// succeeds on a 2xx response. Otherwise it is attempted again after
+    /// `retry_after`, or fails for good when that is `None`.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn record_attempt(
+        mut self,
+        db: &DatabaseConnection,
+        attempt: Attempt,
+        retry_after: Option<std::time::Duration>,
+    ) -> ModelResult<Model> {
+        let now = Local::now();
+        let mut next_attempt_at = None;
+        let status = if attempt.succeeded() {
+            self.delivered_at = ActiveValue::set(Some(now.into()));
+            STATUS_SUCCEEDED
+        } else if let Some(retry_after) = retry_after {
+            next_attempt_at = Some(
+                now + chrono::Duration::from_std(retry_after).unwrap_or(chrono::Duration::MAX),
+            );
+            STATUS_PENDING
+        } else {
+            STATUS_FAILED
+        };
+        self.next_attempt_at = ActiveValue::set(next_attempt_at.map(Into::into));
+        self.locked_until = ActiveValue::set(None);
+
+        self.attempts = ActiveValue::set(self.attempts.as_ref() + 1);
+        self.status = ActiveValue::set(status.to_string());
+        self.response_status = ActiveValue::set(attempt.response_status.map(i32::from));
+        self.response_body = ActiveValue::set(
+            attempt
+                .response_body
+                .map(|body| body.chars().take(MAX_RESPONSE_BODY).collect()),
+        );
+        self.error = ActiveValue::set(attempt.error);
+        self.update(db).await.map_err(ModelError::from)
+    }
+}
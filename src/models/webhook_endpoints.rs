@@ -0,0 +1,143 @@
+use loco_rs::{hash, prelude::*};
+use sea_orm::QueryOrder;
+use uuid::Uuid;
+
+pub use super::_entities::webhook_endpoints::{self, ActiveModel, Entity, Model};
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            Ok(this)
+        } else if self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+/// Returns a new random signing secret
+#[must_use]
+pub fn generate_secret() -> String {
+    format!("whsec_{}", hash::random_string(32))
+}
+
+impl Model {
+    /// Registers an endpoint that receives the given event types
+    ///
+    /// # Errors
+    ///
+    /// When could not save the endpoint into the DB
+    pub async fn create(
+        db: &DatabaseConnection,
+        url: &str,
+        event_types: &[String],
+        secret: String,
+    ) -> ModelResult<Self> {
+        let endpoint = ActiveModel {
+            url: ActiveValue::set(url.to_string()),
+            secret: ActiveValue::set(secret),
+            event_types: ActiveValue::set(
+                serde_json::to_value(event_types).map_err(ModelError::wrap)?,
+            ),
+            active: ActiveValue::set(true),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(endpoint)
+    }
+
+    /// Lists every endpoint, oldest first
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn list(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        Ok(Entity::find()
+            .order_by_asc(webhook_endpoints::Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    /// Finds an endpoint by pid
+    ///
+    /// # Errors
+    ///
+    /// When the endpoint does not exist or DB query error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let pid = Uuid::parse_str(pid).map_err(|_| ModelError::EntityNotFound)?;
+        Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(webhook_endpoints::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or(ModelError::EntityNotFound)
+    }
+
+    /// Active endpoints subscribed to the event
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn subscribed_to(db: &DatabaseConnection, event: &str) -> ModelResult<Vec<Self>> {
+        let endpoints = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(webhook_endpoints::Column::Active, true)
+                    .build(),
+            )
+            .order_by_asc(webhook_endpoints::Column::Id)
+            .all(db)
+            .await?;
+        Ok(endpoints
+            .into_iter()
+            .filter(|endpoint| endpoint.event_types().iter().any(|kind| kind == event))
+            .collect())
+    }
+
+    /// The event types the endpoint is subscribed to
+    #[must_use]
+    pub fn event_types(&self) -> Vec<String> {
+        serde_json::from_value(self.event_types.clone()).unwrap_or_default()
+    }
+}
+
+impl ActiveModel {
+    /// Changes the URL, subscribed event types or active state of the
+    /// endpoint. `None` keeps the current value.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn update_endpoint(
+        mut self,
+        db: &DatabaseConnection,
+        url: Option<String>,
+        event_types: Option<Vec<String>>,
+        active: Option<bool>,
+    ) -> ModelResult<Model> {
+        if let Some(url) = url {
+            self.url = ActiveValue::set(url);
+        }
+        if let Some(event_types) = event_types {
+            self.event_types =
+                ActiveValue::set(serde_json::to_value(event_types).map_err(ModelError::wrap)?);
+        }
+        if let Some(active) = active {
+            self.active = ActiveValue::set(active);
+        }
+        self.update(db).await.map_err(ModelError::from)
+    }
+}
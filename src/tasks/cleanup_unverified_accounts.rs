@@ -0,0 +1,76 @@
+use chrono::{offset::Local, Duration};
+use loco_rs::prelude::*;
+
+use crate::{
+    common::{account, settings::Settings, unverified_accounts::Action},
+    mailers::auth::AuthMailer,
+    models::users,
+};
+
+pub struct CleanupUnverifiedAccounts;
+#[async_trait]
+impl Task for CleanupUnverifiedAccounts {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "cleanup_unverified_accounts".to_string(),
+            detail: "Remind, warn and finally delete accounts that never verified their email nor logged in. Usage: cleanup_unverified_accounts [dry_run:true]"
+                .to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, vars: &task::Vars) -> Result<()> {
+        let dry_run = vars.cli_arg("dry_run").is_ok_and(|value| value == "true");
+        let settings = Settings::from_config(&app_context.config)?.unverified_accounts;
+        settings.validate()?;
+
+        let now = Local::now();
+        let users = users::Model::find_unverified_created_before(
+            &app_context.db,
+            now - Duration::days(settings.remind_after_days),
+        )
+        .await?;
+
+        for user in users {
+            let Some(action) = settings.next_action(&user, now.fixed_offset()) else {
+                continue;
+            };
+            if dry_run {
+                println!("would {} {}", action.as_str(), user.email);
+                continue;
+            }
+
+            let pid = user.pid.to_string();
+            let email = user.email.clone();
+            match action {
+                Action::Remind => {
+                    let user = user
+                        .into_active_model()
+                        .set_email_verification_sent(&app_context.db)
+                        .await?;
+                    AuthMailer::send_welcome(app_context, &user).await?;
+                    tracing::info!(pid, "verification reminder sent to unverified account");
+                }
+                Action::Warn => {
+                    let user = user
+                        .into_active_model()
+                        .set_email_verification_sent(&app_context.db)
+                        .await?;
+                    AuthMailer::send_verification_warning(
+                        app_context,
+                        &user,
+                        settings.notice_days(),
+                    )
+                    .await?;
+                    tracing::info!(pid, "deletion warning sent to unverified account");
+                }
+                Action::Delete => {
+                    account::delete(app_context, user).await?;
+                    tracing::info!(pid, "unverified account deleted");
+                }
+            }
+            println!("{} {email}", action.as_str());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+use loco_rs::prelude::*;
+
+use crate::mailers::outbox;
+
+pub struct DrainEmailOutbox;
+#[async_trait]
+impl Task for DrainEmailOutbox {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "drain_email_outbox".to_string(),
+            detail: "Send every email left queued in the outbox".to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, _vars: &task::Vars) -> Result<()> {
+        let sent = outbox::drain(app_context).await?;
+        tracing::info!(sent, "email outbox drained");
+        Ok(())
+    }
+}
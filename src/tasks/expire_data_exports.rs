@@ -0,0 +1,34 @@
+use chrono::{offset::Local, Duration};
+use loco_rs::prelude::*;
+
+use crate::{
+    common::data_export::{self, EXPORT_LINK_EXPIRATION_HOURS},
+    models::data_exports,
+};
+
+pub struct ExpireDataExports;
+#[async_trait]
+impl Task for ExpireDataExports {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "expire_data_exports".to_string(),
+            detail: "Delete export archives whose download link has expired".to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, _vars: &task::Vars) -> Result<()> {
+        let exports = data_exports::Model::find_created_before(
+            &app_context.db,
+            Local::now() - Duration::hours(EXPORT_LINK_EXPIRATION_HOURS),
+        )
+        .await?;
+
+        for export in exports {
+            let export_id = export.pid.to_string();
+            data_export::delete(app_context, export).await?;
+            tracing::info!(export_id, "expired data export deleted");
+        }
+
+        Ok(())
+    }
+}
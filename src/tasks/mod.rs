@@ -1 +1,10 @@
-
+pub mod cleanup_unverified_accounts;
+pub mod drain_email_outbox;
+pub mod expire_data_exports;
+pub mod preview_mailer;
+pub mod prune_usage_counters;
+pub mod purge_deleted_accounts;
+pub mod retry_webhook_deliveries;
+pub mod set_admin;
+pub mod suspend_user;
+pub mod unsuspend_user;
@@ -0,0 +1,42 @@
+use loco_rs::prelude::*;
+
+use crate::mailers::{auth::AuthEmail, preview};
+
+pub struct PreviewMailer;
+#[async_trait]
+impl Task for PreviewMailer {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "preview_mailer".to_string(),
+            detail: "Render a mailer template with sample data, or list the templates when none is given. Usage: preview_mailer [template:<name>] [format:subject|text|html]"
+                .to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, vars: &task::Vars) -> Result<()> {
+        let Ok(template) = vars.cli_arg("template") else {
+            for template in AuthEmail::TEMPLATES {
+                println!("{template}");
+            }
+            return Ok(());
+        };
+
+        let email = preview::render(app_context, template).map_err(|err| match err {
+            Error::NotFound => Error::string(&format!("unknown mailer template `{template}`")),
+            err => err,
+        })?;
+
+        match vars.cli_arg("format").ok().map(String::as_str) {
+            None => {
+                println!("Subject: {}\n", email.subject);
+                println!("--- text ---\n{}\n", email.text);
+                println!("--- html ---\n{}", email.html);
+            }
+            Some("subject") => println!("{}", email.subject),
+            Some("text") => println!("{}", email.text),
+            Some("html") => println!("{}", email.html),
+            Some(_) => return Err(Error::string("format must be subject, text or html")),
+        }
+        Ok(())
+    }
+}
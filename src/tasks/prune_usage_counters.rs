@@ -0,0 +1,23 @@
+use chrono::Utc;
+use loco_rs::prelude::*;
+
+use crate::common::plans::Plans;
+
+pub struct PruneUsageCounters;
+#[async_trait]
+impl Task for PruneUsageCounters {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "prune_usage_counters".to_string(),
+            detail: "Delete daily request counters past their retention".to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, _vars: &task::Vars) -> Result<()> {
+        let deleted = Plans::from_context(app_context)
+            .prune_request_counters(&app_context.db, Utc::now())
+            .await?;
+        tracing::info!(deleted, "usage counters pruned");
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use loco_rs::prelude::*;
+
+use crate::{common::account, models::users};
+
+pub struct PurgeDeletedAccounts;
+#[async_trait]
+impl Task for PurgeDeletedAccounts {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "purge_deleted_accounts".to_string(),
+            detail: "Delete accounts whose deletion grace period has elapsed".to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, _vars: &task::Vars) -> Result<()> {
+        let users = users::Model::find_due_for_deletion(&app_context.db).await?;
+
+        for user in users {
+            let pid = user.pid.to_string();
+            account::delete(app_context, user).await?;
+            tracing::info!(pid, "account purged after deletion grace period");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+use loco_rs::prelude::*;
+
+use crate::common::webhooks;
+
+pub struct RetryWebhookDeliveries;
+#[async_trait]
+impl Task for RetryWebhookDeliveries {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "retry_webhook_deliveries".to_string(),
+            detail: "Attempt every webhook delivery whose retry is due".to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, _vars: &task::Vars) -> Result<()> {
+        let attempted = webhooks::retry_due(app_context).await?;
+        tracing::info!(attempted, "due webhook deliveries attempted");
+        Ok(())
+    }
+}
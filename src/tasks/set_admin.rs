@@ -0,0 +1,32 @@
+use loco_rs::prelude::*;
+
+use crate::models::users;
+
+pub struct SetAdmin;
+#[async_trait]
+impl Task for SetAdmin {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "set_admin".to_string(),
+            detail: "Grant or revoke admin access. Usage: set_admin email:<email> [admin:false]"
+                .to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, vars: &task::Vars) -> Result<()> {
+        let email = vars.cli_arg("email")?;
+        let is_admin = vars
+            .cli_arg("admin")
+            .map_or(Ok(true), |value| value.parse::<bool>())
+            .map_err(|_| Error::string("admin must be `true` or `false`"))?;
+
+        let user = users::Model::find_by_email(&app_context.db, email).await?;
+        let user = user
+            .into_active_model()
+            .set_admin(&app_context.db, is_admin)
+            .await?;
+
+        println!("{} is_admin={}", user.email, user.is_admin);
+        Ok(())
+    }
+}
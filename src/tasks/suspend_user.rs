@@ -0,0 +1,41 @@
+use chrono::DateTime;
+use loco_rs::prelude::*;
+
+use crate::models::users;
+
+pub struct SuspendUser;
+#[async_trait]
+impl Task for SuspendUser {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "suspend_user".to_string(),
+            detail: "Suspend an account. Usage: suspend_user email:<email> [reason:<reason>] [until:<RFC 3339 date>]"
+                .to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, vars: &task::Vars) -> Result<()> {
+        let email = vars.cli_arg("email")?;
+        let reason = vars.cli_arg("reason").ok().cloned();
+        let until = vars
+            .cli_arg("until")
+            .ok()
+            .map(|value| DateTime::parse_from_rfc3339(value))
+            .transpose()
+            .map_err(|_| Error::string("until must be an RFC 3339 date"))?;
+
+        let user = users::Model::find_by_email(&app_context.db, email).await?;
+        let user = user
+            .into_active_model()
+            .suspend(&app_context.db, reason, until)
+            .await?;
+
+        println!(
+            "{} suspended until {}",
+            user.email,
+            user.suspended_until
+                .map_or_else(|| "it is lifted".to_string(), |until| until.to_rfc3339())
+        );
+        Ok(())
+    }
+}
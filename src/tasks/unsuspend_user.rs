@@ -0,0 +1,25 @@
+use loco_rs::prelude::*;
+
+use crate::models::users;
+
+pub struct UnsuspendUser;
+#[async_trait]
+impl Task for UnsuspendUser {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "unsuspend_user".to_string(),
+            detail: "Lift the suspension of an account. Usage: unsuspend_user email:<email>"
+                .to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, vars: &task::Vars) -> Result<()> {
+        let email = vars.cli_arg("email")?;
+
+        let user = users::Model::find_by_email(&app_context.db, email).await?;
+        let user = user.into_active_model().unsuspend(&app_context.db).await?;
+
+        println!("{} unsuspended", user.email);
+        Ok(())
+    }
+}
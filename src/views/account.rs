@@ -0,0 +1,48 @@
+use loco_rs::model::query::PageResponse;
+use serde::{Deserialize, Serialize};
+
+use crate::models::_entities::audit_events;
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct AuditEventResponse {
+    pub event: String,
+    pub detail: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: String,
+}
+
+impl AuditEventResponse {
+    #[must_use]
+    pub fn new(event: &audit_events::Model) -> Self {
+        Self {
+            event: event.event.clone(),
+            detail: event.detail.clone(),
+            ip_address: event.ip_address.clone(),
+            user_agent: event.user_agent.clone(),
+            created_at: event.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct ActivityResponse {
+    pub events: Vec<AuditEventResponse>,
+    #[ts(type = "number")]
+    pub total_pages: u64,
+    #[ts(type = "number")]
+    pub total_items: u64,
+}
+
+impl ActivityResponse {
+    #[must_use]
+    pub fn new(page: &PageResponse<audit_events::Model>) -> Self {
+        Self {
+            events: page.page.iter().map(AuditEventResponse::new).collect(),
+            total_pages: page.total_pages,
+            total_items: page.total_items,
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use loco_rs::model::query::PageResponse;
+use serde::{Deserialize, Serialize};
+
+use crate::models::_entities::{impersonation_logs, users};
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct AdminUserResponse {
+    pub pid: String,
+    pub email: String,
+    pub name: String,
+    pub is_admin: bool,
+    pub is_verified: bool,
+    pub is_disabled: bool,
+    pub is_suspended: bool,
+    pub created_at: String,
+    pub email_verified_at: Option<String>,
+    pub disabled_at: Option<String>,
+    pub deletion_requested_at: Option<String>,
+    pub suspension_reason: Option<String>,
+    pub suspended_until: Option<String>,
+    /// Assigned plan, `None` when the user is on the default plan
+    pub plan: Option<String>,
+}
+
+impl AdminUserResponse {
+    #[must_use]
+    pub fn new(user: &users::Model) -> Self {
+        Self {
+            pid: user.pid.to_string(),
+            email: user.email.clone(),
+            name: user.name.clone(),
+            is_admin: user.is_admin,
+            is_verified: user.email_verified_at.is_some(),
+            is_disabled: user.disabled_at.is_some(),
+            is_suspended: user.is_suspended(),
+            created_at: user.created_at.to_rfc3339(),
+            email_verified_at: user.email_verified_at.map(|at| at.to_rfc3339()),
+            disabled_at: user.disabled_at.map(|at| at.to_rfc3339()),
+            deletion_requested_at: user.deletion_requested_at.map(|at| at.to_rfc3339()),
+            suspension_reason: user.suspension_reason.clone(),
+            suspended_until: user.suspended_until.map(|at| at.to_rfc3339()),
+            plan: user.plan.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct AdminUserListResponse {
+    pub users: Vec<AdminUserResponse>,
+    #[ts(type = "number")]
+    pub total_pages: u64,
+    #[ts(type = "number")]
+    pub total_items: u64,
+}
+
+impl AdminUserListResponse {
+    #[must_use]
+    pub fn new(page: &PageResponse<users::Model>) -> Self {
+        Self {
+            users: page.page.iter().map(AdminUserResponse::new).collect(),
+            total_pages: page.total_pages,
+            total_items: page.total_items,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct ImpersonationResponse {
+    pub token: String,
+    pub pid: String,
+    pub name: String,
+    pub email: String,
+    pub impersonator_pid: String,
+    /// unix timestamp at which the impersonation token expires
+    #[ts(type = "number")]
+    pub expires_at: i64,
+}
+
+impl ImpersonationResponse {
+    #[must_use]
+    pub fn new(
+        user: &users::Model,
+        impersonator: &users::Model,
+        token: &str,
+        expires_at: i64,
+    ) -> Self {
+        Self {
+            token: token.to_string(),
+            pid: user.pid.to_string(),
+            name: user.name.clone(),
+            email: user.email.clone(),
+            impersonator_pid: impersonator.pid.to_string(),
+            expires_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct ImpersonationLogResponse {
+    pub event: String,
+    pub impersonator_pid: String,
+    pub user_pid: String,
+    pub ip_address: Option<String>,
+    pub created_at: String,
+}
+
+impl ImpersonationLogResponse {
+    #[must_use]
+    pub fn new(log: &impersonation_logs::Model) -> Self {
+        Self {
+            event: log.event.clone(),
+            impersonator_pid: log.impersonator_pid.to_string(),
+            user_pid: log.user_pid.to_string(),
+            ip_address: log.ip_address.clone(),
+            created_at: log.created_at.to_rfc3339(),
+        }
+    }
+}
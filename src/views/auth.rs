@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+use super::profile::AvatarUrls;
 use crate::models::_entities::users;
 
 #[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
@@ -10,6 +11,7 @@ pub struct LoginResponse {
     pub name: String,
     pub is_verified: bool,
     pub email: String,
+    pub avatar: Option<AvatarUrls>,
 }
 
 impl LoginResponse {
@@ -21,6 +23,7 @@ impl LoginResponse {
             name: user.name.clone(),
             is_verified: user.email_verified_at.is_some(),
             email: user.email.clone(),
+            avatar: AvatarUrls::new(user),
         }
     }
 }
@@ -32,6 +35,9 @@ pub struct CurrentResponse {
     pub pid: String,
     pub name: String,
     pub email: String,
+    pub avatar: Option<AvatarUrls>,
+    /// email of the administrator impersonating this user, if any
+    pub impersonated_by: Option<String>,
 }
 
 impl CurrentResponse {
@@ -42,6 +48,14 @@ impl CurrentResponse {
             pid: user.pid.to_string(),
             name: user.name.clone(),
             email: user.email.clone(),
+            avatar: AvatarUrls::new(user),
+            impersonated_by: None,
         }
     }
+
+    #[must_use]
+    pub fn with_impersonator(mut self, impersonator: &users::Model) -> Self {
+        self.impersonated_by = Some(impersonator.email.clone());
+        self
+    }
 }
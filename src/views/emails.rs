@@ -0,0 +1,118 @@
+use loco_rs::model::query::PageResponse;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{email_outbox, email_suppressions};
+
+/// A message of the email outbox. The rendered bodies are left out.
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct EmailMessageResponse {
+    pub pid: String,
+    pub template: String,
+    pub recipient: String,
+    pub subject: String,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub provider_message_id: Option<String>,
+    pub created_at: String,
+    pub sent_at: Option<String>,
+    /// When the one-time links in the message were redacted. Such messages
+    /// cannot be resent.
+    pub redacted_at: Option<String>,
+}
+
+impl EmailMessageResponse {
+    #[must_use]
+    pub fn new(message: &email_outbox::Model) -> Self {
+        Self {
+            pid: message.pid.to_string(),
+            template: message.template.clone(),
+            recipient: message.recipient.clone(),
+            subject: message.subject.clone(),
+            status: message.status.clone(),
+            attempts: message.attempts,
+            last_error: message.last_error.clone(),
+            provider_message_id: message.provider_message_id.clone(),
+            created_at: message.created_at.to_rfc3339(),
+            sent_at: message.sent_at.map(|at| at.to_rfc3339()),
+            redacted_at: message.redacted_at.map(|at| at.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct EmailMessageListResponse {
+    pub messages: Vec<EmailMessageResponse>,
+    #[ts(type = "number")]
+    pub total_pages: u64,
+    #[ts(type = "number")]
+    pub total_items: u64,
+}
+
+impl EmailMessageListResponse {
+    #[must_use]
+    pub fn new(page: &PageResponse<email_outbox::Model>) -> Self {
+        Self {
+            messages: page.page.iter().map(EmailMessageResponse::new).collect(),
+            total_pages: page.total_pages,
+            total_items: page.total_items,
+        }
+    }
+}
+
+/// An address emails are no longer sent to.
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct EmailSuppressionResponse {
+    pub pid: String,
+    pub email: String,
+    /// `bounce` or `complaint`
+    pub reason: String,
+    /// Format of the notification that suppressed the address
+    pub source: String,
+    pub detail: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl EmailSuppressionResponse {
+    #[must_use]
+    pub fn new(suppression: &email_suppressions::Model) -> Self {
+        Self {
+            pid: suppression.pid.to_string(),
+            email: suppression.email.clone(),
+            reason: suppression.reason.clone(),
+            source: suppression.source.clone(),
+            detail: suppression.detail.clone(),
+            created_at: suppression.created_at.to_rfc3339(),
+            updated_at: suppression.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct EmailSuppressionListResponse {
+    pub suppressions: Vec<EmailSuppressionResponse>,
+    #[ts(type = "number")]
+    pub total_pages: u64,
+    #[ts(type = "number")]
+    pub total_items: u64,
+}
+
+impl EmailSuppressionListResponse {
+    #[must_use]
+    pub fn new(page: &PageResponse<email_suppressions::Model>) -> Self {
+        Self {
+            suppressions: page
+                .page
+                .iter()
+                .map(EmailSuppressionResponse::new)
+                .collect(),
+            total_pages: page.total_pages,
+            total_items: page.total_items,
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::feature_flags::FlagRule;
+
+/// Flags evaluated for the current user, by key
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct FlagsResponse {
+    pub flags: BTreeMap<String, bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct FeatureFlagResponse {
+    pub key: String,
+    pub enabled: bool,
+    pub percentage: u8,
+    pub user_pids: Vec<String>,
+    /// Whether the rule is stored in the database rather than coming from
+    /// the configuration defaults
+    pub overridden: bool,
+}
+
+impl FeatureFlagResponse {
+    #[must_use]
+    pub fn new(key: String, rule: FlagRule, overridden: bool) -> Self {
+        Self {
+            key,
+            enabled: rule.enabled,
+            percentage: rule.percentage,
+            user_pids: rule.user_pids,
+            overridden,
+        }
+    }
+}
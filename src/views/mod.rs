@@ -1 +1,9 @@
+pub mod account;
+pub mod admin;
 pub mod auth;
+pub mod emails;
+pub mod flags;
+pub mod notifications;
+pub mod profile;
+pub mod usage;
+pub mod webhooks;
@@ -0,0 +1,57 @@
+use loco_rs::model::query::PageResponse;
+use serde::{Deserialize, Serialize};
+
+use crate::models::_entities::notifications;
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct NotificationResponse {
+    pub pid: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[ts(type = "unknown")]
+    pub payload: serde_json::Value,
+    pub read_at: Option<String>,
+    pub created_at: String,
+}
+
+impl NotificationResponse {
+    #[must_use]
+    pub fn new(notification: &notifications::Model) -> Self {
+        Self {
+            pid: notification.pid.to_string(),
+            kind: notification.kind.clone(),
+            payload: notification.payload.clone(),
+            read_at: notification.read_at.map(|at| at.to_rfc3339()),
+            created_at: notification.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct NotificationListResponse {
+    pub notifications: Vec<NotificationResponse>,
+    #[ts(type = "number")]
+    pub total_pages: u64,
+    #[ts(type = "number")]
+    pub total_items: u64,
+}
+
+impl NotificationListResponse {
+    #[must_use]
+    pub fn new(page: &PageResponse<notifications::Model>) -> Self {
+        Self {
+            notifications: page.page.iter().map(NotificationResponse::new).collect(),
+            total_pages: page.total_pages,
+            total_items: page.total_items,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct UnreadCountResponse {
+    #[ts(type = "number")]
+    pub unread: u64,
+}
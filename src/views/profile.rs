@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{common::avatar, models::_entities::users};
+
+/// URLs of the avatar thumbnails of a user.
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct AvatarUrls {
+    pub small: String,
+    pub medium: String,
+    pub large: String,
+}
+
+impl AvatarUrls {
+    #[must_use]
+    pub fn new(user: &users::Model) -> Option<Self> {
+        user.avatar_key.as_deref().map(|key| Self {
+            small: avatar::url(key, "small"),
+            medium: avatar::url(key, "medium"),
+            large: avatar::url(key, "large"),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct ProfileResponse {
+    pub pid: String,
+    pub email: String,
+    pub name: String,
+    pub display_name: Option<String>,
+    pub timezone: Option<String>,
+    pub locale: Option<String>,
+    pub bio: Option<String>,
+    pub avatar: Option<AvatarUrls>,
+}
+
+impl ProfileResponse {
+    #[must_use]
+    pub fn new(user: &users::Model) -> Self {
+        Self {
+            pid: user.pid.to_string(),
+            email: user.email.clone(),
+            name: user.name.clone(),
+            display_name: user.display_name.clone(),
+            timezone: user.timezone.clone(),
+            locale: user.preferences().locale,
+            bio: user.bio.clone(),
+            avatar: AvatarUrls::new(user),
+        }
+    }
+}
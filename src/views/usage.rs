@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use crate::common::plans::{PlanSettings, Quota, QuotaUsage};
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct QuotaUsageResponse {
+    pub quota: Quota,
+    #[ts(type = "number")]
+    pub used: u64,
+    /// `null` when the plan does not limit this quota
+    #[ts(type = "number | null")]
+    pub limit: Option<u64>,
+    pub resets_at: Option<String>,
+}
+
+impl From<QuotaUsage> for QuotaUsageResponse {
+    fn from(usage: QuotaUsage) -> Self {
+        Self {
+            quota: usage.quota,
+            used: usage.used,
+            limit: usage.limit,
+            resets_at: usage.resets_at.map(|at| at.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct UsageResponse {
+    pub plan: String,
+    pub plan_name: String,
+    pub quotas: Vec<QuotaUsageResponse>,
+}
+
+impl UsageResponse {
+    #[must_use]
+    pub fn new(key: &str, plan: PlanSettings, usage: Vec<QuotaUsage>) -> Self {
+        Self {
+            plan: key.to_string(),
+            plan_name: plan.name.unwrap_or_else(|| key.to_string()),
+            quotas: usage.into_iter().map(QuotaUsageResponse::from).collect(),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use loco_rs::model::query::PageResponse;
+use serde::{Deserialize, Serialize};
+
+use crate::models::_entities::{webhook_deliveries, webhook_endpoints};
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct WebhookEndpointResponse {
+    pub pid: String,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub active: bool,
+    pub created_at: String,
+    /// Only returned when the endpoint is created
+    pub secret: Option<String>,
+}
+
+impl WebhookEndpointResponse {
+    #[must_use]
+    pub fn new(endpoint: &webhook_endpoints::Model) -> Self {
+        Self {
+            pid: endpoint.pid.to_string(),
+            url: endpoint.url.clone(),
+            event_types: endpoint.event_types(),
+            active: endpoint.active,
+            created_at: endpoint.created_at.to_rfc3339(),
+            secret: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_secret(endpoint: &webhook_endpoints::Model) -> Self {
+        Self {
+            secret: Some(endpoint.secret.clone()),
+            ..Self::new(endpoint)
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct WebhookDeliveryResponse {
+    pub pid: String,
+    pub event: String,
+    #[ts(type = "unknown")]
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub delivered_at: Option<String>,
+    /// When a failed delivery is attempted again
+    pub next_attempt_at: Option<String>,
+}
+
+impl WebhookDeliveryResponse {
+    #[must_use]
+    pub fn new(delivery: &webhook_deliveries::Model) -> Self {
+        Self {
+            pid: delivery.pid.to_string(),
+            event: delivery.event.clone(),
+            payload: delivery.payload.clone(),
+            status: delivery.status.clone(),
+            attempts: delivery.attempts,
+            response_status: delivery.response_status,
+            response_body: delivery.response_body.clone(),
+            error: delivery.error.clone(),
+            created_at: delivery.created_at.to_rfc3339(),
+            delivered_at: delivery.delivered_at.map(|at| at.to_rfc3339()),
+            next_attempt_at: delivery.next_attempt_at.map(|at| at.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct WebhookDeliveryListResponse {
+    pub deliveries: Vec<WebhookDeliveryResponse>,
+    #[ts(type = "number")]
+    pub total_pages: u64,
+    #[ts(type = "number")]
+    pub total_items: u64,
+}
+
+impl WebhookDeliveryListResponse {
+    #[must_use]
+    pub fn new(page: &PageResponse<webhook_deliveries::Model>) -> Self {
+        Self {
+            deliveries: page.page.iter().map(WebhookDeliveryResponse::new).collect(),
+            total_pages: page.total_pages,
+            total_items: page.total_items,
+        }
+    }
+}
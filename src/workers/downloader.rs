@@ -1,6 +1,23 @@
+use std::io::{Cursor, Write};
+
+use axum::body::Bytes;
+use chrono::Utc;
 use loco_rs::prelude::*;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use crate::{
+    common::{avatar, data_export},
+    mailers::auth::AuthMailer,
+    models::{
+        audit_events, data_exports, email_outbox, impersonation_logs, notifications,
+        usage_counters, users,
+    },
+};
 
+/// Builds an export of the personal data of a user, stores it and emails the
+/// user a signed download link.
 pub struct DownloadWorker {
     pub ctx: AppContext,
 }
@@ -15,9 +32,172 @@ impl BackgroundWorker<DownloadWorkerArgs> for DownloadWorker {
     fn build(ctx: &AppContext) -> Self {
         Self { ctx: ctx.clone() }
     }
-    async fn perform(&self, _args: DownloadWorkerArgs) -> Result<()> {
-        // TODO: Some actual work goes here...
+    async fn perform(&self, args: DownloadWorkerArgs) -> Result<()> {
+        let user = users::Model::find_by_pid(&self.ctx.db, &args.user_guid).await?;
+
+        let archive = self.archive(&user).await?;
+        // Recorded before the upload so the archive is always found by the
+        // expiry task, even when the rest of the job fails
+        let export_id = data_exports::Model::create(&self.ctx.db, &user)
+            .await?
+            .pid
+            .to_string();
+        self.ctx
+            .storage
+            .upload(
+                &data_export::storage_path(&export_id),
+                &Bytes::from(archive),
+            )
+            .await?;
 
+        let url = data_export::signed_url(&self.ctx, &export_id)?;
+        AuthMailer::send_data_export(&self.ctx, &user, &url).await?;
+        notifications::Model::notify(
+            &self.ctx.db,
+            &user,
+            notifications::KIND_DATA_EXPORT_READY,
+            json!({ "url": url }),
+        )
+        .await?;
+
+        tracing::info!(pid = user.pid.to_string(), export_id, "data export created");
         Ok(())
     }
 }
+
+impl DownloadWorker {
+    /// Collects everything stored about the user. Credentials and one time
+    /// tokens are left out.
+    async fn collect(&self, user: &users::Model) -> Result<serde_json::Value> {
+        let impersonations = impersonation_logs::Model::find_by_user(&self.ctx.db, user)
+            .await?
+            .into_iter()
+            .map(|log| {
+                json!({
+                    "event": log.event,
+                    "impersonator_pid": log.impersonator_pid,
+                    "ip_address": log.ip_address,
+                    "created_at": log.created_at,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let activity = audit_events::Model::find_by_user(&self.ctx.db, user)
+            .await?
+            .into_iter()
+            .map(|event| {
+                json!({
+                    "event": event.event,
+                    "detail": event.detail,
+                    "ip_address": event.ip_address,
+                    "user_agent": event.user_agent,
+                    "created_at": event.created_at,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let notifications = notifications::Model::find_by_user(&self.ctx.db, user)
+            .await?
+            .into_iter()
+            .map(|notification| {
+                json!({
+                    "type": notification.kind,
+                    "payload": notification.payload,
+                    "read_at": notification.read_at,
+                    "created_at": notification.created_at,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let emails = email_outbox::Model::find_by_user(&self.ctx.db, user)
+            .await?
+            .into_iter()
+            .map(|message| {
+                json!({
+                    "template": message.template,
+                    "recipient": message.recipient,
+                    "subject": message.subject,
+                    "status": message.status,
+                    "created_at": message.created_at,
+                    "sent_at": message.sent_at,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let usage = usage_counters::Model::find_by_user(&self.ctx.db, user)
+            .await?
+            .into_iter()
+            .map(|counter| {
+                json!({
+                    "metric": counter.metric,
+                    "bucket": counter.bucket,
+                    "value": counter.value,
+                    "updated_at": counter.updated_at,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(json!({
+            "exported_at": Utc::now(),
+            "account": {
+                "pid": user.pid,
+                "email": user.email,
+                "name": user.name,
+                "created_at": user.created_at,
+                "updated_at": user.updated_at,
+                "email_verified_at": user.email_verified_at,
+                "last_login_at": user.last_login_at,
+                "is_admin": user.is_admin,
+                "plan": user.plan,
+                "disabled_at": user.disabled_at,
+                "deletion_requested_at": user.deletion_requested_at,
+                "suspended_at": user.suspended_at,
+                "suspension_reason": user.suspension_reason,
+                "suspended_until": user.suspended_until,
+            },
+            "profile": {
+                "display_name": user.display_name,
+                "timezone": user.timezone,
+                "bio": user.bio,
+                "avatar_key": user.avatar_key,
+            },
+            "preferences": user.preferences(),
+            "activity": activity,
+            "impersonations": impersonations,
+            "notifications": notifications,
+            "emails": emails,
+            "usage": usage,
+        }))
+    }
+
+    /// Writes the collected data as `data.json` into a ZIP archive, along
+    /// with the avatar thumbnails if the user has any.
+    async fn archive(&self, user: &users::Model) -> Result<Vec<u8>> {
+        let data = serde_json::to_vec_pretty(&self.collect(user).await?)?;
+
+        let mut avatars = Vec::new();
+        if let Some(key) = &user.avatar_key {
+            for (size, _) in avatar::AVATAR_SIZES {
+                let bytes: Vec<u8> = self
+                    .ctx
+                    .storage
+                    .download(&avatar::storage_path(key, size))
+                    .await?;
+                avatars.push((format!("avatar/{size}.png"), bytes));
+            }
+        }
+
+        let zip_err = |err: zip::result::ZipError| Error::Message(err.to_string());
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+
+        zip.start_file("data.json", options).map_err(zip_err)?;
+        zip.write_all(&data)?;
+        for (name, bytes) in avatars {
+            zip.start_file(name, options).map_err(zip_err)?;
+            zip.write_all(&bytes)?;
+        }
+
+        Ok(zip.finish().map_err(zip_err)?.into_inner())
+    }
+}
@@ -0,0 +1,35 @@
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::mailers::outbox;
+
+/// Sends a message recorded in the email outbox, see [`outbox::dispatch`].
+///
+/// Retries wait inside the job, so a failing mail server never fails the
+/// request that queued the email. The outcome is recorded on the message.
+pub struct MailWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct MailWorkerArgs {
+    pub outbox_pid: String,
+}
+
+#[async_trait]
+impl BackgroundWorker<MailWorkerArgs> for MailWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+    async fn perform(&self, args: MailWorkerArgs) -> Result<()> {
+        let pid = Uuid::parse_str(&args.outbox_pid).map_err(|_| Error::NotFound)?;
+        if outbox::dispatch(&self.ctx, pid).await?.is_none() {
+            tracing::debug!(
+                outbox_pid = args.outbox_pid,
+                "email already handled by another dispatcher"
+            );
+        }
+        Ok(())
+    }
+}
@@ -1 +1,3 @@
 pub mod downloader;
+pub mod mailer;
+pub mod webhook;
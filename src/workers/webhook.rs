@@ -0,0 +1,35 @@
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::common::webhooks;
+
+/// Makes one attempt at a stored webhook delivery, see
+/// [`webhooks::deliver`]. Failed attempts are scheduled on the delivery and
+/// picked up again by the `retry_webhook_deliveries` task, so no job waits
+/// between attempts and a restart loses nothing.
+pub struct WebhookWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct WebhookWorkerArgs {
+    pub delivery_pid: String,
+}
+
+#[async_trait]
+impl BackgroundWorker<WebhookWorkerArgs> for WebhookWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+    async fn perform(&self, args: WebhookWorkerArgs) -> Result<()> {
+        let pid = Uuid::parse_str(&args.delivery_pid).map_err(|_| Error::NotFound)?;
+        if webhooks::deliver(&self.ctx, pid).await?.is_none() {
+            tracing::debug!(
+                delivery = args.delivery_pid,
+                "webhook delivery not due or handled by another worker"
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1 @@
+<p>Hi {{ name }}</p>
@@ -0,0 +1 @@
+Hi {{ name }}, your code is {{ missingCode }}.
@@ -0,0 +1,179 @@
+use std::path::Path;
+
+use insta::assert_snapshot;
+use loco_nuxt_template::{
+    app::App,
+    mailers::{
+        self,
+        auth::{AuthEmail, AuthMailer},
+        layout, preview, LOCALES,
+    },
+    models::preferences::Preferences,
+};
+use loco_rs::testing::prelude::*;
+use serial_test::parallel;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        settings.set_snapshot_suffix("auth_mailer");
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+/// One email of every variant
+fn every_email() -> Vec<AuthEmail> {
+    let emails = vec![
+        AuthEmail::Welcome,
+        AuthEmail::ForgotPassword,
+        AuthEmail::MagicLink,
+        AuthEmail::AccountDeletion,
+        AuthEmail::DataExport {
+            download_url: "https://example.com/export".to_string(),
+        },
+        AuthEmail::PasswordChanged,
+        AuthEmail::NewDeviceLogin {
+            ip_address: None,
+            user_agent: "loco-test".to_string(),
+        },
+        AuthEmail::EmailVerified,
+        AuthEmail::VerificationWarning { days_left: 7 },
+    ];
+    // Stops compiling when a variant is added, as a reminder to list it above
+    for email in &emails {
+        match email {
+            AuthEmail::Welcome
+            | AuthEmail::ForgotPassword
+            | AuthEmail::MagicLink
+            | AuthEmail::AccountDeletion
+            | AuthEmail::DataExport { .. }
+            | AuthEmail::PasswordChanged
+            | AuthEmail::NewDeviceLogin { .. }
+            | AuthEmail::EmailVerified
+            | AuthEmail::VerificationWarning { .. } => {}
+        }
+    }
+    emails
+}
+
+#[test]
+fn templates_list_every_email() {
+    let templates: Vec<&str> = every_email().iter().map(AuthEmail::template).collect();
+    assert_eq!(templates, AuthEmail::TEMPLATES);
+    for template in AuthEmail::TEMPLATES {
+        assert_eq!(
+            preview::sample(template).as_ref().map(AuthEmail::template),
+            Some(template),
+            "{template} has no preview sample"
+        );
+    }
+}
+
+#[test]
+fn every_locale_has_every_template() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/mailers/auth");
+    for template in AuthEmail::TEMPLATES {
+        for locale in LOCALES {
+            for file in ["subject.t", "text.t", "html.t"] {
+                let path = root.join(template).join(locale).join(file);
+                assert!(path.is_file(), "missing {}", path.display());
+            }
+        }
+
+        for entry in std::fs::read_dir(root.join(template)).unwrap() {
+            let name = entry.unwrap().file_name();
+            let name = name.to_string_lossy();
+            assert!(
+                LOCALES.contains(&name.as_ref()),
+                "{template}/{name} is not a supported locale"
+            );
+        }
+    }
+}
+
+#[test]
+fn every_locale_has_a_layout_footer() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/mailers/layout/partials/footer");
+    for locale in LOCALES {
+        let path = root.join(format!("{locale}.html"));
+        assert!(path.is_file(), "missing {}", path.display());
+    }
+}
+
+#[test]
+fn picks_supported_locale() {
+    assert_eq!(mailers::supported_locale("es"), Some("es"));
+    assert_eq!(mailers::supported_locale("es-MX"), Some("es"));
+    assert_eq!(mailers::supported_locale("EN-gb"), Some("en"));
+    assert_eq!(mailers::supported_locale("fr"), None);
+
+    assert_eq!(mailers::accept_language("es-ES,es;q=0.9"), Some("es"));
+    assert_eq!(
+        mailers::accept_language("fr;q=1, en;q=0.5, es;q=0.8"),
+        Some("es")
+    );
+    assert_eq!(mailers::accept_language("es;q=0, en"), Some("en"));
+    assert_eq!(mailers::accept_language("fr-CH, *;q=0.5"), None);
+    assert_eq!(mailers::accept_language(""), None);
+}
+
+#[tokio::test]
+#[parallel]
+async fn renders_in_user_locale_with_fallback() {
+    let boot = boot_test::<App>().await.unwrap();
+    let ctx = &boot.app_context;
+
+    let with_locale = |locale: Option<&str>| {
+        let mut user = preview::sample_user();
+        user.preferences = Some(
+            serde_json::to_value(Preferences {
+                locale: locale.map(ToString::to_string),
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+        user
+    };
+
+    let email = AuthMailer::render(ctx, &with_locale(Some("es-MX")), &AuthEmail::Welcome).unwrap();
+    assert_eq!(email.subject.trim_end(), "Bienvenido, Preview User");
+
+    for locale in [None, Some("fr")] {
+        let email = AuthMailer::render(ctx, &with_locale(locale), &AuthEmail::Welcome).unwrap();
+        assert_eq!(email.subject.trim_end(), "Welcome Preview User");
+    }
+}
+
+#[tokio::test]
+#[parallel]
+async fn wraps_html_in_branded_layout() {
+    configure_insta!();
+
+    let boot = boot_test::<App>().await.unwrap();
+    let ctx = &boot.app_context;
+
+    let email = AuthMailer::render(ctx, &preview::sample_user(), &AuthEmail::Welcome).unwrap();
+    assert_snapshot!(email.html);
+
+    let mut user = preview::sample_user();
+    user.preferences = Some(serde_json::json!({ "locale": "es" }));
+    let email = AuthMailer::render(ctx, &user, &AuthEmail::MagicLink).unwrap();
+    assert!(email.html.contains("<html lang=\"es\">"));
+    assert!(email.html.contains("El equipo de Loco"));
+    assert!(email
+        .html
+        .contains("/magic-link?token=sample-magic-link-token"));
+    assert!(!email.html.contains("<style>"));
+}
+
+#[test]
+fn inlines_css() {
+    let html = layout::inline_css(
+        "<html><head><style>.button { color: red; }</style></head>\
+         <body><a class=\"button\" href=\"#\">Go</a></body></html>",
+    )
+    .unwrap();
+    assert!(html.contains("style=\"color: red;\""), "{html}");
+    assert!(!html.contains("<style>"), "{html}");
+}
@@ -0,0 +1,61 @@
+use loco_nuxt_template::mailers::file::{FileTransport, INDEX};
+use loco_rs::mailer::Email;
+use uuid::Uuid;
+
+/// A transport writing to a directory of its own, removed when dropped
+struct TempTransport(FileTransport);
+
+impl TempTransport {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("mail-{}", Uuid::new_v4()));
+        Self(FileTransport::new(path))
+    }
+}
+
+impl Drop for TempTransport {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(self.0.path());
+    }
+}
+
+fn email(to: &str) -> Email {
+    Email {
+        from: Some("Loco <noreply@loco.rs>".to_string()),
+        to: to.to_string(),
+        reply_to: None,
+        subject: "Hello".to_string(),
+        text: "plain body".to_string(),
+        html: "<p>html body</p>".to_string(),
+        bcc: None,
+        cc: None,
+    }
+}
+
+#[tokio::test]
+async fn writes_emails_and_lists_them_in_the_index() {
+    let transport = TempTransport::new();
+    let transport = &transport.0;
+    assert!(transport.deliveries().await.unwrap().is_empty());
+
+    let first = transport.send(&email("first@loco.com")).await.unwrap();
+    let second = transport.send(&email("second@loco.com")).await.unwrap();
+
+    let deliveries = transport.deliveries().await.unwrap();
+    assert_eq!(
+        deliveries
+            .iter()
+            .map(|delivery| delivery.id.as_str())
+            .collect::<Vec<_>>(),
+        vec![first.as_str(), second.as_str()]
+    );
+    assert!(transport.path().join(INDEX).exists());
+
+    let found = transport.find(&second).await.unwrap().unwrap();
+    assert_eq!(found.to, "second@loco.com");
+    assert_eq!(found.subject, "Hello");
+    assert_eq!(found.text, "plain body");
+
+    let eml = transport.read(&first).await.unwrap();
+    assert!(eml.contains("To: first@loco.com"));
+    assert!(eml.contains("Content-Type: multipart/alternative"));
+}
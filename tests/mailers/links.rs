@@ -0,0 +1,109 @@
+use chrono::Duration;
+use loco_nuxt_template::{
+    app::App,
+    common::links::Links,
+    mailers::{
+        auth::{AuthEmail, AuthMailer},
+        preview,
+    },
+};
+use loco_rs::testing::prelude::*;
+use serial_test::parallel;
+
+fn split(link: &str) -> (String, Option<String>) {
+    let url = url::Url::parse(link).unwrap();
+    (url.path().to_string(), url.query().map(ToString::to_string))
+}
+
+#[test]
+fn builds_typed_links() {
+    let links = Links::new("https://app.example.com/", "secret");
+    assert_eq!(
+        links.verify_email("abc").to_string(),
+        "https://app.example.com/api/auth/verify/abc"
+    );
+    assert_eq!(
+        links.reset_password("a b&c").to_string(),
+        "https://app.example.com/forgot?token=a+b%26c"
+    );
+    assert_eq!(
+        links.magic_link("abc").to_string(),
+        "https://app.example.com/magic-link?token=abc"
+    );
+    assert_eq!(
+        links.cancel_deletion("abc").to_string(),
+        "https://app.example.com/api/account/cancel-deletion/abc"
+    );
+    assert_eq!(
+        links.forgot_password().to_string(),
+        "https://app.example.com/forgot"
+    );
+}
+
+#[test]
+fn verifies_signed_links() {
+    let links = Links::new("https://app.example.com", "secret");
+    let link = links
+        .link("/download")
+        .query("file", "report")
+        .signed(Duration::hours(1))
+        .to_string();
+    let (path, query) = split(&link);
+    assert!(links.verify(&path, query.as_deref()));
+
+    let other_secret = Links::new("https://app.example.com", "other");
+    assert!(!other_secret.verify(&path, query.as_deref()));
+    assert!(!links.verify("/elsewhere", query.as_deref()));
+
+    let tampered = query.as_deref().unwrap().replace("report", "secrets");
+    assert!(!links.verify(&path, Some(&tampered)));
+
+    let unsigned = links.link("/download").query("file", "report").to_string();
+    let (path, query) = split(&unsigned);
+    assert!(!links.verify(&path, query.as_deref()));
+
+    let expired = links
+        .link("/download")
+        .signed(Duration::seconds(-1))
+        .to_string();
+    let (path, query) = split(&expired);
+    assert!(!links.verify(&path, query.as_deref()));
+}
+
+#[tokio::test]
+#[parallel]
+async fn every_auth_email_links_to_the_frontend() {
+    let boot = boot_test::<App>().await.unwrap();
+    let ctx = &boot.app_context;
+    ctx.shared_store
+        .insert(Links::new("https://app.example.com", "secret"));
+
+    let user = preview::sample_user();
+    for (email, link) in [
+        (
+            AuthEmail::Welcome,
+            "https://app.example.com/api/auth/verify/sample-verification-token",
+        ),
+        (
+            AuthEmail::ForgotPassword,
+            "https://app.example.com/forgot?token=sample-reset-token",
+        ),
+        (
+            AuthEmail::MagicLink,
+            "https://app.example.com/magic-link?token=sample-magic-link-token",
+        ),
+        (
+            AuthEmail::AccountDeletion,
+            "https://app.example.com/api/account/cancel-deletion/sample-deletion-token",
+        ),
+        (AuthEmail::PasswordChanged, "https://app.example.com/forgot"),
+    ] {
+        let rendered = AuthMailer::render(ctx, &user, &email).unwrap();
+        assert!(rendered.text.contains(link), "{email:?}: {}", rendered.text);
+        assert!(
+            rendered.html.contains(&format!("href=\"{link}\"")),
+            "{email:?}: {}",
+            rendered.html
+        );
+    }
+}
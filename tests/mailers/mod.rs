@@ -0,0 +1,3 @@
+mod auth;
+mod file;
+mod links;
@@ -1,3 +1,4 @@
+mod mailers;
 mod models;
 mod prepare;
 mod requests;
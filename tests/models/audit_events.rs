@@ -0,0 +1,50 @@
+use loco_nuxt_template::{
+    app::App,
+    models::audit_events::{self, Model},
+};
+use loco_rs::{model::query::PaginationQuery, testing::prelude::*};
+use serial_test::parallel;
+
+use crate::prepare::users::create_random_user;
+
+#[tokio::test]
+#[parallel]
+async fn can_record_and_list_events() -> anyhow::Result<()> {
+    let boot = boot_test::<App>().await?;
+    let db = &boot.app_context.db;
+
+    let user = create_random_user(db).await?;
+    let other = create_random_user(db).await?;
+
+    Model::record(
+        db,
+        &user,
+        audit_events::EVENT_LOGIN_FAILED,
+        Some("invalid_password"),
+        Some("127.0.0.1".to_string()),
+        Some("loco-test".to_string()),
+    )
+    .await?;
+    Model::record(db, &user, audit_events::EVENT_LOGIN, None, None, None).await?;
+    Model::record(db, &other, audit_events::EVENT_LOGIN, None, None, None).await?;
+
+    let events = Model::find_by_user(db, &user).await?;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event, audit_events::EVENT_LOGIN);
+    assert_eq!(events[1].detail.as_deref(), Some("invalid_password"));
+    assert_eq!(events[1].ip_address.as_deref(), Some("127.0.0.1"));
+
+    let page = Model::paginate_by_user(
+        db,
+        &user,
+        &PaginationQuery {
+            page_size: 1,
+            page: 1,
+        },
+    )
+    .await?;
+    assert_eq!(page.total_items, 2);
+    assert_eq!(page.page.len(), 1);
+
+    Ok(())
+}
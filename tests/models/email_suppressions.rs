@@ -0,0 +1,38 @@
+use loco_nuxt_template::{
+    app::App,
+    models::email_suppressions::{Model, REASON_BOUNCE, REASON_COMPLAINT},
+};
+use loco_rs::testing::prelude::*;
+use serial_test::parallel;
+
+#[tokio::test]
+#[parallel]
+async fn can_suppress_and_lift() -> anyhow::Result<()> {
+    let boot = boot_test::<App>().await?;
+    let db = &boot.app_context.db;
+
+    let email = "Bounced.Model@Loco.com";
+    assert!(!Model::is_suppressed(db, email).await?);
+
+    let suppression = Model::suppress(db, email, REASON_BOUNCE, "generic", Some("550")).await?;
+    assert_eq!(suppression.email, "bounced.model@loco.com");
+    assert!(Model::is_suppressed(db, "bounced.model@loco.com").await?);
+    assert!(Model::is_suppressed(db, " BOUNCED.MODEL@LOCO.COM").await?);
+
+    let again = Model::suppress(db, email, REASON_COMPLAINT, "sendgrid", None).await?;
+    assert_eq!(
+        again.pid, suppression.pid,
+        "Suppressing again should update"
+    );
+    assert_eq!(again.reason, REASON_COMPLAINT);
+    assert_eq!(again.source, "sendgrid");
+    assert_eq!(again.detail, None);
+
+    Model::find_by_pid(db, &suppression.pid.to_string())
+        .await?
+        .lift(db)
+        .await?;
+    assert!(!Model::is_suppressed(db, email).await?);
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use loco_nuxt_template::{
+    app::App,
+    common::feature_flags::{bucket, FeatureFlags, FlagRule},
+    models::feature_flags::Model,
+};
+use loco_rs::testing::prelude::*;
+use serial_test::parallel;
+use uuid::Uuid;
+
+use crate::prepare::users::create_random_user;
+
+#[test]
+fn percentage_rollout_is_deterministic() {
+    let pids = (0..1000)
+        .map(|_| Uuid::new_v4().to_string())
+        .collect::<Vec<_>>();
+    let rule = |percentage| FlagRule {
+        percentage,
+        ..Default::default()
+    };
+
+    for pid in &pids {
+        assert_eq!(bucket("rollout", pid), bucket("rollout", pid));
+        assert!(!rule(0).evaluate("rollout", Some(pid)));
+        assert!(rule(100).evaluate("rollout", Some(pid)));
+        if rule(10).evaluate("rollout", Some(pid)) {
+            assert!(
+                rule(20).evaluate("rollout", Some(pid)),
+                "Raising the percentage should keep earlier users"
+            );
+        }
+    }
+
+    let enabled = pids
+        .iter()
+        .filter(|pid| rule(25).evaluate("rollout", Some(pid)))
+        .count();
+    assert!(
+        (150..350).contains(&enabled),
+        "{enabled} of 1000 users enabled"
+    );
+    assert!(
+        !rule(100).evaluate("rollout", None),
+        "Anonymous visitors are not part of rollouts"
+    );
+}
+
+#[test]
+fn explicit_users_and_global_switch() {
+    let pid = Uuid::new_v4().to_string();
+    let listed = FlagRule {
+        user_pids: vec![pid.clone()],
+        ..Default::default()
+    };
+    assert!(listed.evaluate("beta", Some(&pid)));
+    assert!(!listed.evaluate("beta", Some(&Uuid::new_v4().to_string())));
+
+    let global = FlagRule {
+        enabled: true,
+        ..Default::default()
+    };
+    assert!(global.evaluate("beta", None));
+}
+
+#[tokio::test]
+#[parallel]
+async fn stored_rules_override_defaults() -> anyhow::Result<()> {
+    let boot = boot_test::<App>().await?;
+    let ctx = &boot.app_context;
+    let user = create_random_user(&ctx.db).await?;
+
+    let flags = FeatureFlags::from_context(ctx);
+    assert!(flags.is_enabled(&ctx.db, "config_enabled", None).await?);
+    assert!(
+        !flags
+            .is_enabled(&ctx.db, "model_unknown", Some(&user))
+            .await?
+    );
+
+    Model::upsert(
+        &ctx.db,
+        "model_override",
+        &FlagRule {
+            user_pids: vec![user.pid.to_string()],
+            ..Default::default()
+        },
+    )
+    .await?;
+    assert!(
+        flags
+            .is_enabled(&ctx.db, "model_override", Some(&user))
+            .await?
+    );
+    assert!(!flags.is_enabled(&ctx.db, "model_override", None).await?);
+
+    let evaluated = flags.evaluate(&ctx.db, Some(&user)).await?;
+    assert_eq!(evaluated.get("model_override"), Some(&true));
+    assert_eq!(evaluated.get("config_enabled"), Some(&true));
+    assert_eq!(evaluated.get("config_disabled"), Some(&false));
+
+    assert!(Model::remove(&ctx.db, "model_override").await?);
+    assert!(
+        !flags
+            .is_enabled(&ctx.db, "model_override", Some(&user))
+            .await?
+    );
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use loco_nuxt_template::{
+    app::App,
+    models::impersonation_logs::{self, Impersonation, Model},
+};
+use loco_rs::{auth::jwt, testing::prelude::*};
+use sea_orm::ModelTrait;
+use serial_test::parallel;
+
+use crate::prepare::users::{create_admin_user, create_random_user};
+
+#[tokio::test]
+#[parallel]
+async fn can_record_impersonation_events() -> anyhow::Result<()> {
+    let boot = boot_test::<App>().await?;
+
+    let admin = create_admin_user(&boot.app_context.db).await?;
+    let user = create_random_user(&boot.app_context.db).await?;
+
+    Model::record(
+        &boot.app_context.db,
+        impersonation_logs::EVENT_START,
+        &admin,
+        &user,
+        Some("127.0.0.1".to_string()),
+    )
+    .await?;
+    Model::record(
+        &boot.app_context.db,
+        impersonation_logs::EVENT_STOP,
+        &admin,
+        &user,
+        None,
+    )
+    .await?;
+
+    let logs = Model::find_by_user(&boot.app_context.db, &user).await?;
+
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0].event, impersonation_logs::EVENT_STOP);
+    assert_eq!(logs[1].event, impersonation_logs::EVENT_START);
+    assert_eq!(logs[1].impersonator_pid, admin.pid);
+    assert_eq!(logs[1].ip_address.as_deref(), Some("127.0.0.1"));
+
+    user.clone().delete(&boot.app_context.db).await?;
+    let logs = Model::find_by_user(&boot.app_context.db, &user).await?;
+    assert_eq!(logs.len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_read_impersonation_from_claims() -> anyhow::Result<()> {
+    let boot = boot_test::<App>().await?;
+
+    let admin = create_admin_user(&boot.app_context.db).await?;
+    let user = create_random_user(&boot.app_context.db).await?;
+    let jwt_secret = boot.app_context.config.get_jwt_config()?;
+
+    let impersonation = Impersonation::start(&admin);
+    let token = user.generate_impersonation_jwt(&jwt_secret.secret, &impersonation)?;
+    let claims = jwt::JWT::new(&jwt_secret.secret).validate(&token)?.claims;
+
+    assert_eq!(Impersonation::from_claims(&claims), Some(impersonation));
+
+    let token = user.generate_jwt(&jwt_secret.secret, jwt_secret.expiration)?;
+    let claims = jwt::JWT::new(&jwt_secret.secret).validate(&token)?.claims;
+
+    assert_eq!(Impersonation::from_claims(&claims), None);
+
+    Ok(())
+}
@@ -1 +1,7 @@
+mod audit_events;
+mod email_suppressions;
+mod feature_flags;
+mod impersonation_logs;
+mod notifications;
+mod usage_counters;
 mod users;
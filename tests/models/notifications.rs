@@ -0,0 +1,50 @@
+use loco_nuxt_template::{
+    app::App,
+    models::notifications::{self, Model},
+};
+use loco_rs::{model::query::PaginationQuery, testing::prelude::*};
+use sea_orm::IntoActiveModel;
+use serial_test::parallel;
+
+use crate::prepare::users::create_random_user;
+
+#[tokio::test]
+#[parallel]
+async fn can_notify_and_mark_read() -> anyhow::Result<()> {
+    let boot = boot_test::<App>().await?;
+    let db = &boot.app_context.db;
+
+    let user = create_random_user(db).await?;
+    let other = create_random_user(db).await?;
+
+    let first = Model::notify(
+        db,
+        &user,
+        notifications::KIND_DATA_EXPORT_READY,
+        serde_json::json!({ "url": "https://example.com" }),
+    )
+    .await?;
+    Model::notify(db, &user, "welcome", serde_json::json!({})).await?;
+    Model::notify(db, &other, "welcome", serde_json::json!({})).await?;
+    assert_eq!(Model::unread_count(db, &user).await?, 2);
+
+    let read = first.into_active_model().mark_read(db).await?;
+    assert!(read.read_at.is_some());
+    assert_eq!(Model::unread_count(db, &user).await?, 1);
+
+    let unread = Model::paginate_by_user(db, &user, true, &PaginationQuery::default()).await?;
+    assert_eq!(unread.total_items, 1);
+    assert_eq!(unread.page[0].kind, "welcome");
+
+    assert!(
+        Model::find_by_pid(db, &other, &read.pid.to_string())
+            .await
+            .is_err(),
+        "Notifications should only be found for their owner"
+    );
+
+    assert_eq!(Model::mark_all_read(db, &user).await?, 1);
+    assert_eq!(Model::unread_count(db, &user).await?, 0);
+    assert_eq!(Model::unread_count(db, &other).await?, 1);
+    Ok(())
+}
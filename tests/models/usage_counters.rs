@@ -0,0 +1,86 @@
+use loco_nuxt_template::{app::App, models::usage_counters::Model};
+use loco_rs::testing::prelude::*;
+use serial_test::parallel;
+
+use crate::prepare::users::create_random_user;
+
+#[tokio::test]
+#[parallel]
+async fn can_increment_within_limit() -> anyhow::Result<()> {
+    let boot = boot_test::<App>().await?;
+    let db = &boot.app_context.db;
+
+    let user = create_random_user(db).await?;
+    let other = create_random_user(db).await?;
+
+    assert_eq!(Model::value(db, &user, "requests", "2026-10-18").await?, 0);
+    assert_eq!(
+        Model::increment_within(db, &user, "requests", "2026-10-18", 1, Some(2)).await?,
+        Some(1)
+    );
+    assert_eq!(
+        Model::increment_within(db, &user, "requests", "2026-10-18", 1, Some(2)).await?,
+        Some(2)
+    );
+    assert_eq!(
+        Model::increment_within(db, &user, "requests", "2026-10-18", 1, Some(2)).await?,
+        None,
+        "Increments over the limit should be refused"
+    );
+    assert_eq!(Model::value(db, &user, "requests", "2026-10-18").await?, 2);
+
+    assert_eq!(
+        Model::increment_within(db, &user, "requests", "2026-10-19", 5, None).await?,
+        Some(5),
+        "Buckets should be counted separately"
+    );
+    assert_eq!(Model::value(db, &other, "requests", "2026-10-18").await?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[parallel]
+async fn concurrent_increments_respect_limit() -> anyhow::Result<()> {
+    let boot = boot_test::<App>().await?;
+    let db = &boot.app_context.db;
+
+    let user = create_random_user(db).await?;
+
+    let attempts =
+        (0..20).map(|_| Model::increment_within(db, &user, "requests", "day", 1, Some(10)));
+    let counted = futures_util::future::try_join_all(attempts)
+        .await?
+        .into_iter()
+        .filter(Option::is_some)
+        .count();
+    assert_eq!(counted, 10);
+    assert_eq!(Model::value(db, &user, "requests", "day").await?, 10);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_set_and_total() -> anyhow::Result<()> {
+    let boot = boot_test::<App>().await?;
+    let db = &boot.app_context.db;
+
+    let user = create_random_user(db).await?;
+
+    Model::set(db, &user, "storage_bytes", "avatars", 300).await?;
+    Model::set(db, &user, "storage_bytes", "exports", 200).await?;
+    Model::set(db, &user, "storage_bytes", "avatars", 100).await?;
+
+    assert_eq!(
+        Model::value(db, &user, "storage_bytes", "avatars").await?,
+        100
+    );
+    assert_eq!(Model::total(db, &user, "storage_bytes", None).await?, 300);
+    assert_eq!(
+        Model::total(db, &user, "storage_bytes", Some("avatars")).await?,
+        200
+    );
+
+    Ok(())
+}
@@ -4,9 +4,12 @@ use fake::{faker::internet::en::Password, Fake};
 use insta::assert_debug_snapshot;
 use loco_nuxt_template::{
     app::App,
-    models::users::{self, Model, RegisterParams},
+    models::users::{self, Model, RegisterParams, UpdateProfileParams},
+};
+use loco_rs::{
+    model::{Authenticable, ModelError},
+    testing::prelude::*,
 };
-use loco_rs::testing::prelude::*;
 use sea_orm::{ActiveModelTrait, ActiveValue, IntoActiveModel};
 use serial_test::parallel;
 
@@ -324,3 +327,175 @@ async fn magic_link() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[parallel]
+async fn can_request_and_cancel_deletion() -> anyhow::Result<()> {
+    configure_insta!();
+
+    let boot = boot_test::<App>().await?;
+    let user = create_random_user(&boot.app_context.db).await?;
+    let pid = user.pid.to_string();
+
+    assert!(!user.is_pending_deletion());
+
+    let user = user
+        .into_active_model()
+        .request_deletion(&boot.app_context.db)
+        .await?;
+
+    assert!(user.is_pending_deletion());
+    let token = user
+        .deletion_token
+        .clone()
+        .expect("Deletion token should be generated");
+
+    let found = Model::find_by_deletion_token(&boot.app_context.db, &token).await?;
+    assert_eq!(found.pid.to_string(), pid);
+
+    let user = user
+        .into_active_model()
+        .cancel_deletion(&boot.app_context.db)
+        .await?;
+
+    assert!(!user.is_pending_deletion());
+    assert!(user.deletion_token.is_none());
+    assert!(Model::find_by_deletion_token(&boot.app_context.db, &token)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_find_by_email_when_pending_deletion() -> anyhow::Result<()> {
+    configure_insta!();
+
+    let boot = boot_test::<App>().await?;
+    let user = create_random_user(&boot.app_context.db)
+        .await?
+        .into_active_model()
+        .request_deletion(&boot.app_context.db)
+        .await?;
+
+    // pending users are still returned so that the email stays reserved and
+    // callers can decide how to treat them
+    let user = Model::find_by_email(&boot.app_context.db, &user.email).await?;
+
+    assert!(user.is_pending_deletion());
+    assert!(user.deletion_requested_at.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_find_due_for_deletion() -> anyhow::Result<()> {
+    configure_insta!();
+
+    let boot = boot_test::<App>().await?;
+
+    let recent = create_random_user(&boot.app_context.db)
+        .await?
+        .into_active_model()
+        .request_deletion(&boot.app_context.db)
+        .await?;
+
+    let expired = create_random_user(&boot.app_context.db)
+        .await?
+        .into_active_model()
+        .request_deletion(&boot.app_context.db)
+        .await?;
+    let mut expired = expired.into_active_model();
+    expired.deletion_requested_at = ActiveValue::set(Some(
+        (Local::now() - Duration::days(users::DELETION_GRACE_PERIOD_DAYS + 1)).into(),
+    ));
+    let expired = expired.update(&boot.app_context.db).await?;
+
+    let due = Model::find_due_for_deletion(&boot.app_context.db).await?;
+
+    assert!(due.iter().any(|u| u.id == expired.id));
+    assert!(!due.iter().any(|u| u.id == recent.id));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_update_profile() -> anyhow::Result<()> {
+    let boot = boot_test::<App>().await?;
+
+    let user = create_random_user(&boot.app_context.db).await?;
+
+    let params = UpdateProfileParams {
+        display_name: Some("Loco".to_string()),
+        timezone: Some("America/New_York".to_string()),
+        ..Default::default()
+    };
+    let user = user
+        .into_active_model()
+        .update_profile(&boot.app_context.db, &params)
+        .await?;
+
+    assert_eq!(user.display_name.as_deref(), Some("Loco"));
+    assert_eq!(user.timezone.as_deref(), Some("America/New_York"));
+
+    let params = UpdateProfileParams {
+        locale: Some("not a locale".to_string()),
+        ..Default::default()
+    };
+    let result = user
+        .into_active_model()
+        .update_profile(&boot.app_context.db, &params)
+        .await;
+
+    assert!(matches!(result, Err(ModelError::Validation(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_suspend_and_unsuspend() -> anyhow::Result<()> {
+    let boot = boot_test::<App>().await?;
+    let db = &boot.app_context.db;
+
+    let user = create_random_user(db).await?;
+    assert!(!user.is_suspended());
+
+    let user = user
+        .into_active_model()
+        .suspend(db, None, Some((Local::now() + Duration::days(1)).into()))
+        .await?;
+    assert!(user.is_suspended());
+    let result = <Model as Authenticable>::find_by_api_key(db, &user.api_key).await;
+    assert!(
+        result.is_err(),
+        "Suspended users cannot authenticate with an API key"
+    );
+
+    let user = user
+        .into_active_model()
+        .suspend(db, None, Some((Local::now() - Duration::days(1)).into()))
+        .await?;
+    assert!(!user.is_suspended(), "Expired suspensions no longer apply");
+
+    let user = user
+        .into_active_model()
+        .suspend(db, Some("abuse".to_string()), None)
+        .await?;
+    assert!(user.is_suspended());
+    assert_eq!(
+        user.check_suspension().unwrap_err().reason.as_deref(),
+        Some("abuse")
+    );
+
+    let user = user.into_active_model().unsuspend(db).await?;
+    assert!(!user.is_suspended());
+    assert!(<Model as Authenticable>::find_by_api_key(db, &user.api_key)
+        .await
+        .is_ok());
+
+    Ok(())
+}
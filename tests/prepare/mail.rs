@@ -0,0 +1,57 @@
+use loco_nuxt_template::{
+    mailers::{memory::MemoryTransport, Delivery},
+    models::users,
+};
+use loco_rs::app::AppContext;
+use url::Url;
+
+/// Emails sent to the user's address, oldest first. Captured in memory by
+/// the transport configured in config/test.yaml, each test context has its
+/// own.
+pub fn deliveries(ctx: &AppContext, user: &users::Model) -> Vec<Delivery> {
+    MemoryTransport::from_context(ctx)
+        .deliveries()
+        .into_iter()
+        .filter(|delivery| delivery.to == user.email)
+        .collect()
+}
+
+/// The last email sent to the user
+pub fn last_delivery(ctx: &AppContext, user: &users::Model) -> Delivery {
+    deliveries(ctx, user)
+        .pop()
+        .unwrap_or_else(|| panic!("an email should have been sent to {}", user.email))
+}
+
+/// The first link in the text body of the email that points to `path` or
+/// below it, such as `/api/auth/verify/`
+pub fn link(delivery: &Delivery, path: &str) -> Url {
+    delivery
+        .text
+        .split_whitespace()
+        .filter_map(|word| Url::parse(word).ok())
+        .find(|url| url.path().starts_with(path))
+        .unwrap_or_else(|| {
+            panic!(
+                "{:?} should link to {path}, the text is:\n{}",
+                delivery.subject, delivery.text
+            )
+        })
+}
+
+/// The path and query of a link, to request it from the test server
+pub fn local(url: &Url) -> String {
+    url.query().map_or_else(
+        || url.path().to_string(),
+        |query| format!("{}?{query}", url.path()),
+    )
+}
+
+/// A query parameter of a link, for links to frontend pages that pass it on
+/// to the API
+pub fn query_param(url: &Url, key: &str) -> String {
+    url.query_pairs()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.into_owned())
+        .unwrap_or_else(|| panic!("{url} should have a {key} query parameter"))
+}
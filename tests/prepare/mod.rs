@@ -1 +1,3 @@
+pub mod mail;
 pub mod users;
+pub mod webhooks;
@@ -15,7 +15,7 @@ pub async fn create_random_user(db: &DatabaseConnection) -> anyhow::Result<users
         name: Name().fake(),
     };
 
-    let user = Model::create_with_password(&db, &registration_params).await?;
+    let user = Model::create_with_password(db, &registration_params).await?;
 
     Ok(user)
 }
@@ -30,7 +30,26 @@ pub async fn create_random_user_with_password(
         name: Name().fake(),
     };
 
-    let user = Model::create_with_password(&db, &registration_params).await?;
+    let user = Model::create_with_password(db, &registration_params).await?;
 
     Ok(user)
 }
+
+pub async fn create_admin_user(db: &DatabaseConnection) -> anyhow::Result<users::Model> {
+    let user = create_random_user(db)
+        .await?
+        .into_active_model()
+        .set_admin(db, true)
+        .await?;
+
+    Ok(user)
+}
+
+pub fn auth_header(ctx: &AppContext, user: &users::Model) -> String {
+    let jwt_secret = ctx.config.get_jwt_config().unwrap();
+    let token = user
+        .generate_jwt(&jwt_secret.secret, jwt_secret.expiration)
+        .unwrap();
+
+    format!("Bearer {token}")
+}
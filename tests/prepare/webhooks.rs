@@ -0,0 +1,95 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use axum::{extract::State, http::HeaderMap, http::StatusCode, routing::post, Router};
+use loco_nuxt_template::{common::webhooks, models::webhook_deliveries};
+use loco_rs::app::AppContext;
+
+/// A request received by [`StandIn`]
+#[derive(Debug, Clone)]
+pub struct Received {
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+#[derive(Default)]
+struct StandInState {
+    received: Vec<Received>,
+    seen_deliveries: HashSet<String>,
+}
+
+/// Local HTTP server standing in for a webhook receiver. The first attempt of
+/// every delivery is answered with a 500 and retries with a 200.
+pub struct StandIn {
+    pub url: String,
+    state: Arc<Mutex<StandInState>>,
+}
+
+async fn receive(
+    State(state): State<Arc<Mutex<StandInState>>>,
+    headers: HeaderMap,
+    body: String,
+) -> StatusCode {
+    let delivery = headers
+        .get("x-webhook-delivery")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let mut state = state.lock().unwrap();
+    state.received.push(Received { headers, body });
+    if state.seen_deliveries.insert(delivery) {
+        StatusCode::INTERNAL_SERVER_ERROR
+    } else {
+        StatusCode::OK
+    }
+}
+
+impl StandIn {
+    pub async fn start() -> Self {
+        let state = Arc::new(Mutex::new(StandInState::default()));
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .with_state(state.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        Self { url, state }
+    }
+
+    /// Requests whose body contains `needle`
+    pub fn received_with(&self, needle: &str) -> Vec<Received> {
+        self.state
+            .lock()
+            .unwrap()
+            .received
+            .iter()
+            .filter(|received| received.body.contains(needle))
+            .cloned()
+            .collect()
+    }
+}
+
+/// URL of a local port nothing listens on
+pub async fn unreachable_url() -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    drop(listener);
+    url
+}
+
+/// Makes the next attempt at a pending delivery once its retry is due, as
+/// the `retry_webhook_deliveries` task would
+pub async fn retry(
+    ctx: &AppContext,
+    delivery: &webhook_deliveries::Model,
+) -> webhook_deliveries::Model {
+    for _ in 0..100 {
+        if let Some(delivery) = webhooks::deliver(ctx, delivery.pid).await.unwrap() {
+            return delivery;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    panic!("delivery {} should become due", delivery.pid);
+}
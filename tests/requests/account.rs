@@ -0,0 +1,267 @@
+use std::io::{Cursor, Read};
+
+use loco_nuxt_template::{
+    app::App,
+    models::{data_exports, notifications, usage_counters, users},
+    views::account::ActivityResponse,
+};
+use loco_rs::testing::prelude::*;
+use sea_orm::IntoActiveModel;
+use serial_test::parallel;
+
+use crate::prepare::{
+    mail,
+    users::{auth_header, create_random_user, create_random_user_with_password},
+};
+
+#[tokio::test]
+#[parallel]
+async fn can_request_account_deletion() {
+    request::<App, _, _>(|request, ctx| async move {
+        let password = "12341234";
+        let user = create_random_user_with_password(&ctx.db, password)
+            .await
+            .unwrap();
+
+        let jwt_secret = ctx.config.get_jwt_config().unwrap();
+        let token = user
+            .generate_jwt(&jwt_secret.secret, jwt_secret.expiration)
+            .unwrap();
+
+        let response = request
+            .delete("/api/account")
+            .add_header("Authorization", format!("Bearer {token}"))
+            .json(&serde_json::json!({ "password": password }))
+            .await;
+
+        assert_eq!(
+            response.status_code(),
+            200,
+            "Delete account request should succeed"
+        );
+
+        let user = users::Model::find_by_email(&ctx.db, &user.email)
+            .await
+            .expect("User should still exist during the grace period");
+        assert!(user.is_pending_deletion());
+
+        assert_eq!(
+            mail::deliveries(&ctx, &user).len(),
+            1,
+            "Cancellation email should be sent"
+        );
+
+        let login_response = request
+            .post("/api/auth/login")
+            .json(&serde_json::json!({
+                "email": user.email,
+                "password": password
+            }))
+            .await;
+        assert_eq!(
+            login_response.status_code(),
+            401,
+            "Login should be blocked while pending deletion"
+        );
+
+        let magic_link_response = request
+            .post("/api/auth/magic-link")
+            .json(&serde_json::json!({ "email": user.email }))
+            .await;
+        assert_eq!(magic_link_response.status_code(), 200);
+
+        let user = users::Model::find_by_email(&ctx.db, &user.email)
+            .await
+            .unwrap();
+        assert!(
+            user.magic_link_token.is_none(),
+            "Magic link should not be issued while pending deletion"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn cannot_request_account_deletion_with_invalid_password() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user_with_password(&ctx.db, "12341234")
+            .await
+            .unwrap();
+
+        let jwt_secret = ctx.config.get_jwt_config().unwrap();
+        let token = user
+            .generate_jwt(&jwt_secret.secret, jwt_secret.expiration)
+            .unwrap();
+
+        let response = request
+            .delete("/api/account")
+            .add_header("Authorization", format!("Bearer {token}"))
+            .json(&serde_json::json!({ "password": "invalid-password" }))
+            .await;
+
+        assert_eq!(response.status_code(), 401);
+
+        let user = users::Model::find_by_email(&ctx.db, &user.email)
+            .await
+            .unwrap();
+        assert!(!user.is_pending_deletion());
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_cancel_account_deletion() {
+    request::<App, _, _>(|request, ctx| async move {
+        let password = "12341234";
+        let user = create_random_user_with_password(&ctx.db, password)
+            .await
+            .unwrap()
+            .into_active_model()
+            .request_deletion(&ctx.db)
+            .await
+            .unwrap();
+
+        let deletion_token = user.deletion_token.clone().unwrap();
+        let response = request
+            .get(&format!("/api/account/cancel-deletion/{deletion_token}"))
+            .await;
+        assert_eq!(
+            response.status_code(),
+            200,
+            "Cancel deletion request should succeed"
+        );
+
+        let login_response = request
+            .post("/api/auth/login")
+            .json(&serde_json::json!({
+                "email": user.email,
+                "password": password
+            }))
+            .await;
+        assert_eq!(login_response.status_code(), 200);
+
+        let invalid_response = request
+            .get("/api/account/cancel-deletion/invalid-token")
+            .await;
+        assert_eq!(invalid_response.status_code(), 401);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_export_account_data() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+        usage_counters::Model::set(&ctx.db, &user, "requests", "2026-01-01", 7)
+            .await
+            .unwrap();
+
+        let response = request
+            .post("/api/account/export")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let deliveries = mail::deliveries(&ctx, &user);
+        assert_eq!(deliveries.len(), 1, "Download link should be emailed");
+        let link = mail::local(&mail::link(&deliveries[0], "/api/account/export/"));
+
+        let response = request.get(&link).await;
+        assert_eq!(response.status_code(), 200);
+        assert_eq!(response.header("content-type"), "application/zip");
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(response.as_bytes().to_vec())).unwrap();
+        let mut data = String::new();
+        archive
+            .by_name("data.json")
+            .unwrap()
+            .read_to_string(&mut data)
+            .unwrap();
+        let data: serde_json::Value = serde_json::from_str(&data).unwrap();
+        assert_eq!(data["account"]["email"], user.email);
+        assert!(
+            data["account"].get("password").is_none(),
+            "Credentials should not be exported"
+        );
+        assert!(data["account"].get("suspended_at").is_some());
+        assert!(data["account"].get("suspension_reason").is_some());
+        assert!(data["account"].get("suspended_until").is_some());
+        assert!(data["profile"].get("avatar_key").is_some());
+        assert_eq!(data["usage"][0]["metric"], "requests");
+        assert_eq!(data["usage"][0]["bucket"], "2026-01-01");
+        assert_eq!(data["usage"][0]["value"], 7);
+
+        let exports = data_exports::Model::find_by_user(&ctx.db, &user)
+            .await
+            .unwrap();
+        assert_eq!(exports.len(), 1);
+        assert!(link.contains(&exports[0].pid.to_string()));
+
+        let unread = notifications::Model::unread_count(&ctx.db, &user)
+            .await
+            .unwrap();
+        assert_eq!(unread, 1, "Export should also be announced in the inbox");
+
+        let tampered = link.replace("signature=", "signature=00");
+        assert_eq!(request.get(&tampered).await.status_code(), 401);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_list_account_activity() {
+    request::<App, _, _>(|request, ctx| async move {
+        let email = "activity@loco.com";
+        let password = "12341234";
+        request
+            .post("/api/auth/register")
+            .add_header("User-Agent", "loco-test")
+            .json(&serde_json::json!({ "name": "loco", "email": email, "password": password }))
+            .await;
+        request
+            .post("/api/auth/login")
+            .json(&serde_json::json!({ "email": email, "password": "wrong password" }))
+            .await;
+        request
+            .post("/api/auth/login")
+            .json(&serde_json::json!({ "email": email, "password": password }))
+            .await;
+
+        let user = users::Model::find_by_email(&ctx.db, email).await.unwrap();
+        let response = request
+            .get("/api/account/activity")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let activity = response.json::<ActivityResponse>();
+        let events: Vec<(&str, Option<&str>)> = activity
+            .events
+            .iter()
+            .map(|event| (event.event.as_str(), event.detail.as_deref()))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                ("login", None),
+                ("login_failed", Some("invalid_password")),
+                ("register", None),
+            ]
+        );
+        assert_eq!(activity.total_items, 3);
+        assert_eq!(activity.events[2].user_agent.as_deref(), Some("loco-test"));
+
+        let page = request
+            .get("/api/account/activity?page=2&page_size=2")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await
+            .json::<ActivityResponse>();
+        assert_eq!(page.total_pages, 2);
+        assert_eq!(page.events.len(), 1);
+    })
+    .await;
+}
@@ -0,0 +1,321 @@
+use loco_nuxt_template::{
+    app::App,
+    models::users,
+    views::{
+        admin::{
+            AdminUserListResponse, AdminUserResponse, ImpersonationLogResponse,
+            ImpersonationResponse,
+        },
+        auth::CurrentResponse,
+    },
+};
+use loco_rs::testing::prelude::*;
+use sea_orm::IntoActiveModel;
+use serial_test::parallel;
+
+use crate::prepare::{
+    mail,
+    users::{auth_header, create_admin_user, create_random_user, create_random_user_with_password},
+};
+
+#[tokio::test]
+#[parallel]
+async fn cannot_access_admin_endpoints_as_regular_user() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .get("/api/admin/users")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await;
+        assert_eq!(response.status_code(), 403);
+
+        let response = request.get("/api/admin/users").await;
+        assert_eq!(response.status_code(), 401);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_list_users_with_filters() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .get("/api/admin/users")
+            .add_query_param("email", &user.email)
+            .add_query_param("sort_by", "email")
+            .add_query_param("order", "asc")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let list = response.json::<AdminUserListResponse>();
+        assert_eq!(list.total_items, 1);
+        assert_eq!(list.users[0].pid, user.pid.to_string());
+
+        let response = request
+            .get("/api/admin/users")
+            .add_query_param("email", user.email.to_uppercase())
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        let list = response.json::<AdminUserListResponse>();
+        assert_eq!(list.total_items, 1);
+        assert_eq!(list.users[0].pid, user.pid.to_string());
+
+        let response = request
+            .get("/api/admin/users")
+            .add_query_param("email", "%")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.json::<AdminUserListResponse>().total_items, 0);
+
+        let response = request
+            .get("/api/admin/users")
+            .add_query_param("verified", "true")
+            .add_query_param("email", &user.email)
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.json::<AdminUserListResponse>().total_items, 0);
+
+        let response = request
+            .get("/api/admin/users")
+            .add_query_param("created_from", "not-a-date")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 400);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_manage_user_as_admin() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let password = "12341234";
+        let user = create_random_user_with_password(&ctx.db, password)
+            .await
+            .unwrap();
+        let base = format!("/api/admin/users/{}", user.pid);
+
+        let response = request
+            .get(&base)
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        assert!(!response.json::<AdminUserResponse>().is_verified);
+
+        let response = request
+            .post(&format!("{base}/verify"))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert!(response.json::<AdminUserResponse>().is_verified);
+
+        let response = request
+            .post(&format!("{base}/reset-password"))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let reloaded = users::Model::find_by_pid(&ctx.db, &user.pid.to_string())
+            .await
+            .unwrap();
+        assert!(reloaded.reset_token.is_some());
+        assert_eq!(mail::deliveries(&ctx, &user).len(), 1);
+
+        let response = request
+            .post(&format!("{base}/disable"))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert!(response.json::<AdminUserResponse>().is_disabled);
+
+        let login_payload = serde_json::json!({
+            "email": user.email,
+            "password": password
+        });
+        let login_response = request.post("/api/auth/login").json(&login_payload).await;
+        assert_eq!(login_response.status_code(), 401);
+        for path in [
+            "/api/auth/current",
+            "/api/profile",
+            "/api/usage",
+            "/api/flags",
+        ] {
+            let response = request
+                .get(path)
+                .add_header("Authorization", auth_header(&ctx, &user))
+                .await;
+            assert_eq!(
+                response.status_code(),
+                401,
+                "Tokens of disabled users should stop working on {path}"
+            );
+        }
+
+        request
+            .post(&format!("{base}/enable"))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        let login_response = request.post("/api/auth/login").json(&login_payload).await;
+        assert_eq!(login_response.status_code(), 200);
+
+        let response = request
+            .delete(&base)
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        assert!(users::Model::find_by_pid(&ctx.db, &user.pid.to_string())
+            .await
+            .is_err());
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn cannot_disable_own_account() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .post(&format!("/api/admin/users/{}/disable", admin.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 400);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_impersonate_user() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let user = create_random_user_with_password(&ctx.db, "12341234")
+            .await
+            .unwrap();
+
+        let response = request
+            .post(&format!("/api/admin/users/{}/impersonate", user.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let impersonation = response.json::<ImpersonationResponse>();
+        assert_eq!(impersonation.pid, user.pid.to_string());
+        assert_eq!(impersonation.impersonator_pid, admin.pid.to_string());
+        let token = format!("Bearer {}", impersonation.token);
+
+        let current = request
+            .get("/api/auth/current")
+            .add_header("Authorization", token.clone())
+            .await
+            .json::<CurrentResponse>();
+        assert_eq!(current.pid, user.pid.to_string());
+        assert_eq!(current.impersonated_by, Some(admin.email.clone()));
+
+        let delete_response = request
+            .delete("/api/account")
+            .add_header("Authorization", token.clone())
+            .json(&serde_json::json!({ "password": "12341234" }))
+            .await;
+        assert_eq!(
+            delete_response.status_code(),
+            403,
+            "Sensitive actions should be blocked while impersonating"
+        );
+
+        let stop_response = request
+            .post("/api/auth/stop-impersonation")
+            .add_header("Authorization", token)
+            .await;
+        assert_eq!(stop_response.status_code(), 200);
+
+        let logs = request
+            .get(&format!("/api/admin/users/{}/impersonations", user.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await
+            .json::<Vec<ImpersonationLogResponse>>();
+        let events: Vec<&str> = logs.iter().map(|log| log.event.as_str()).collect();
+        assert_eq!(events, vec!["stop", "start"]);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn impersonation_ends_when_impersonator_loses_admin() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let token = request
+            .post(&format!("/api/admin/users/{}/impersonate", user.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await
+            .json::<ImpersonationResponse>()
+            .token;
+        let token = format!("Bearer {token}");
+
+        let response = request
+            .get("/api/profile")
+            .add_header("Authorization", token.clone())
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        admin
+            .into_active_model()
+            .set_admin(&ctx.db, false)
+            .await
+            .unwrap();
+
+        for path in ["/api/auth/current", "/api/profile", "/api/usage"] {
+            let response = request
+                .get(path)
+                .add_header("Authorization", token.clone())
+                .await;
+            assert_eq!(
+                response.status_code(),
+                401,
+                "Impersonation tokens of former admins should stop working on {path}"
+            );
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn cannot_use_admin_endpoints_while_impersonating() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let user = create_random_user(&ctx.db).await.unwrap();
+        let other_admin = create_admin_user(&ctx.db).await.unwrap();
+
+        let token = request
+            .post(&format!("/api/admin/users/{}/impersonate", user.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await
+            .json::<ImpersonationResponse>()
+            .token;
+
+        let response = request
+            .get("/api/admin/users")
+            .add_header("Authorization", format!("Bearer {token}"))
+            .await;
+        assert_eq!(response.status_code(), 403);
+
+        let response = request
+            .post(&format!("/api/admin/users/{}/impersonate", other_admin.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(
+            response.status_code(),
+            400,
+            "Administrators cannot be impersonated"
+        );
+    })
+    .await;
+}
@@ -1,14 +1,23 @@
 use insta::{assert_debug_snapshot, with_settings};
 use loco_nuxt_template::{
     app::App,
-    models::users,
+    models::{
+        email_outbox,
+        preferences::{NotificationPreferences, Preferences},
+        users,
+    },
     views::auth::{CurrentResponse, LoginResponse},
 };
 use loco_rs::testing::prelude::*;
 use rstest::rstest;
 use serial_test::parallel;
 
-use crate::prepare::users::create_random_user;
+use sea_orm::IntoActiveModel;
+
+use crate::prepare::{
+    mail,
+    users::{auth_header, create_random_user, create_random_user_with_password},
+};
 
 // TODO: see how to dedup / extract this to app-local test utils
 // not to framework, because that would require a runtime dep on insta
@@ -57,6 +66,40 @@ async fn can_register() {
     .await;
 }
 
+#[tokio::test]
+#[parallel]
+async fn registers_with_locale_from_accept_language() {
+    request::<App, _, _>(|request, ctx| async move {
+        let email = "accept-language@loco.com";
+        let payload = serde_json::json!({
+            "name": "loco",
+            "email": email,
+            "password": "12341234"
+        });
+
+        let response = request
+            .post("/api/auth/register")
+            .add_header("Accept-Language", "fr-CH, es-MX;q=0.9, en;q=0.8")
+            .json(&payload)
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let user = users::Model::find_by_email(&ctx.db, email).await.unwrap();
+        assert_eq!(user.preferences().locale.as_deref(), Some("es"));
+
+        let messages = email_outbox::Model::find_by_user(&ctx.db, &user)
+            .await
+            .unwrap();
+        let welcome = messages
+            .iter()
+            .find(|message| message.template == "welcome")
+            .unwrap();
+        assert_eq!(welcome.subject.trim_end(), "Bienvenido, loco");
+        assert!(welcome.text.contains("/api/auth/verify/"));
+    })
+    .await;
+}
+
 #[rstest]
 #[case("login_with_valid_password", "12341234")]
 #[case("login_with_invalid_password", "invalid-password")]
@@ -148,7 +191,7 @@ async fn login_with_un_existing_email() {
 async fn can_login_without_verify() {
     configure_insta!();
 
-    request::<App, _, _>(|request, _ctx| async move {
+    request::<App, _, _>(|request, ctx| async move {
         let email = "can_login_without_verify@loco.com";
         let password = "12341234";
         let register_payload = serde_json::json!({
@@ -183,6 +226,9 @@ async fn can_login_without_verify() {
             200,
             "Login request should succeed"
         );
+        let user = users::Model::find_by_email(&ctx.db, email).await.unwrap();
+        assert!(user.email_verified_at.is_none());
+        assert!(user.last_login_at.is_some(), "The login should be recorded");
 
         with_settings!({
             filters => cleanup_user_model()
@@ -365,6 +411,11 @@ async fn can_auth_with_magic_link() {
 
         assert_eq!(magic_link_json.name, user.name);
         assert_eq!(magic_link_json.pid, user.pid.to_string());
+
+        let user = users::Model::find_by_email(&ctx.db, &user.email)
+            .await
+            .unwrap();
+        assert!(user.last_login_at.is_some(), "The login should be recorded");
     })
     .await;
 }
@@ -438,17 +489,16 @@ async fn can_resend_verification_email() {
             "Resend verification email should succeed"
         );
 
-        let deliveries = ctx.mailer.unwrap().deliveries();
+        let user = users::Model::find_by_email(&ctx.db, email)
+            .await
+            .expect("User should exist");
 
         assert_eq!(
-            deliveries.count, 2,
+            mail::deliveries(&ctx, &user).len(),
+            2,
             "Two emails should have been sent: welcome and re-verification"
         );
 
-        let user = users::Model::find_by_email(&ctx.db, email)
-            .await
-            .expect("User should exist");
-
         with_settings!({
             filters => cleanup_user_model()
         }, {
@@ -493,11 +543,307 @@ async fn cannot_resend_email_if_already_verified() {
             "Should return 200 even if already verified"
         );
 
-        let deliveries = ctx.mailer.unwrap().deliveries();
         assert_eq!(
-            deliveries.count, 1,
-            "Only the original welcome email should be sent"
+            mail::deliveries(&ctx, &user).len(),
+            2,
+            "Only the original welcome email and the verification notice should be sent"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn cannot_authenticate_when_suspended() {
+    request::<App, _, _>(|request, ctx| async move {
+        let password = "12341234";
+        let user = create_random_user_with_password(&ctx.db, password)
+            .await
+            .unwrap();
+        let token = auth_header(&ctx, &user);
+        let user = user
+            .into_active_model()
+            .suspend(&ctx.db, Some("Spamming other users".to_string()), None)
+            .await
+            .unwrap();
+
+        let response = request
+            .post("/api/auth/login")
+            .json(&serde_json::json!({ "email": user.email, "password": password }))
+            .await;
+        assert_eq!(response.status_code(), 403);
+        let body = response.json::<serde_json::Value>();
+        assert_eq!(body["error"], "account_suspended");
+        assert_eq!(body["description"], "Spamming other users");
+
+        let response = request
+            .get("/api/auth/current")
+            .add_header("Authorization", token.clone())
+            .await;
+        assert_eq!(
+            response.status_code(),
+            403,
+            "Existing tokens should stop working"
         );
+        for path in ["/api/profile", "/api/usage", "/api/account/activity"] {
+            let response = request
+                .get(path)
+                .add_header("Authorization", token.clone())
+                .await;
+            assert_eq!(response.status_code(), 403, "{path} should be blocked");
+            assert_eq!(
+                response.json::<serde_json::Value>()["error"],
+                "account_suspended"
+            );
+        }
+
+        user.into_active_model().unsuspend(&ctx.db).await.unwrap();
+        let response = request
+            .get("/api/auth/current")
+            .add_header("Authorization", token)
+            .await;
+        assert_eq!(response.status_code(), 200);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn cannot_use_api_key_when_suspended() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+        let api_key = format!("Bearer {}", user.api_key);
+
+        let response = request
+            .get("/api/profile")
+            .add_header("Authorization", api_key.clone())
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let response = request
+            .get("/api/profile")
+            .add_header("Authorization", "Bearer lo-unknown")
+            .await;
+        assert_eq!(response.status_code(), 401);
+
+        let user = user
+            .into_active_model()
+            .suspend(&ctx.db, Some("Spamming other users".to_string()), None)
+            .await
+            .unwrap();
+        let response = request
+            .get("/api/profile")
+            .add_header("Authorization", api_key.clone())
+            .await;
+        assert_eq!(response.status_code(), 403);
+        let body = response.json::<serde_json::Value>();
+        assert_eq!(body["error"], "account_suspended");
+        assert_eq!(body["description"], "Spamming other users");
+
+        user.into_active_model().unsuspend(&ctx.db).await.unwrap();
+        let response = request
+            .get("/api/profile")
+            .add_header("Authorization", api_key)
+            .await;
+        assert_eq!(response.status_code(), 200);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn notifies_login_from_new_device() {
+    request::<App, _, _>(|request, ctx| async move {
+        let password = "12341234";
+        let user = create_random_user_with_password(&ctx.db, password)
+            .await
+            .unwrap();
+        let payload = serde_json::json!({ "email": user.email, "password": password });
+
+        request
+            .post("/api/auth/login")
+            .add_header("User-Agent", "browser-a")
+            .json(&payload)
+            .await;
+        let deliveries = mail::deliveries(&ctx, &user);
+        assert_eq!(deliveries.len(), 1);
+        assert!(deliveries[0]
+            .text
+            .contains("signed in to from a new browser"));
+
+        request
+            .post("/api/auth/login")
+            .add_header("User-Agent", "browser-a")
+            .json(&payload)
+            .await;
+        assert_eq!(
+            mail::deliveries(&ctx, &user).len(),
+            1,
+            "Known browsers should not be reported"
+        );
+
+        let response = request
+            .patch("/api/preferences")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .json(&serde_json::json!({ "notifications": { "new_login": false } }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        request
+            .post("/api/auth/login")
+            .add_header("User-Agent", "browser-b")
+            .json(&payload)
+            .await;
+        assert_eq!(
+            mail::deliveries(&ctx, &user).len(),
+            1,
+            "Opted out users should not be notified"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn notifies_password_change() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db)
+            .await
+            .unwrap()
+            .into_active_model()
+            .set_preferences(
+                &ctx.db,
+                &Preferences {
+                    notifications: NotificationPreferences {
+                        new_login: false,
+                        email_verified: false,
+                    },
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        request
+            .post("/api/auth/forgot")
+            .json(&serde_json::json!({ "email": user.email }))
+            .await;
+        let user = users::Model::find_by_email(&ctx.db, &user.email)
+            .await
+            .unwrap();
+        request
+            .post("/api/auth/reset")
+            .json(&serde_json::json!({
+                "token": user.reset_token,
+                "password": "new-password",
+            }))
+            .await;
+
+        let deliveries = mail::deliveries(&ctx, &user);
+        assert_eq!(deliveries.len(), 2);
+        assert!(
+            deliveries[1]
+                .text
+                .contains("password of your account was changed"),
+            "Password change notices cannot be opted out of"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn welcome_email_link_verifies_account() {
+    request::<App, _, _>(|request, ctx| async move {
+        let email = format!("welcome-{}@loco.com", uuid::Uuid::new_v4());
+        let response = request
+            .post("/api/auth/register")
+            .json(&serde_json::json!({
+                "name": "loco",
+                "email": email,
+                "password": "12341234"
+            }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let user = users::Model::find_by_email(&ctx.db, &email).await.unwrap();
+        let welcome = mail::last_delivery(&ctx, &user);
+        assert_eq!(welcome.to, email);
+        assert_eq!(welcome.subject.trim_end(), "Welcome loco");
+
+        let link = mail::link(&welcome, "/api/auth/verify/");
+        let response = request.get(&mail::local(&link)).await;
+        assert_eq!(response.status_code(), 200, "The verify link should work");
+
+        let user = users::Model::find_by_email(&ctx.db, &email).await.unwrap();
+        assert!(user.email_verified_at.is_some());
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn forgot_email_link_resets_password() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+        request
+            .post("/api/auth/forgot")
+            .json(&serde_json::json!({ "email": user.email }))
+            .await;
+
+        let forgot = mail::last_delivery(&ctx, &user);
+        assert_eq!(forgot.to, user.email);
+        assert_eq!(forgot.subject.trim_end(), "Your reset password link");
+
+        // The link opens the reset page of the frontend, which posts the
+        // token to the API.
+        let link = mail::link(&forgot, "/forgot");
+        let response = request
+            .post("/api/auth/reset")
+            .json(&serde_json::json!({
+                "token": mail::query_param(&link, "token"),
+                "password": "new-password",
+            }))
+            .await;
+        assert_eq!(response.status_code(), 200, "The reset link should work");
+
+        let response = request
+            .post("/api/auth/login")
+            .json(&serde_json::json!({
+                "email": user.email,
+                "password": "new-password"
+            }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn magic_link_email_signs_in() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+        request
+            .post("/api/auth/magic-link")
+            .json(&serde_json::json!({ "email": user.email }))
+            .await;
+
+        let magic_link = mail::last_delivery(&ctx, &user);
+        assert_eq!(magic_link.to, user.email);
+        assert_eq!(magic_link.subject.trim_end(), "Magic link example");
+
+        // The link opens the magic link page of the frontend, which signs in
+        // with the token through the API.
+        let link = mail::link(&magic_link, "/magic-link");
+        let response = request
+            .get(&format!(
+                "/api/auth/magic-link/{}",
+                mail::query_param(&link, "token")
+            ))
+            .await;
+        assert_eq!(response.status_code(), 200, "The magic link should work");
+        assert_eq!(response.json::<LoginResponse>().pid, user.pid.to_string());
     })
     .await;
 }
@@ -0,0 +1,102 @@
+use std::io::Cursor;
+
+use axum_test::multipart::{MultipartForm, Part};
+use image::{ImageFormat, RgbImage};
+use loco_nuxt_template::{
+    app::App,
+    models::users,
+    views::{auth::CurrentResponse, profile::AvatarUrls},
+};
+use loco_rs::testing::prelude::*;
+use serial_test::parallel;
+
+use crate::prepare::users::{auth_header, create_random_user};
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = Cursor::new(Vec::new());
+    RgbImage::new(width, height)
+        .write_to(&mut bytes, ImageFormat::Png)
+        .unwrap();
+    bytes.into_inner()
+}
+
+fn avatar_form(bytes: Vec<u8>, mime_type: &str) -> MultipartForm {
+    MultipartForm::new().add_part(
+        "avatar",
+        Part::bytes(bytes)
+            .file_name("avatar.png")
+            .mime_type(mime_type),
+    )
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_upload_avatar() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .post("/api/avatars")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .multipart(avatar_form(png(300, 200), "image/png"))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let urls = response.json::<AvatarUrls>();
+
+        let thumbnail = request.get(&urls.medium).await;
+        assert_eq!(thumbnail.status_code(), 200);
+        assert_eq!(thumbnail.header("content-type"), "image/png");
+        let thumbnail = image::load_from_memory(thumbnail.as_bytes()).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (128, 128));
+
+        let current = request
+            .get("/api/auth/current")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await
+            .json::<CurrentResponse>();
+        assert_eq!(current.avatar.map(|avatar| avatar.large), Some(urls.large));
+
+        let response = request
+            .delete("/api/avatars")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let user = users::Model::find_by_pid(&ctx.db, &user.pid.to_string())
+            .await
+            .unwrap();
+        assert_eq!(user.avatar_key, None);
+        assert_eq!(request.get(&urls.medium).await.status_code(), 404);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn rejects_invalid_avatar() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .post("/api/avatars")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .multipart(avatar_form(png(16, 16), "application/pdf"))
+            .await;
+        assert_eq!(response.status_code(), 400, "Unsupported type");
+
+        let response = request
+            .post("/api/avatars")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .multipart(avatar_form(b"not an image".to_vec(), "image/png"))
+            .await;
+        assert_eq!(response.status_code(), 400, "Undecodable image");
+
+        let response = request
+            .post("/api/avatars")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .multipart(avatar_form(vec![0; 1_000_001], "image/png"))
+            .await;
+        assert_eq!(response.status_code(), 400, "Too large");
+    })
+    .await;
+}
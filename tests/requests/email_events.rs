@@ -0,0 +1,128 @@
+use loco_nuxt_template::{
+    app::App,
+    models::{email_outbox, email_suppressions},
+};
+use loco_rs::testing::prelude::*;
+use serde_json::json;
+use serial_test::parallel;
+
+use crate::prepare::{mail, users::create_random_user};
+
+const TOKEN: &str = "test-events-token";
+
+#[tokio::test]
+#[parallel]
+async fn rejects_events_without_token() {
+    request::<App, _, _>(|request, ctx| async move {
+        let payload = json!({ "type": "bounce", "email": "no-token@loco.com" });
+
+        let response = request.post("/api/email-events").json(&payload).await;
+        assert_eq!(response.status_code(), 401);
+
+        let response = request
+            .post("/api/email-events")
+            .add_query_param("token", "wrong")
+            .json(&payload)
+            .await;
+        assert_eq!(response.status_code(), 401);
+
+        assert!(
+            !email_suppressions::Model::is_suppressed(&ctx.db, "no-token@loco.com")
+                .await
+                .unwrap()
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn bounce_suppresses_auth_emails() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .post("/api/email-events")
+            .add_query_param("token", TOKEN)
+            .json(&json!({ "type": "bounce", "email": user.email, "permanent": false }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        assert!(
+            !email_suppressions::Model::is_suppressed(&ctx.db, &user.email)
+                .await
+                .unwrap(),
+            "Temporary bounces should not suppress"
+        );
+
+        let response = request
+            .post("/api/email-events")
+            .add_query_param("token", TOKEN)
+            .json(&json!({
+                "type": "bounce",
+                "email": user.email.to_uppercase(),
+                "detail": "550 mailbox unavailable",
+            }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let suppression = email_suppressions::Model::find_by_email(&ctx.db, &user.email)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(suppression.reason, email_suppressions::REASON_BOUNCE);
+        assert_eq!(suppression.source, "generic");
+        assert_eq!(
+            suppression.detail.as_deref(),
+            Some("550 mailbox unavailable")
+        );
+
+        let response = request
+            .post("/api/auth/forgot")
+            .json(&json!({ "email": user.email }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let messages = email_outbox::Model::find_by_user(&ctx.db, &user)
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].status, email_outbox::STATUS_SUPPRESSED);
+        assert!(
+            mail::deliveries(&ctx, &user).is_empty(),
+            "Nothing should be sent to a suppressed address"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_receive_sendgrid_events() {
+    request::<App, _, _>(|request, ctx| async move {
+        let response = request
+            .post("/api/email-events/sendgrid")
+            .add_query_param("token", TOKEN)
+            .json(&json!([
+                { "email": "sg-bounce@loco.com", "event": "bounce", "type": "bounce", "reason": "550 5.1.1" },
+                { "email": "sg-blocked@loco.com", "event": "bounce", "type": "blocked" },
+                { "email": "sg-spam@loco.com", "event": "spamreport" },
+                { "email": "sg-delivered@loco.com", "event": "delivered" },
+            ]))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let reason = |email: &'static str| {
+            let db = ctx.db.clone();
+            async move {
+                email_suppressions::Model::find_by_email(&db, email)
+                    .await
+                    .unwrap()
+                    .map(|suppression| suppression.reason)
+            }
+        };
+        assert_eq!(reason("sg-bounce@loco.com").await.as_deref(), Some("bounce"));
+        assert_eq!(reason("sg-blocked@loco.com").await, None);
+        assert_eq!(reason("sg-spam@loco.com").await.as_deref(), Some("complaint"));
+        assert_eq!(reason("sg-delivered@loco.com").await, None);
+    })
+    .await;
+}
@@ -0,0 +1,68 @@
+use loco_nuxt_template::{
+    app::App,
+    models::email_suppressions::{Model, REASON_COMPLAINT},
+    views::emails::EmailSuppressionListResponse,
+};
+use loco_rs::testing::prelude::*;
+use serial_test::parallel;
+
+use crate::prepare::users::{auth_header, create_admin_user, create_random_user};
+
+#[tokio::test]
+#[parallel]
+async fn cannot_list_suppressions_as_regular_user() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .get("/api/admin/email-suppressions")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await;
+        assert_eq!(response.status_code(), 403);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_list_and_lift_suppressions() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let user = create_random_user(&ctx.db).await.unwrap();
+        Model::suppress(&ctx.db, &user.email, REASON_COMPLAINT, "generic", None)
+            .await
+            .unwrap();
+
+        let list = request
+            .get("/api/admin/email-suppressions")
+            .add_query_param("email", &user.email)
+            .add_query_param("reason", REASON_COMPLAINT)
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await
+            .json::<EmailSuppressionListResponse>();
+        assert_eq!(list.total_items, 1);
+        let suppression = &list.suppressions[0];
+        assert_eq!(suppression.email, user.email.to_lowercase());
+        assert_eq!(suppression.reason, REASON_COMPLAINT);
+
+        let response = request
+            .delete(&format!(
+                "/api/admin/email-suppressions/{}",
+                suppression.pid
+            ))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        assert!(!Model::is_suppressed(&ctx.db, &user.email).await.unwrap());
+
+        let response = request
+            .delete(&format!(
+                "/api/admin/email-suppressions/{}",
+                suppression.pid
+            ))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 404);
+    })
+    .await;
+}
@@ -0,0 +1,224 @@
+use loco_nuxt_template::{
+    app::App,
+    mailers::auth::AuthMailer,
+    models::email_suppressions,
+    models::users,
+    views::emails::{EmailMessageListResponse, EmailMessageResponse},
+};
+use loco_rs::testing::prelude::*;
+use serial_test::parallel;
+
+use crate::prepare::{
+    mail,
+    users::{auth_header, create_admin_user, create_random_user},
+};
+
+#[tokio::test]
+#[parallel]
+async fn cannot_search_emails_as_regular_user() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .get("/api/admin/emails")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await;
+        assert_eq!(response.status_code(), 403);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_search_and_resend_emails() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .post("/api/auth/forgot")
+            .json(&serde_json::json!({ "email": user.email }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let list = request
+            .get("/api/admin/emails")
+            .add_query_param("recipient", &user.email)
+            .add_query_param("template", "forgot")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await
+            .json::<EmailMessageListResponse>();
+        assert_eq!(list.total_items, 1);
+        let message = &list.messages[0];
+        assert_eq!(message.recipient, user.email);
+        assert_eq!(message.status, "sent");
+        assert_eq!(message.attempts, 1);
+        assert!(!message.subject.is_empty());
+        assert!(message.sent_at.is_some());
+
+        let list = request
+            .get("/api/admin/emails")
+            .add_query_param("recipient", &user.email)
+            .add_query_param("status", "failed")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await
+            .json::<EmailMessageListResponse>();
+        assert_eq!(list.total_items, 0);
+
+        assert!(
+            message.redacted_at.is_some(),
+            "The reset token should not be kept once sent"
+        );
+
+        AuthMailer::send_password_changed(&ctx, &user)
+            .await
+            .unwrap();
+        let list = request
+            .get("/api/admin/emails")
+            .add_query_param("recipient", &user.email)
+            .add_query_param("template", "password_changed")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await
+            .json::<EmailMessageListResponse>();
+        let message = &list.messages[0];
+        assert_eq!(message.redacted_at, None);
+
+        let response = request
+            .post(&format!("/api/admin/emails/{}/resend", message.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let resent = response.json::<EmailMessageResponse>();
+        assert_ne!(resent.pid, message.pid);
+        assert_eq!(resent.subject, message.subject);
+        assert_eq!(resent.status, "sent");
+
+        assert_eq!(mail::deliveries(&ctx, &user).len(), 3);
+
+        let list = request
+            .get("/api/admin/emails")
+            .add_query_param("recipient", &user.email)
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await
+            .json::<EmailMessageListResponse>();
+        assert_eq!(list.total_items, 3, "The original messages should be kept");
+
+        let response = request
+            .post(&format!(
+                "/api/admin/emails/{}/resend",
+                uuid::Uuid::new_v4()
+            ))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 404);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn resends_reset_email_with_new_token() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .post("/api/auth/forgot")
+            .json(&serde_json::json!({ "email": user.email }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let old_token = users::Model::find_by_email(&ctx.db, &user.email)
+            .await
+            .unwrap()
+            .reset_token
+            .unwrap();
+
+        let list = request
+            .get("/api/admin/emails")
+            .add_query_param("recipient", &user.email)
+            .add_query_param("template", "forgot")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await
+            .json::<EmailMessageListResponse>();
+        let message = &list.messages[0];
+        assert!(message.redacted_at.is_some());
+
+        let response = request
+            .post(&format!("/api/admin/emails/{}/resend", message.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let resent = response.json::<EmailMessageResponse>();
+        assert_ne!(resent.pid, message.pid);
+        assert_eq!(resent.template, "forgot");
+        assert_eq!(resent.status, "sent");
+
+        let new_token = users::Model::find_by_email(&ctx.db, &user.email)
+            .await
+            .unwrap()
+            .reset_token
+            .unwrap();
+        assert_ne!(new_token, old_token, "The reset token should be rotated");
+
+        let forgot = mail::last_delivery(&ctx, &user);
+        let link = mail::link(&forgot, "/forgot");
+        assert_eq!(mail::query_param(&link, "token"), new_token);
+
+        let response = request
+            .post("/api/auth/reset")
+            .json(&serde_json::json!({
+                "token": new_token,
+                "password": "new-password",
+            }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn resend_to_suppressed_address_is_not_sent() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        AuthMailer::send_password_changed(&ctx, &user)
+            .await
+            .unwrap();
+        let list = request
+            .get("/api/admin/emails")
+            .add_query_param("recipient", &user.email)
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await
+            .json::<EmailMessageListResponse>();
+        let message = &list.messages[0];
+        assert_eq!(message.status, "sent");
+
+        email_suppressions::Model::suppress(
+            &ctx.db,
+            &user.email,
+            email_suppressions::REASON_BOUNCE,
+            "generic",
+            None,
+        )
+        .await
+        .unwrap();
+
+        let response = request
+            .post(&format!("/api/admin/emails/{}/resend", message.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let resent = response.json::<EmailMessageResponse>();
+        assert_eq!(resent.status, "suppressed");
+        assert_eq!(resent.sent_at, None);
+
+        assert_eq!(
+            mail::deliveries(&ctx, &user).len(),
+            1,
+            "The copy should not be sent to a suppressed address"
+        );
+    })
+    .await;
+}
@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use loco_nuxt_template::{
+    app::App,
+    common::events::{self, EventHub},
+    models::users,
+};
+use loco_rs::testing::prelude::*;
+use sea_orm::IntoActiveModel;
+use serial_test::parallel;
+
+use crate::prepare::users::{auth_header, create_random_user};
+
+#[tokio::test]
+#[parallel]
+async fn streams_user_events_until_token_expires() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+        let other = create_random_user(&ctx.db).await.unwrap();
+        let pid = user.pid.to_string();
+        let hub = EventHub::from_context(&ctx);
+
+        let missed = hub.publish(&pid, "seen", serde_json::json!({ "n": 1 }));
+        hub.publish(&pid, "replayed", serde_json::json!({ "n": 2 }));
+        hub.publish(
+            &other.pid.to_string(),
+            "someone_else",
+            serde_json::json!({}),
+        );
+
+        let live = hub.clone();
+        let live_pid = pid.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            live.publish(&live_pid, "live", serde_json::json!({ "n": 3 }));
+        });
+
+        let jwt = ctx.config.get_jwt_config().unwrap();
+        let token = user.generate_jwt(&jwt.secret, 2).unwrap();
+        let response = request
+            .get("/api/events")
+            .add_header("Authorization", format!("Bearer {token}"))
+            .add_header("Last-Event-ID", missed.id.to_string())
+            .await;
+        assert_eq!(response.status_code(), 200);
+        assert_eq!(response.header("content-type"), "text/event-stream");
+
+        let body = response.text();
+        assert!(!body.contains("event: seen"), "{body}");
+        assert!(!body.contains("someone_else"), "{body}");
+        let replayed = body.find("event: replayed").expect(&body);
+        let live = body.find("event: live").expect(&body);
+        let expired = body.find("event: token_expired").expect(&body);
+        assert!(replayed < live && live < expired, "{body}");
+        assert!(body.contains(r#"data: {"n":3}"#), "{body}");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn publishes_email_verification() {
+    request::<App, _, _>(|request, ctx| async move {
+        let email = "events-verify@loco.com";
+        request
+            .post("/api/auth/register")
+            .json(&serde_json::json!({ "name": "loco", "email": email, "password": "12341234" }))
+            .await;
+        let user = users::Model::find_by_email(&ctx.db, email).await.unwrap();
+
+        let mut subscription = EventHub::from_context(&ctx).subscribe(&user.pid.to_string(), None);
+        let response = request
+            .get(&format!(
+                "/api/auth/verify/{}",
+                user.email_verification_token.unwrap()
+            ))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let event = tokio::time::timeout(Duration::from_secs(1), subscription.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.kind, events::EVENT_EMAIL_VERIFIED);
+        assert!(!event.data["email_verified_at"].is_null());
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn cannot_stream_without_token() {
+    request::<App, _, _>(|request, _ctx| async move {
+        let response = request.get("/api/events").await;
+        assert_eq!(response.status_code(), 401);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn cannot_stream_for_inactive_account() {
+    request::<App, _, _>(|request, ctx| async move {
+        let suspended = create_random_user(&ctx.db).await.unwrap();
+        let token = auth_header(&ctx, &suspended);
+        suspended
+            .into_active_model()
+            .suspend(&ctx.db, None, None)
+            .await
+            .unwrap();
+        let response = request
+            .get("/api/events")
+            .add_header("Authorization", token)
+            .await;
+        assert_eq!(response.status_code(), 403);
+        assert_eq!(
+            response.json::<serde_json::Value>()["error"],
+            "account_suspended"
+        );
+
+        let disabled = create_random_user(&ctx.db).await.unwrap();
+        let token = auth_header(&ctx, &disabled);
+        disabled.into_active_model().disable(&ctx.db).await.unwrap();
+        let response = request
+            .get("/api/events")
+            .add_header("Authorization", token)
+            .await;
+        assert_eq!(response.status_code(), 401);
+
+        let deleting = create_random_user(&ctx.db).await.unwrap();
+        let token = auth_header(&ctx, &deleting);
+        deleting
+            .into_active_model()
+            .request_deletion(&ctx.db)
+            .await
+            .unwrap();
+        let response = request
+            .get("/api/events")
+            .add_header("Authorization", token)
+            .await;
+        assert_eq!(response.status_code(), 401);
+    })
+    .await;
+}
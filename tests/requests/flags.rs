@@ -0,0 +1,148 @@
+use loco_nuxt_template::{
+    app::App,
+    views::flags::{FeatureFlagResponse, FlagsResponse},
+};
+use loco_rs::testing::prelude::*;
+use serial_test::parallel;
+
+use crate::prepare::users::{auth_header, create_admin_user, create_random_user};
+
+#[tokio::test]
+#[parallel]
+async fn can_get_evaluated_flags() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .get("/api/flags")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let flags = response.json::<FlagsResponse>().flags;
+        assert_eq!(flags.get("config_enabled"), Some(&true));
+        assert_eq!(flags.get("config_disabled"), Some(&false));
+
+        let response = request.get("/api/flags").await;
+        assert_eq!(response.status_code(), 401);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn admin_can_toggle_flags() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let user = create_random_user(&ctx.db).await.unwrap();
+        let other = create_random_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .patch("/api/admin/flags/request_beta")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .json(&serde_json::json!({ "user_pids": [user.pid.to_string()] }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let flag = response.json::<FeatureFlagResponse>();
+        assert!(!flag.enabled);
+        assert!(flag.overridden);
+
+        let flags = request
+            .get("/api/flags")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await
+            .json::<FlagsResponse>()
+            .flags;
+        assert_eq!(flags.get("request_beta"), Some(&true));
+        let flags = request
+            .get("/api/flags")
+            .add_header("Authorization", auth_header(&ctx, &other))
+            .await
+            .json::<FlagsResponse>()
+            .flags;
+        assert_eq!(flags.get("request_beta"), Some(&false));
+
+        let response = request
+            .patch("/api/admin/flags/config_disabled")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .json(&serde_json::json!({ "enabled": true }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let list = request
+            .get("/api/admin/flags")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await
+            .json::<Vec<FeatureFlagResponse>>();
+        let flag = list
+            .iter()
+            .find(|flag| flag.key == "config_disabled")
+            .unwrap();
+        assert!(flag.enabled);
+        assert!(flag.overridden);
+        let flag = list.iter().find(|flag| flag.key == "request_beta").unwrap();
+        assert_eq!(flag.user_pids, vec![user.pid.to_string()]);
+
+        let response = request
+            .delete("/api/admin/flags/config_disabled")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let flags = request
+            .get("/api/flags")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await
+            .json::<FlagsResponse>()
+            .flags;
+        assert_eq!(
+            flags.get("config_disabled"),
+            Some(&false),
+            "Resetting should fall back to the configured default"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn rejects_invalid_flag_updates() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .patch("/api/admin/flags/request_invalid")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .json(&serde_json::json!({ "percentage": 150 }))
+            .await;
+        assert_eq!(response.status_code(), 400);
+
+        let response = request
+            .patch("/api/admin/flags/request_invalid")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .json(&serde_json::json!({ "user_pids": ["not-a-pid"] }))
+            .await;
+        assert_eq!(response.status_code(), 400);
+
+        let response = request
+            .patch("/api/admin/flags/Not%20A%20Key")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .json(&serde_json::json!({ "enabled": true }))
+            .await;
+        assert_eq!(response.status_code(), 400);
+
+        let response = request
+            .patch("/api/admin/flags/request_invalid")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .json(&serde_json::json!({ "enabled": true }))
+            .await;
+        assert_eq!(response.status_code(), 403);
+
+        let response = request
+            .delete("/api/admin/flags/request_unknown")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 404);
+    })
+    .await;
+}
@@ -0,0 +1,82 @@
+use include_dir::{include_dir, Dir};
+use loco_nuxt_template::{app::App, mailers};
+use loco_rs::testing::prelude::*;
+use serde_json::json;
+use serial_test::parallel;
+
+static BROKEN: Dir<'_> = include_dir!("tests/fixtures/mailers/broken");
+
+#[tokio::test]
+#[parallel]
+async fn can_list_templates() {
+    request::<App, _, _>(|request, _ctx| async move {
+        let response = request.get("/_dev/mailers").await;
+        assert_eq!(response.status_code(), 200);
+        for template in ["welcome", "forgot", "magic_link", "email_verified"] {
+            assert!(response
+                .text()
+                .contains(&format!("/_dev/mailers/{template}/html")));
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn can_preview_every_template() {
+    request::<App, _, _>(|request, _ctx| async move {
+        for template in mailers::auth::AuthEmail::TEMPLATES {
+            for path in ["", "/html", "/text"] {
+                let response = request
+                    .get(&format!("/_dev/mailers/{template}{path}"))
+                    .await;
+                assert_eq!(
+                    response.status_code(),
+                    200,
+                    "{template}{path}: {}",
+                    response.text()
+                );
+            }
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn shows_subject_html_and_text_side_by_side() {
+    request::<App, _, _>(|request, _ctx| async move {
+        let page = request.get("/_dev/mailers/magic_link").await.text();
+        assert!(page.contains("<strong>Subject:</strong>"));
+        assert!(page.contains("<iframe srcdoc="));
+        assert!(page.contains("sample-magic-link-token"));
+
+        let text = request.get("/_dev/mailers/forgot/text").await.text();
+        assert!(text.contains("sample-reset-token"));
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn unknown_template_is_not_found() {
+    request::<App, _, _>(|request, _ctx| async move {
+        let response = request.get("/_dev/mailers/nope").await;
+        assert_eq!(response.status_code(), 404);
+    })
+    .await;
+}
+
+#[test]
+fn undefined_locals_are_reported() {
+    let err = mailers::render(
+        &BROKEN,
+        "from@example.com",
+        "to@example.com",
+        &json!({ "name": "Preview" }),
+    )
+    .unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("text.t"), "{message}");
+    assert!(message.contains("missingCode"), "{message}");
+}
@@ -1 +1,15 @@
+mod account;
+mod admin;
 mod auth;
+mod avatars;
+mod email_events;
+mod email_suppressions;
+mod emails;
+mod events;
+mod flags;
+mod mailer_preview;
+mod notifications;
+mod preferences;
+mod profile;
+mod usage;
+mod webhooks;
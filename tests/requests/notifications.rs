@@ -0,0 +1,106 @@
+use loco_nuxt_template::{
+    app::App,
+    models::notifications,
+    views::notifications::{NotificationListResponse, NotificationResponse, UnreadCountResponse},
+};
+use loco_rs::testing::prelude::*;
+use serial_test::parallel;
+
+use crate::prepare::users::{auth_header, create_random_user};
+
+#[tokio::test]
+#[parallel]
+async fn can_list_and_read_notifications() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+        for index in 0..3 {
+            notifications::Model::notify(
+                &ctx.db,
+                &user,
+                "welcome",
+                serde_json::json!({ "index": index }),
+            )
+            .await
+            .unwrap();
+        }
+
+        let response = request
+            .get("/api/notifications")
+            .add_query_param("page_size", "2")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let list = response.json::<NotificationListResponse>();
+        assert_eq!(list.total_items, 3);
+        assert_eq!(list.total_pages, 2);
+        assert_eq!(list.notifications[0].payload["index"], 2, "Newest first");
+        assert_eq!(list.notifications[0].kind, "welcome");
+
+        let response = request
+            .post(&format!(
+                "/api/notifications/{}/read",
+                list.notifications[0].pid
+            ))
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        assert!(response.json::<NotificationResponse>().read_at.is_some());
+
+        let count = request
+            .get("/api/notifications/unread-count")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await
+            .json::<UnreadCountResponse>();
+        assert_eq!(count.unread, 2);
+
+        let list = request
+            .get("/api/notifications")
+            .add_query_param("unread", "true")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await
+            .json::<NotificationListResponse>();
+        assert_eq!(list.total_items, 2);
+
+        let response = request
+            .post("/api/notifications/read-all")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let count = request
+            .get("/api/notifications/unread-count")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await
+            .json::<UnreadCountResponse>();
+        assert_eq!(count.unread, 0);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn cannot_read_notifications_of_other_users() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+        let other = create_random_user(&ctx.db).await.unwrap();
+        let notification =
+            notifications::Model::notify(&ctx.db, &other, "welcome", serde_json::json!({}))
+                .await
+                .unwrap();
+
+        let response = request
+            .post(&format!("/api/notifications/{}/read", notification.pid))
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await;
+        assert_eq!(response.status_code(), 404);
+
+        let response = request
+            .post("/api/notifications/not-a-pid/read")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await;
+        assert_eq!(response.status_code(), 404);
+
+        let response = request.get("/api/notifications").await;
+        assert_eq!(response.status_code(), 401);
+    })
+    .await;
+}
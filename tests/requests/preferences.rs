@@ -0,0 +1,138 @@
+use loco_nuxt_template::{
+    app::App,
+    models::preferences::{Preferences, Theme},
+};
+use loco_rs::testing::prelude::*;
+use sea_orm::{ActiveModelTrait, ActiveValue, IntoActiveModel};
+use serial_test::parallel;
+
+use crate::prepare::users::{auth_header, create_random_user};
+
+#[tokio::test]
+#[parallel]
+async fn can_get_and_merge_preferences() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let preferences = request
+            .get("/api/preferences")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await
+            .json::<Preferences>();
+        assert_eq!(preferences, Preferences::default());
+        assert!(preferences.notifications.new_login);
+
+        let response = request
+            .patch("/api/preferences")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .json(&serde_json::json!({
+                "theme": "dark",
+                "locale": "fr",
+                "notifications": { "new_login": false },
+            }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let preferences = response.json::<Preferences>();
+        assert_eq!(preferences.theme, Theme::Dark);
+        assert_eq!(preferences.locale.as_deref(), Some("fr"));
+        assert!(!preferences.notifications.new_login);
+        assert!(
+            preferences.notifications.email_verified,
+            "Keys left out of a nested object should be kept"
+        );
+
+        let preferences = request
+            .patch("/api/preferences")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .json(&serde_json::json!({ "locale": null, "notifications": { "email_verified": false } }))
+            .await
+            .json::<Preferences>();
+        assert_eq!(preferences.theme, Theme::Dark);
+        assert_eq!(preferences.locale, None, "Null should reset to the default");
+        assert!(!preferences.notifications.new_login);
+        assert!(!preferences.notifications.email_verified);
+
+        let profile = request
+            .get("/api/profile")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await
+            .json::<serde_json::Value>();
+        assert_eq!(profile["locale"], serde_json::Value::Null);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn rejects_invalid_preferences() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        for patch in [
+            serde_json::json!({ "theme": "purple" }),
+            serde_json::json!({ "colour": "red" }),
+            serde_json::json!({ "notifications": { "new_login": "no" } }),
+            serde_json::json!({ "notifications": { "sms": false } }),
+            serde_json::json!(["dark"]),
+        ] {
+            let response = request
+                .patch("/api/preferences")
+                .add_header("Authorization", auth_header(&ctx, &user))
+                .json(&patch)
+                .await;
+            assert_eq!(response.status_code(), 400, "{patch} should be rejected");
+        }
+
+        let response = request
+            .patch("/api/preferences")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .json(&serde_json::json!({ "locale": "english" }))
+            .await;
+        assert_eq!(response.status_code(), 400);
+        let errors = response.json::<serde_json::Value>()["errors"].clone();
+        assert!(errors.get("locale").is_some());
+
+        let preferences = request
+            .get("/api/preferences")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await
+            .json::<Preferences>();
+        assert_eq!(
+            preferences,
+            Preferences::default(),
+            "Nothing should be saved"
+        );
+
+        let response = request.get("/api/preferences").await;
+        assert_eq!(response.status_code(), 401);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn reads_stored_preferences_with_unknown_keys() {
+    request::<App, _, _>(|request, ctx| async move {
+        let mut user = create_random_user(&ctx.db)
+            .await
+            .unwrap()
+            .into_active_model();
+        user.preferences = ActiveValue::set(Some(serde_json::json!({
+            "theme": "dark",
+            "density": "compact",
+            "notifications": { "new_login": false, "sms": true },
+        })));
+        let user = user.update(&ctx.db).await.unwrap();
+
+        let preferences = request
+            .get("/api/preferences")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await
+            .json::<Preferences>();
+        assert_eq!(preferences.theme, Theme::Dark);
+        assert!(!preferences.notifications.new_login);
+        assert!(preferences.notifications.email_verified);
+    })
+    .await;
+}
@@ -0,0 +1,78 @@
+use loco_nuxt_template::{app::App, views::profile::ProfileResponse};
+use loco_rs::testing::prelude::*;
+use serial_test::parallel;
+
+use crate::prepare::users::{auth_header, create_random_user};
+
+#[tokio::test]
+#[parallel]
+async fn can_get_and_update_profile() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let profile = request
+            .get("/api/profile")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await
+            .json::<ProfileResponse>();
+        assert_eq!(profile.pid, user.pid.to_string());
+        assert_eq!(profile.display_name, None);
+
+        let response = request
+            .patch("/api/profile")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .json(&serde_json::json!({
+                "display_name": "Loco",
+                "timezone": "Europe/Madrid",
+                "locale": "es-ES",
+                "bio": "Hello there",
+            }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+
+        let profile = response.json::<ProfileResponse>();
+        assert_eq!(profile.name, user.name);
+        assert_eq!(profile.display_name.as_deref(), Some("Loco"));
+        assert_eq!(profile.timezone.as_deref(), Some("Europe/Madrid"));
+        assert_eq!(profile.locale.as_deref(), Some("es-ES"));
+        assert_eq!(profile.bio.as_deref(), Some("Hello there"));
+
+        let profile = request
+            .patch("/api/profile")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .json(&serde_json::json!({ "bio": "" }))
+            .await
+            .json::<ProfileResponse>();
+        assert_eq!(profile.bio, None, "An empty string should clear the field");
+        assert_eq!(profile.timezone.as_deref(), Some("Europe/Madrid"));
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn rejects_invalid_profile() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .patch("/api/profile")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .json(&serde_json::json!({
+                "name": "x",
+                "timezone": "Mars/Olympus_Mons",
+                "locale": "english",
+            }))
+            .await;
+        assert_eq!(response.status_code(), 400);
+
+        let errors = response.json::<serde_json::Value>()["errors"].clone();
+        assert!(errors.get("name").is_some());
+        assert!(errors.get("timezone").is_some());
+        assert!(errors.get("locale").is_some());
+
+        let response = request.get("/api/profile").await;
+        assert_eq!(response.status_code(), 401);
+    })
+    .await;
+}
@@ -0,0 +1,218 @@
+use std::io::Cursor;
+
+use axum_test::multipart::{MultipartForm, Part};
+use image::{ImageFormat, RgbImage};
+use loco_nuxt_template::{
+    app::App,
+    common::plans::Quota,
+    views::{admin::AdminUserResponse, usage::UsageResponse},
+};
+use loco_rs::testing::prelude::*;
+use serial_test::parallel;
+
+use crate::prepare::users::{auth_header, create_admin_user, create_random_user};
+
+#[tokio::test]
+#[parallel]
+async fn can_get_usage_of_default_plan() {
+    request::<App, _, _>(|request, ctx| async move {
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        for _ in 0..3 {
+            let response = request
+                .get("/api/profile")
+                .add_header("Authorization", auth_header(&ctx, &user))
+                .await;
+            assert_eq!(response.status_code(), 200);
+        }
+
+        let usage = request
+            .get("/api/usage")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await
+            .json::<UsageResponse>();
+        assert_eq!(usage.plan, "free");
+        assert_eq!(usage.plan_name, "Free");
+
+        let quota = |quota| usage.quotas.iter().find(|q| q.quota == quota).unwrap();
+        assert_eq!(usage.quotas.len(), 2);
+        assert_eq!(quota(Quota::RequestsPerDay).used, 3);
+        assert_eq!(quota(Quota::RequestsPerDay).limit, None);
+        assert!(quota(Quota::RequestsPerDay).resets_at.is_some());
+        assert_eq!(quota(Quota::StorageBytes).used, 0);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn rejects_requests_over_daily_quota() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .put(&format!("/api/admin/users/{}/plan", user.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .json(&serde_json::json!({ "plan": "tiny" }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        assert_eq!(
+            response.json::<AdminUserResponse>().plan.as_deref(),
+            Some("tiny")
+        );
+
+        for _ in 0..2 {
+            let response = request
+                .get("/api/preferences")
+                .add_header("Authorization", auth_header(&ctx, &user))
+                .await;
+            assert_eq!(response.status_code(), 200);
+        }
+
+        let response = request
+            .get("/api/notifications")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await;
+        assert_eq!(response.status_code(), 429);
+        let body = response.json::<serde_json::Value>();
+        assert_eq!(body["error"], "quota_exceeded");
+        assert_eq!(body["errors"]["quota"], "requests_per_day");
+        assert_eq!(body["errors"]["limit"], 2);
+        assert_eq!(body["errors"]["used"], 2);
+
+        let usage = request
+            .get("/api/usage")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await;
+        assert_eq!(
+            usage.status_code(),
+            200,
+            "Usage should stay readable over quota"
+        );
+        assert_eq!(usage.json::<UsageResponse>().plan_name, "Tiny");
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn meters_every_authenticated_route() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let user = create_random_user(&ctx.db).await.unwrap();
+        let api_key = format!("Bearer {}", user.api_key);
+
+        for path in ["/api/account/activity", "/api/flags"] {
+            let response = request
+                .get(path)
+                .add_header("Authorization", auth_header(&ctx, &user))
+                .await;
+            assert_eq!(response.status_code(), 200, "{path}");
+        }
+        let response = request
+            .get("/api/profile")
+            .add_header("Authorization", api_key.clone())
+            .await;
+        assert_eq!(response.status_code(), 200);
+        for path in ["/api/notifications/unread-count", "/api/auth/current"] {
+            let response = request
+                .get(path)
+                .add_header("Authorization", auth_header(&ctx, &user))
+                .await;
+            assert_eq!(response.status_code(), 200, "{path} should not be metered");
+        }
+        request.get("/api/profile").await;
+
+        let usage = request
+            .get("/api/usage")
+            .add_header("Authorization", api_key.clone())
+            .await
+            .json::<UsageResponse>();
+        let requests = usage
+            .quotas
+            .iter()
+            .find(|q| q.quota == Quota::RequestsPerDay)
+            .unwrap();
+        assert_eq!(requests.used, 3);
+
+        request
+            .put(&format!("/api/admin/users/{}/plan", user.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .json(&serde_json::json!({ "plan": "tiny" }))
+            .await;
+        let response = request
+            .get("/api/profile")
+            .add_header("Authorization", api_key)
+            .await;
+        assert_eq!(
+            response.status_code(),
+            429,
+            "API keys should share the quota"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn rejects_uploads_over_storage_quota() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        request
+            .put(&format!("/api/admin/users/{}/plan", user.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .json(&serde_json::json!({ "plan": "tiny" }))
+            .await;
+
+        let mut png = Cursor::new(Vec::new());
+        RgbImage::new(64, 64)
+            .write_to(&mut png, ImageFormat::Png)
+            .unwrap();
+        let response = request
+            .post("/api/avatars")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .multipart(
+                MultipartForm::new().add_part(
+                    "avatar",
+                    Part::bytes(png.into_inner())
+                        .file_name("avatar.png")
+                        .mime_type("image/png"),
+                ),
+            )
+            .await;
+        assert_eq!(response.status_code(), 402);
+        assert_eq!(
+            response.json::<serde_json::Value>()["errors"]["quota"],
+            "storage_bytes"
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn cannot_assign_unknown_plan() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let response = request
+            .put(&format!("/api/admin/users/{}/plan", user.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .json(&serde_json::json!({ "plan": "platinum" }))
+            .await;
+        assert_eq!(response.status_code(), 400);
+
+        let response = request
+            .put(&format!("/api/admin/users/{}/plan", user.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .json(&serde_json::json!({ "plan": null }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        assert_eq!(response.json::<AdminUserResponse>().plan, None);
+    })
+    .await;
+}
@@ -0,0 +1,215 @@
+use loco_nuxt_template::{
+    app::App,
+    common::webhooks::{self, HEADER_SIGNATURE},
+    models::{webhook_deliveries, webhook_endpoints},
+    views::webhooks::{
+        WebhookDeliveryListResponse, WebhookDeliveryResponse, WebhookEndpointResponse,
+    },
+};
+use loco_rs::testing::prelude::*;
+use sea_orm::ModelTrait;
+use serial_test::parallel;
+
+use crate::prepare::{
+    users::{auth_header, create_admin_user, create_random_user},
+    webhooks::{retry, unreachable_url, StandIn},
+};
+
+#[tokio::test]
+#[parallel]
+async fn delivers_signed_webhooks_with_retries() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let stand_in = StandIn::start().await;
+
+        let response = request
+            .post("/api/admin/webhooks")
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .json(&serde_json::json!({
+                "url": stand_in.url,
+                "event_types": [webhooks::EVENT_USER_REGISTERED],
+            }))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let endpoint = response.json::<WebhookEndpointResponse>();
+        let secret = endpoint
+            .secret
+            .clone()
+            .expect("secret is shown on creation");
+
+        let email = "webhook-register@loco.com";
+        request
+            .post("/api/auth/register")
+            .json(&serde_json::json!({ "name": "loco", "email": email, "password": "12341234" }))
+            .await;
+
+        assert_eq!(stand_in.received_with(email).len(), 1);
+        let log = request
+            .get(&format!("/api/admin/webhooks/{}/deliveries", endpoint.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await
+            .json::<WebhookDeliveryListResponse>();
+        let delivery = log
+            .deliveries
+            .iter()
+            .find(|delivery| delivery.payload["data"]["user"]["email"] == email)
+            .unwrap();
+        assert_eq!(delivery.status, webhook_deliveries::STATUS_PENDING);
+        assert!(
+            delivery.next_attempt_at.is_some(),
+            "The failed first attempt should be scheduled again"
+        );
+        let delivery = webhook_deliveries::Model::find_by_pid(&ctx.db, &delivery.pid)
+            .await
+            .unwrap();
+        retry(&ctx, &delivery).await;
+
+        let received = stand_in.received_with(email);
+        assert_eq!(
+            received.len(),
+            2,
+            "The failed first attempt should be retried"
+        );
+        let signature = received[1].headers[HEADER_SIGNATURE].to_str().unwrap();
+        assert!(webhooks::verify_signature(
+            &secret,
+            signature,
+            &received[1].body
+        ));
+        assert!(!webhooks::verify_signature(
+            "another secret",
+            signature,
+            &received[1].body
+        ));
+        let payload: serde_json::Value = serde_json::from_str(&received[1].body).unwrap();
+        assert_eq!(payload["event"], webhooks::EVENT_USER_REGISTERED);
+        assert_eq!(payload["data"]["user"]["email"], email);
+
+        let log = request
+            .get(&format!("/api/admin/webhooks/{}/deliveries", endpoint.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await
+            .json::<WebhookDeliveryListResponse>();
+        let delivery = log
+            .deliveries
+            .iter()
+            .find(|delivery| delivery.payload["data"]["user"]["email"] == email)
+            .unwrap();
+        assert_eq!(delivery.status, webhook_deliveries::STATUS_SUCCEEDED);
+        assert_eq!(delivery.attempts, 2);
+        assert_eq!(delivery.response_status, Some(200));
+
+        let response = request
+            .post(&format!(
+                "/api/admin/webhooks/deliveries/{}/redeliver",
+                delivery.pid
+            ))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let redelivery = response.json::<WebhookDeliveryResponse>();
+        assert_ne!(redelivery.pid, delivery.pid);
+        assert_eq!(redelivery.status, webhook_deliveries::STATUS_PENDING);
+        assert_eq!(
+            redelivery.payload["id"], delivery.payload["id"],
+            "Redeliveries keep the event id"
+        );
+        let redelivery = webhook_deliveries::Model::find_by_pid(&ctx.db, &redelivery.pid)
+            .await
+            .unwrap();
+        let redelivery = retry(&ctx, &redelivery).await;
+        assert_eq!(redelivery.status, webhook_deliveries::STATUS_SUCCEEDED);
+        assert_eq!(stand_in.received_with(email).len(), 4);
+
+        let response = request
+            .delete(&format!("/api/admin/webhooks/{}", endpoint.pid))
+            .add_header("Authorization", auth_header(&ctx, &admin))
+            .await;
+        assert_eq!(response.status_code(), 200);
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn marks_delivery_failed_after_max_attempts() {
+    request::<App, _, _>(|_request, ctx| async move {
+        let endpoint = webhook_endpoints::Model::create(
+            &ctx.db,
+            &unreachable_url().await,
+            &[webhooks::EVENT_USER_VERIFIED.to_string()],
+            webhook_endpoints::generate_secret(),
+        )
+        .await
+        .unwrap();
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        let delivery = webhook_deliveries::Model::create(
+            &ctx.db,
+            &endpoint,
+            webhooks::EVENT_USER_VERIFIED,
+            webhooks::user_data(&user),
+        )
+        .await
+        .unwrap();
+        webhooks::enqueue(&ctx, &delivery).await.unwrap();
+
+        let mut delivery =
+            webhook_deliveries::Model::find_by_pid(&ctx.db, &delivery.pid.to_string())
+                .await
+                .unwrap();
+        assert_eq!(delivery.attempts, 1);
+        while delivery.status == webhook_deliveries::STATUS_PENDING {
+            delivery = retry(&ctx, &delivery).await;
+        }
+        assert_eq!(delivery.status, webhook_deliveries::STATUS_FAILED);
+        assert_eq!(
+            delivery.attempts, 3,
+            "Should stop at the configured maximum"
+        );
+        assert_eq!(delivery.response_status, None);
+        assert!(delivery.error.is_some());
+        assert_eq!(delivery.next_attempt_at, None);
+        assert!(
+            webhooks::deliver(&ctx, delivery.pid)
+                .await
+                .unwrap()
+                .is_none(),
+            "Failed deliveries should not be attempted again"
+        );
+
+        endpoint.delete(&ctx.db).await.unwrap();
+    })
+    .await;
+}
+
+#[tokio::test]
+#[parallel]
+async fn rejects_invalid_webhook_endpoints() {
+    request::<App, _, _>(|request, ctx| async move {
+        let admin = create_admin_user(&ctx.db).await.unwrap();
+        let user = create_random_user(&ctx.db).await.unwrap();
+
+        for payload in [
+            serde_json::json!({ "url": "not a url", "event_types": [webhooks::EVENT_USER_VERIFIED] }),
+            serde_json::json!({ "url": "ftp://example.com/hook", "event_types": [webhooks::EVENT_USER_VERIFIED] }),
+            serde_json::json!({ "url": "file:///etc/passwd", "event_types": [webhooks::EVENT_USER_VERIFIED] }),
+            serde_json::json!({ "url": "https://example.com/hook", "event_types": [] }),
+            serde_json::json!({ "url": "https://example.com/hook", "event_types": ["user.deleted"] }),
+        ] {
+            let response = request
+                .post("/api/admin/webhooks")
+                .add_header("Authorization", auth_header(&ctx, &admin))
+                .json(&payload)
+                .await;
+            assert_eq!(response.status_code(), 400, "{payload} should be rejected");
+        }
+
+        let response = request
+            .get("/api/admin/webhooks")
+            .add_header("Authorization", auth_header(&ctx, &user))
+            .await;
+        assert_eq!(response.status_code(), 403);
+    })
+    .await;
+}
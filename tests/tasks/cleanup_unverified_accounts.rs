@@ -0,0 +1,132 @@
+use chrono::{offset::Local, Duration};
+use loco_nuxt_template::{
+    app::App,
+    models::{email_outbox, users},
+};
+use loco_rs::{app::AppContext, boot::run_task, task, testing::prelude::*};
+use sea_orm::{ActiveModelTrait, ActiveValue, DatabaseConnection, IntoActiveModel};
+use serial_test::serial;
+
+use crate::prepare::users::create_random_user;
+
+/// A user created `age` days ago who was last sent the verification email
+/// `sent` days after that
+async fn unverified_user(db: &DatabaseConnection, age: i64, sent: i64) -> users::Model {
+    let created_at = Local::now() - Duration::days(age);
+    let mut user = create_random_user(db).await.unwrap().into_active_model();
+    user.created_at = ActiveValue::set(created_at.into());
+    user.email_verification_sent_at =
+        ActiveValue::set(Some((created_at + Duration::days(sent)).into()));
+    user.email_verification_token = ActiveValue::set(Some("old-token".to_string()));
+    user.email_verified_at = ActiveValue::set(None);
+    user.update(db).await.unwrap()
+}
+
+async fn templates(db: &DatabaseConnection, user: &users::Model) -> Vec<String> {
+    email_outbox::Model::find_by_user(db, user)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|message| message.template)
+        .collect()
+}
+
+async fn run(ctx: &AppContext, vars: task::Vars) {
+    assert!(
+        run_task::<App>(ctx, Some(&"cleanup_unverified_accounts".to_string()), &vars)
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_can_run_cleanup_unverified_accounts() {
+    let boot = boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+
+    let fresh = unverified_user(db, 2, 0).await;
+    let remind = unverified_user(db, 5, 0).await;
+    let warn = unverified_user(db, 25, 3).await;
+    let delete = unverified_user(db, 31, 23).await;
+    let recently_warned = unverified_user(db, 31, 28).await;
+    let verified = unverified_user(db, 40, 0)
+        .await
+        .into_active_model()
+        .verified(db)
+        .await
+        .unwrap();
+
+    run(
+        &boot.app_context,
+        task::Vars::from_cli_args(vec![("dry_run".to_string(), "true".to_string())]),
+    )
+    .await;
+    for user in [&remind, &warn, &delete] {
+        let unchanged = users::Model::find_by_email(db, &user.email).await.unwrap();
+        assert_eq!(
+            unchanged.email_verification_token.as_deref(),
+            Some("old-token")
+        );
+        assert!(templates(db, user).await.is_empty());
+    }
+
+    run(&boot.app_context, task::Vars::default()).await;
+
+    for user in [&fresh, &recently_warned, &verified] {
+        let unchanged = users::Model::find_by_email(db, &user.email).await.unwrap();
+        assert_eq!(
+            unchanged.email_verification_sent_at,
+            user.email_verification_sent_at
+        );
+        assert!(templates(db, user).await.is_empty());
+    }
+
+    let reminded = users::Model::find_by_email(db, &remind.email)
+        .await
+        .unwrap();
+    assert_ne!(
+        reminded.email_verification_token.as_deref(),
+        Some("old-token")
+    );
+    assert_eq!(templates(db, &remind).await, vec!["welcome"]);
+
+    let warned = users::Model::find_by_email(db, &warn.email).await.unwrap();
+    assert_ne!(
+        warned.email_verification_token.as_deref(),
+        Some("old-token")
+    );
+    assert_eq!(templates(db, &warn).await, vec!["verification_warning"]);
+
+    assert!(users::Model::find_by_email(db, &delete.email)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn keeps_unverified_accounts_that_log_in() {
+    let boot = boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+
+    let due_for_deletion = unverified_user(db, 31, 23)
+        .await
+        .into_active_model()
+        .logged_in(db)
+        .await
+        .unwrap();
+    let due_for_warning = unverified_user(db, 25, 3)
+        .await
+        .into_active_model()
+        .logged_in(db)
+        .await
+        .unwrap();
+
+    run(&boot.app_context, task::Vars::default()).await;
+
+    for user in [&due_for_deletion, &due_for_warning] {
+        let kept = users::Model::find_by_email(db, &user.email).await.unwrap();
+        assert_eq!(kept.email_verification_token.as_deref(), Some("old-token"));
+        assert!(templates(db, user).await.is_empty());
+    }
+}
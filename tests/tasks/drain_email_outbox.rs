@@ -0,0 +1,40 @@
+use loco_nuxt_template::{
+    app::App,
+    mailers::auth::{AuthEmail, AuthMailer},
+    models::email_outbox,
+};
+use loco_rs::{boot::run_task, task, testing::prelude::*};
+use serial_test::serial;
+
+use crate::prepare::{mail, users::create_random_user};
+
+#[tokio::test]
+#[serial]
+async fn test_can_run_drain_email_outbox() {
+    let boot = boot_test::<App>().await.unwrap();
+    let ctx = &boot.app_context;
+
+    // Recorded without going through the mail worker, as if queuing failed.
+    let user = create_random_user(&ctx.db).await.unwrap();
+    let email = AuthMailer::render(ctx, &user, &AuthEmail::EmailVerified).unwrap();
+    let message = email_outbox::Model::create(&ctx.db, &user, "email_verified", &email)
+        .await
+        .unwrap();
+    assert_eq!(message.status, email_outbox::STATUS_QUEUED);
+
+    assert!(run_task::<App>(
+        ctx,
+        Some(&"drain_email_outbox".to_string()),
+        &task::Vars::default()
+    )
+    .await
+    .is_ok());
+
+    let message = email_outbox::Model::find_by_pid(&ctx.db, &message.pid.to_string())
+        .await
+        .unwrap();
+    assert_eq!(message.status, email_outbox::STATUS_SENT);
+    let deliveries = mail::deliveries(ctx, &user);
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].to, user.email);
+}
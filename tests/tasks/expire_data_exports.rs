@@ -0,0 +1,71 @@
+use axum::body::Bytes;
+use chrono::{offset::Local, Duration};
+use loco_nuxt_template::{
+    app::App,
+    common::data_export::{self, EXPORT_LINK_EXPIRATION_HOURS},
+    models::data_exports,
+};
+use loco_rs::{app::AppContext, boot::run_task, task, testing::prelude::*};
+use sea_orm::{ActiveModelTrait, ActiveValue, EntityTrait, IntoActiveModel};
+use serial_test::serial;
+
+use crate::prepare::users::create_random_user;
+
+/// An export created `age_hours` ago, with its archive in storage
+async fn stored_export(ctx: &AppContext, age_hours: i64) -> data_exports::Model {
+    let user = create_random_user(&ctx.db).await.unwrap();
+    let mut export = data_exports::Model::create(&ctx.db, &user)
+        .await
+        .unwrap()
+        .into_active_model();
+    export.created_at = ActiveValue::set((Local::now() - Duration::hours(age_hours)).into());
+    let export = export.update(&ctx.db).await.unwrap();
+    ctx.storage
+        .upload(
+            &data_export::storage_path(&export.pid.to_string()),
+            &Bytes::from_static(b"zip"),
+        )
+        .await
+        .unwrap();
+    export
+}
+
+async fn is_stored(ctx: &AppContext, export: &data_exports::Model) -> bool {
+    let stored: Result<Vec<u8>, _> = ctx
+        .storage
+        .download(&data_export::storage_path(&export.pid.to_string()))
+        .await;
+    stored.is_ok()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_can_run_expire_data_exports() {
+    let boot = boot_test::<App>().await.unwrap();
+    let ctx = &boot.app_context;
+
+    let fresh = stored_export(ctx, 1).await;
+    let expired = stored_export(ctx, EXPORT_LINK_EXPIRATION_HOURS + 1).await;
+
+    assert!(run_task::<App>(
+        ctx,
+        Some(&"expire_data_exports".to_string()),
+        &task::Vars::default()
+    )
+    .await
+    .is_ok());
+
+    assert!(is_stored(ctx, &fresh).await);
+    assert!(data_exports::Entity::find_by_id(fresh.id)
+        .one(&ctx.db)
+        .await
+        .unwrap()
+        .is_some());
+
+    assert!(!is_stored(ctx, &expired).await);
+    assert!(data_exports::Entity::find_by_id(expired.id)
+        .one(&ctx.db)
+        .await
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,36 @@
+use loco_nuxt_template::app::App;
+use loco_rs::{boot::run_task, task, testing::prelude::*};
+use serial_test::serial;
+
+#[tokio::test]
+#[serial]
+async fn test_can_run_preview_mailer() {
+    let boot = boot_test::<App>().await.unwrap();
+
+    for args in [
+        vec![],
+        vec![("template".to_string(), "welcome".to_string())],
+        vec![
+            ("template".to_string(), "magic_link".to_string()),
+            ("format".to_string(), "text".to_string()),
+        ],
+    ] {
+        let vars = task::Vars::from_cli_args(args);
+        assert!(run_task::<App>(
+            &boot.app_context,
+            Some(&"preview_mailer".to_string()),
+            &vars
+        )
+        .await
+        .is_ok());
+    }
+
+    let vars = task::Vars::from_cli_args(vec![("template".to_string(), "nope".to_string())]);
+    assert!(run_task::<App>(
+        &boot.app_context,
+        Some(&"preview_mailer".to_string()),
+        &vars
+    )
+    .await
+    .is_err());
+}
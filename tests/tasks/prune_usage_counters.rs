@@ -0,0 +1,63 @@
+use chrono::{Days, Utc};
+use loco_nuxt_template::{
+    app::App,
+    common::plans::{Quota, REQUEST_COUNTER_RETENTION_DAYS, STORAGE_AVATARS},
+    models::usage_counters,
+};
+use loco_rs::{boot::run_task, task, testing::prelude::*};
+use serial_test::serial;
+
+use crate::prepare::users::create_random_user;
+
+fn day(days_ago: u64) -> String {
+    Utc::now()
+        .checked_sub_days(Days::new(days_ago))
+        .unwrap()
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_can_run_prune_usage_counters() {
+    let boot = boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+    let user = create_random_user(db).await.unwrap();
+    let requests = Quota::RequestsPerDay.metric();
+    let storage = Quota::StorageBytes.metric();
+
+    let today = day(0);
+    let oldest_kept = day(REQUEST_COUNTER_RETENTION_DAYS - 1);
+    let expired = day(REQUEST_COUNTER_RETENTION_DAYS);
+    for bucket in [&today, &oldest_kept, &expired] {
+        usage_counters::Model::set(db, &user, requests, bucket, 5)
+            .await
+            .unwrap();
+    }
+    usage_counters::Model::set(db, &user, storage, STORAGE_AVATARS, 50)
+        .await
+        .unwrap();
+
+    assert!(run_task::<App>(
+        &boot.app_context,
+        Some(&"prune_usage_counters".to_string()),
+        &task::Vars::default()
+    )
+    .await
+    .is_ok());
+
+    let buckets: Vec<(String, String)> = usage_counters::Model::find_by_user(db, &user)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|counter| (counter.metric, counter.bucket))
+        .collect();
+    assert_eq!(
+        buckets,
+        vec![
+            (requests.to_string(), oldest_kept),
+            (requests.to_string(), today),
+            (storage.to_string(), STORAGE_AVATARS.to_string()),
+        ]
+    );
+}
@@ -0,0 +1,76 @@
+use axum::body::Bytes;
+use chrono::{offset::Local, Duration};
+use loco_nuxt_template::{app::App, common::avatar, models::users};
+use loco_rs::{boot::run_task, task, testing::prelude::*};
+use sea_orm::{ActiveModelTrait, ActiveValue, IntoActiveModel};
+use serial_test::serial;
+use uuid::Uuid;
+
+use crate::prepare::users::create_random_user;
+
+#[tokio::test]
+#[serial]
+async fn test_can_run_purge_deleted_accounts() {
+    let boot = boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+
+    let recent = create_random_user(db)
+        .await
+        .unwrap()
+        .into_active_model()
+        .request_deletion(db)
+        .await
+        .unwrap();
+
+    let mut expired = create_random_user(db)
+        .await
+        .unwrap()
+        .into_active_model()
+        .request_deletion(db)
+        .await
+        .unwrap()
+        .into_active_model();
+    expired.deletion_requested_at = ActiveValue::set(Some(
+        (Local::now() - Duration::days(users::DELETION_GRACE_PERIOD_DAYS + 1)).into(),
+    ));
+    let expired = expired
+        .update(db)
+        .await
+        .unwrap()
+        .into_active_model()
+        .set_avatar(db, Some(Uuid::new_v4().to_string()))
+        .await
+        .unwrap();
+    let avatar_key = expired.avatar_key.clone().unwrap();
+    for (size, _) in avatar::AVATAR_SIZES {
+        boot.app_context
+            .storage
+            .upload(
+                &avatar::storage_path(&avatar_key, size),
+                &Bytes::from_static(b"png"),
+            )
+            .await
+            .unwrap();
+    }
+
+    assert!(run_task::<App>(
+        &boot.app_context,
+        Some(&"purge_deleted_accounts".to_string()),
+        &task::Vars::default()
+    )
+    .await
+    .is_ok());
+
+    assert!(users::Model::find_by_email(db, &expired.email)
+        .await
+        .is_err());
+    assert!(users::Model::find_by_email(db, &recent.email).await.is_ok());
+    for (size, _) in avatar::AVATAR_SIZES {
+        let stored: Result<Vec<u8>, _> = boot
+            .app_context
+            .storage
+            .download(&avatar::storage_path(&avatar_key, size))
+            .await;
+        assert!(stored.is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use chrono::{offset::Local, Duration};
+use loco_nuxt_template::{
+    app::App,
+    models::{webhook_deliveries, webhook_endpoints},
+};
+use loco_rs::{app::AppContext, boot::run_task, task, testing::prelude::*};
+use sea_orm::{ActiveModelTrait, ActiveValue, IntoActiveModel};
+use serial_test::serial;
+
+use crate::prepare::webhooks::StandIn;
+
+/// A pending delivery whose next attempt is `next_attempt_in` from now and
+/// whose claim lasts `locked_for`
+async fn pending_delivery(
+    ctx: &AppContext,
+    endpoint: &webhook_endpoints::Model,
+    next_attempt_in: Duration,
+    locked_for: Option<Duration>,
+) -> webhook_deliveries::Model {
+    let mut delivery = webhook_deliveries::Model::create(
+        &ctx.db,
+        endpoint,
+        "user.registered",
+        serde_json::json!({ "test": true }),
+    )
+    .await
+    .unwrap()
+    .into_active_model();
+    delivery.attempts = ActiveValue::set(1);
+    delivery.next_attempt_at = ActiveValue::set(Some((Local::now() + next_attempt_in).into()));
+    delivery.locked_until =
+        ActiveValue::set(locked_for.map(|locked_for| (Local::now() + locked_for).into()));
+    delivery.update(&ctx.db).await.unwrap()
+}
+
+async fn reload(
+    ctx: &AppContext,
+    delivery: &webhook_deliveries::Model,
+) -> webhook_deliveries::Model {
+    webhook_deliveries::Model::find_by_pid(&ctx.db, &delivery.pid.to_string())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_can_run_retry_webhook_deliveries() {
+    let boot = boot_test::<App>().await.unwrap();
+    let ctx = &boot.app_context;
+
+    let stand_in = StandIn::start().await;
+    let endpoint = webhook_endpoints::Model::create(
+        &ctx.db,
+        &stand_in.url,
+        &["user.registered".to_string()],
+        webhook_endpoints::generate_secret(),
+    )
+    .await
+    .unwrap();
+
+    let due = pending_delivery(ctx, &endpoint, Duration::seconds(-1), None).await;
+    let later = pending_delivery(ctx, &endpoint, Duration::hours(1), None).await;
+    let claimed = pending_delivery(
+        ctx,
+        &endpoint,
+        Duration::seconds(-1),
+        Some(Duration::minutes(5)),
+    )
+    .await;
+
+    assert!(run_task::<App>(
+        ctx,
+        Some(&"retry_webhook_deliveries".to_string()),
+        &task::Vars::default()
+    )
+    .await
+    .is_ok());
+
+    let due = reload(ctx, &due).await;
+    assert_eq!(due.attempts, 2);
+    assert_eq!(due.status, webhook_deliveries::STATUS_PENDING);
+    assert!(due.next_attempt_at.is_some());
+    assert!(due.locked_until.is_none());
+
+    assert_eq!(reload(ctx, &later).await.attempts, 1);
+    assert_eq!(reload(ctx, &claimed).await.attempts, 1);
+}
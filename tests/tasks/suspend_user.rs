@@ -0,0 +1,41 @@
+use loco_nuxt_template::{app::App, models::users};
+use loco_rs::{boot::run_task, task, testing::prelude::*};
+use serial_test::serial;
+
+use crate::prepare::users::create_random_user;
+
+#[tokio::test]
+#[serial]
+async fn test_can_run_suspend_and_unsuspend_user() {
+    let boot = boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+
+    let user = create_random_user(db).await.unwrap();
+
+    let vars = task::Vars::from_cli_args(vec![
+        ("email".to_string(), user.email.clone()),
+        ("reason".to_string(), "abuse".to_string()),
+        ("until".to_string(), "2999-01-01T00:00:00Z".to_string()),
+    ]);
+    assert!(
+        run_task::<App>(&boot.app_context, Some(&"suspend_user".to_string()), &vars)
+            .await
+            .is_ok()
+    );
+
+    let user = users::Model::find_by_email(db, &user.email).await.unwrap();
+    assert!(user.is_suspended());
+    assert_eq!(user.suspension_reason.as_deref(), Some("abuse"));
+
+    let vars = task::Vars::from_cli_args(vec![("email".to_string(), user.email.clone())]);
+    assert!(run_task::<App>(
+        &boot.app_context,
+        Some(&"unsuspend_user".to_string()),
+        &vars
+    )
+    .await
+    .is_ok());
+
+    let user = users::Model::find_by_email(db, &user.email).await.unwrap();
+    assert!(!user.is_suspended());
+}
@@ -0,0 +1,191 @@
+use std::time::{Duration, Instant};
+
+use chrono::{offset::Local, Duration as ChronoDuration};
+
+use loco_nuxt_template::{
+    app::App,
+    common::settings::Settings,
+    mailers::{
+        auth::{AuthEmail, AuthMailer},
+        outbox,
+    },
+    models::email_outbox,
+    workers::mailer::{MailWorker, MailWorkerArgs},
+};
+use loco_rs::{
+    app::AppContext, bgworker::BackgroundWorker, config::SmtpMailer, mailer::EmailSender,
+    testing::prelude::*,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+};
+use serial_test::parallel;
+
+use crate::prepare::{mail, users::create_random_user};
+
+/// A context that sends with the `mailer` section instead of keeping emails
+/// in memory
+fn smtp_transport(ctx: &AppContext) -> AppContext {
+    let mut config = ctx.config.clone();
+    if let Some(settings) = config.settings.as_mut() {
+        settings["mail"]["transport"] = serde_json::json!({ "driver": "smtp" });
+    }
+    AppContext {
+        config,
+        ..ctx.clone()
+    }
+}
+
+/// A context whose mail server refuses every connection
+fn unreachable_mailer(ctx: &AppContext) -> AppContext {
+    let sender = EmailSender::smtp(&SmtpMailer {
+        enable: true,
+        host: "127.0.0.1".to_string(),
+        port: 1,
+        secure: false,
+        auth: None,
+        hello_name: None,
+    })
+    .unwrap();
+    AppContext {
+        mailer: Some(sender),
+        ..smtp_transport(ctx)
+    }
+}
+
+async fn messages_of(ctx: &AppContext, email: &str) -> Vec<email_outbox::Model> {
+    email_outbox::Entity::find()
+        .filter(email_outbox::email_outbox::Column::Recipient.eq(email))
+        .all(&ctx.db)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+#[parallel]
+async fn records_and_sends_auth_emails() -> anyhow::Result<()> {
+    let boot = boot_test::<App>().await?;
+    let ctx = &boot.app_context;
+
+    let user = create_random_user(&ctx.db)
+        .await?
+        .into_active_model()
+        .create_magic_link(&ctx.db)
+        .await?;
+    AuthMailer::send_magic_link(ctx, &user).await?;
+
+    // `ForegroundBlocking` in config/test.yaml sends before returning.
+    let deliveries = mail::deliveries(ctx, &user);
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].to, user.email);
+    assert!(deliveries[0]
+        .text
+        .contains(user.magic_link_token.as_deref().unwrap()));
+
+    let messages = messages_of(ctx, &user.email).await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].template, "magic_link");
+    assert_eq!(messages[0].status, email_outbox::STATUS_SENT);
+    assert_eq!(messages[0].attempts, 1);
+    assert!(messages[0].sent_at.is_some());
+    assert_eq!(
+        messages[0].provider_message_id.as_deref(),
+        Some(deliveries[0].id.as_str()),
+        "The delivery id should be recorded as the provider message id"
+    );
+
+    assert_eq!(messages[0].locked_until, None);
+    assert!(messages[0].redacted_at.is_some());
+    for body in [&messages[0].text, &messages[0].html] {
+        assert!(!body.contains(user.magic_link_token.as_deref().unwrap()));
+        assert!(body.contains(outbox::REDACTED));
+    }
+
+    assert!(
+        outbox::dispatch(ctx, messages[0].pid).await?.is_none(),
+        "Sent messages should not be dispatched again"
+    );
+    assert_eq!(mail::deliveries(ctx, &user).len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[parallel]
+async fn retries_with_backoff_then_records_failure() -> anyhow::Result<()> {
+    let boot = boot_test::<App>().await?;
+    let ctx = unreachable_mailer(&boot.app_context);
+    let settings = Settings::from_config(&ctx.config)?.mail;
+
+    let user = create_random_user(&ctx.db).await?;
+
+    let started = Instant::now();
+    AuthMailer::send_welcome(&ctx, &user).await?;
+    assert!(
+        started.elapsed() >= settings.backoff(1) + settings.backoff(2),
+        "Every failed attempt but the last should wait before retrying"
+    );
+
+    let messages = messages_of(&ctx, &user.email).await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].status, email_outbox::STATUS_FAILED);
+    assert_eq!(messages[0].attempts, 3);
+    assert!(messages[0].last_error.is_some());
+    assert_eq!(messages[0].sent_at, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[parallel]
+async fn claims_messages_until_the_lease_expires() -> anyhow::Result<()> {
+    let boot = boot_test::<App>().await?;
+    let ctx = &boot.app_context;
+
+    let user = create_random_user(&ctx.db).await?;
+    let email = AuthMailer::render(ctx, &user, &AuthEmail::EmailVerified)?;
+    let message = email_outbox::Model::create(&ctx.db, &user, "email_verified", &email).await?;
+
+    let claimed = email_outbox::Model::claim(&ctx.db, message.pid, Duration::from_secs(60))
+        .await?
+        .unwrap();
+    assert_eq!(claimed.status, email_outbox::STATUS_SENDING);
+    assert!(claimed.locked_until.is_some());
+    assert!(
+        email_outbox::Model::claim(&ctx.db, message.pid, Duration::from_secs(60))
+            .await?
+            .is_none(),
+        "A claimed message cannot be claimed twice"
+    );
+    assert!(outbox::dispatch(ctx, message.pid).await?.is_none());
+    assert!(mail::deliveries(ctx, &user).is_empty());
+
+    // The dispatcher died mid-send and its claim ran out.
+    let mut abandoned = claimed.into_active_model();
+    abandoned.locked_until =
+        ActiveValue::set(Some((Local::now() - ChronoDuration::seconds(1)).into()));
+    abandoned.update(&ctx.db).await?;
+
+    let sent = outbox::dispatch(ctx, message.pid).await?.unwrap();
+    assert_eq!(sent.status, email_outbox::STATUS_SENT);
+    assert_eq!(sent.locked_until, None);
+    assert_eq!(mail::deliveries(ctx, &user).len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[parallel]
+async fn ignores_unknown_messages() -> anyhow::Result<()> {
+    let boot = boot_test::<App>().await?;
+    let ctx = &smtp_transport(&boot.app_context);
+
+    MailWorker::build(ctx)
+        .perform(MailWorkerArgs {
+            outbox_pid: uuid::Uuid::new_v4().to_string(),
+        })
+        .await?;
+    assert_eq!(ctx.mailer.as_ref().unwrap().deliveries().count, 0);
+
+    Ok(())
+}
@@ -1 +1 @@
-
+mod mailer;